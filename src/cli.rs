@@ -1,3 +1,4 @@
+use crate::domain::review::ReviewEvent;
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
@@ -15,7 +16,13 @@ pub struct Cli {
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     /// Launch the TUI dashboard (default when no subcommand given)
-    Dashboard,
+    Dashboard {
+        /// Render in this terminal without attaching to tmux, read-only.
+        /// Watches the sessions of a dashboard already running, e.g. from a
+        /// second monitor or over SSH; any number can run at once.
+        #[arg(long, alias = "read-only")]
+        no_attach: bool,
+    },
 
     /// Initialize vibe in current repository
     Init,
@@ -25,13 +32,24 @@ pub enum Commands {
         /// Session name (used for branch + tmux window)
         name: String,
 
-        /// Branch name (defaults to feat/{name})
+        /// Branch name (defaults to feat/{name}). Use repo=branch to
+        /// override a single repo in multi-repo workspaces; repeatable.
         #[arg(short, long)]
-        branch: Option<String>,
+        branch: Vec<String>,
 
-        /// Base ref to create branch from (defaults to default branch)
+        /// Base ref to create branch from (defaults to default branch). Use
+        /// repo=ref to override a single repo in multi-repo workspaces; repeatable.
         #[arg(long)]
-        base: Option<String>,
+        base: Vec<String>,
+
+        /// Only check out these repos (multi-repo workspaces; defaults to all)
+        #[arg(long, value_delimiter = ',')]
+        repos: Option<Vec<String>>,
+
+        /// Fail and roll back if any repo worktree can't be created, instead
+        /// of creating the session without it (multi-repo workspaces)
+        #[arg(long)]
+        all_or_nothing: bool,
 
         /// Agent template to use
         #[arg(short, long)]
@@ -73,6 +91,12 @@ pub enum Commands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        /// Report active sessions that change the same files
+        #[arg(long)]
+        conflicts: bool,
+        /// With --conflicts, trial-merge overlapping branches to find real conflicts
+        #[arg(long, requires = "conflicts")]
+        trial_merge: bool,
     },
 
     /// List sessions, agents, or templates
@@ -93,6 +117,10 @@ pub enum Commands {
         /// Also delete the git branch
         #[arg(long)]
         delete_branch: bool,
+
+        /// Also delete the session's transcript and activity log
+        #[arg(long)]
+        purge: bool,
     },
 
     /// Attach to a session's tmux pane
@@ -102,13 +130,31 @@ pub enum Commands {
     },
 
     /// Spawn a PR review agent
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Review {
+        #[command(subcommand)]
+        action: Option<ReviewSubcommand>,
+
         /// PR number or URL
-        pr: String,
+        #[arg(required = true)]
+        pr: Option<String>,
 
         /// Run interactively
-        #[arg(long)]
+        #[arg(long, conflicts_with = "post")]
         interactive: bool,
+
+        /// Post the findings to the PR as a GitHub review with inline comments
+        #[arg(long)]
+        post: bool,
+
+        /// How to submit with --post: pending (default), comment,
+        /// request-changes or approve
+        #[arg(long, requires = "post")]
+        event: Option<ReviewEvent>,
+
+        /// Post without asking for confirmation
+        #[arg(short, long, requires = "post")]
+        yes: bool,
     },
 
     /// Manage shared plan files
@@ -121,7 +167,116 @@ pub enum Commands {
     Doctor,
 
     /// Re-scan parent directory for new/removed repos (multi-repo only)
-    RefreshRepos,
+    RefreshRepos {
+        /// Also add worktrees for new repos to active sessions and remove
+        /// worktrees of repos that are gone
+        #[arg(long)]
+        apply_to_sessions: bool,
+
+        /// Show what would change without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Rebase or merge session branches onto their base branch
+    Sync {
+        /// Session name
+        session: Option<String>,
+
+        /// Sync all active sessions
+        #[arg(long, conflicts_with = "session")]
+        all: bool,
+
+        /// Rebase onto the base branch (overrides sync_strategy)
+        #[arg(long, conflicts_with = "merge")]
+        rebase: bool,
+
+        /// Merge the base branch in (overrides sync_strategy)
+        #[arg(long)]
+        merge: bool,
+
+        /// Abort and leave the branch unchanged if conflicts occur
+        #[arg(long)]
+        abort_on_conflict: bool,
+    },
+
+    /// Open and track the PRs of a session (one per repo)
+    Pr {
+        #[command(subcommand)]
+        action: PrSubcommand,
+    },
+
+    /// Send a message to a session's Claude, as if typed in its window
+    Send {
+        /// Session name
+        session: String,
+        /// Message to send (read from stdin if omitted)
+        message: Option<String>,
+    },
+
+    /// Print a session's transcript
+    Log {
+        /// Session name
+        session: String,
+        /// Keep printing new output as it arrives
+        #[arg(short, long)]
+        follow: bool,
+    },
+
+    /// Search all session and agent transcripts
+    Search {
+        /// Text to find (case-insensitive)
+        query: String,
+    },
+
+    /// Record a pane's output as a transcript (run by `tmux pipe-pane`)
+    #[command(hide = true)]
+    RecordTranscript {
+        /// Transcript file to append to
+        file: PathBuf,
+    },
+
+    /// Record a Claude Code hook event (run by the hooks vibe installs)
+    #[command(hide = true)]
+    HookEvent {
+        /// Session events log to append to
+        events_file: PathBuf,
+    },
+
+    /// Work on failing CI checks
+    Ci {
+        #[command(subcommand)]
+        action: CiSubcommand,
+    },
+
+    /// Adjust an existing session
+    Session {
+        #[command(subcommand)]
+        action: SessionSubcommand,
+    },
+
+    /// Merge completed sessions into an integration branch, one at a time
+    Integrate {
+        /// Sessions to merge, in order
+        #[arg(required = true)]
+        sessions: Vec<String>,
+
+        /// Integration branch (created from --base if it doesn't exist)
+        #[arg(long)]
+        into: String,
+
+        /// Base ref for a new integration branch (defaults to default branch)
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Command to run after each merge (overrides integrate_check)
+        #[arg(long)]
+        check: Option<String>,
+
+        /// On failure, start a fixer agent in the integration worktree
+        #[arg(long)]
+        fix: bool,
+    },
 
     /// Clean up stale worktrees and archived sessions
     Cleanup {
@@ -132,6 +287,10 @@ pub enum Commands {
         /// Dry run
         #[arg(long)]
         dry_run: bool,
+
+        /// Also delete the sessions' transcripts and activity logs
+        #[arg(long)]
+        purge: bool,
     },
 
 }
@@ -151,6 +310,101 @@ pub enum ListSubcommand {
     Plans,
 }
 
+#[derive(Debug, Subcommand)]
+pub enum SessionSubcommand {
+    /// Check out another workspace repo in a multi-repo session
+    AddRepo {
+        /// Session name
+        session: String,
+        /// Repo name
+        repo: String,
+        /// Branch for this repo (defaults to [repos.<name>] config, then the session branch)
+        #[arg(short, long)]
+        branch: Option<String>,
+        /// Base ref for a new branch (defaults to [repos.<name>] config, then the repo default branch)
+        #[arg(long)]
+        base: Option<String>,
+    },
+    /// Remove a repo's worktree from a multi-repo session
+    RemoveRepo {
+        /// Session name
+        session: String,
+        /// Repo name
+        repo: String,
+        /// Remove even if the worktree has uncommitted changes
+        #[arg(long)]
+        force: bool,
+    },
+    /// Retry repo worktrees that failed at creation or are missing from disk
+    Repair {
+        /// Session name
+        session: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ReviewSubcommand {
+    /// Poll open PRs and review each new PR or new push headlessly
+    Watch {
+        /// Only PRs with this label
+        #[arg(long)]
+        label: Option<String>,
+        /// Only PRs by this author
+        #[arg(long)]
+        author: Option<String>,
+        /// Seconds between polls
+        #[arg(long, default_value_t = 300)]
+        interval: u64,
+        /// Poll once and exit
+        #[arg(long)]
+        once: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CiSubcommand {
+    /// Spawn an agent with the failed job logs to fix the checks
+    Fix {
+        /// Session name, or PR number or URL
+        target: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PrSubcommand {
+    /// Push the session's branches and open a PR per repo with commits,
+    /// cross-linked to each other
+    Open {
+        /// Session name
+        session: String,
+        /// PR title (defaults to the session description, then its name)
+        #[arg(long)]
+        title: Option<String>,
+        /// PR description
+        #[arg(long)]
+        body: Option<String>,
+        /// Open as draft PRs
+        #[arg(long)]
+        draft: bool,
+    },
+    /// Show review and check state of the session's PRs
+    Status {
+        /// Session name
+        session: String,
+    },
+    /// Have an implementer work through a PR's unresolved review threads
+    Address {
+        /// PR number or URL
+        pr: String,
+        /// Wait for the agent and reply to each thread with what was changed
+        #[arg(long)]
+        reply: bool,
+        /// Post replies without asking for confirmation
+        #[arg(short, long, requires = "reply")]
+        yes: bool,
+    },
+}
+
 #[derive(Debug, Subcommand)]
 pub enum PlanSubcommand {
     /// Create a new plan
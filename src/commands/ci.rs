@@ -0,0 +1,168 @@
+use crate::commands::{pr, spawn};
+use crate::config::MergedConfig;
+use crate::domain::ci;
+use crate::domain::pr::{CheckOutcome, CheckRun};
+use crate::error::VibeError;
+use crate::infra::{gh, state::StateManager};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Log lines included per failed run; enough for the failures, small enough
+/// to leave the agent room to work.
+const MAX_LOG_LINES: usize = 200;
+
+/// A PR whose failed checks are handed to the agent.
+struct FailingPr {
+    /// Repo name for multi-repo sessions.
+    repo: Option<String>,
+    worktree: PathBuf,
+    number: u64,
+}
+
+/// Spawn an implementer in a session to fix its PRs' failing checks, with
+/// excerpts of the failed job logs. `target` is a session name or a PR
+/// number/URL; a PR without a session gets one, as with `vibe pr address`.
+pub async fn fix(workspace_root: &Path, target: String, config: &MergedConfig) -> Result<(), VibeError> {
+    pr::require_gh()?;
+    let state = StateManager::new(workspace_root).load().await?;
+
+    let (session_name, prs) = if state.find_session_by_name(&target).is_some() {
+        let session = pr::find_session(&state, &target)?;
+        let prs: Vec<FailingPr> = pr::pr_targets(&state, &session)
+            .into_iter()
+            .filter_map(|t| {
+                let number = session.pr_for(t.repo.as_deref())?;
+                Some(FailingPr {
+                    repo: t.repo,
+                    worktree: t.worktree,
+                    number,
+                })
+            })
+            .collect();
+        if prs.is_empty() {
+            return Err(VibeError::User(format!(
+                "{target} has no PRs. Open them with `vibe pr open {target}`."
+            )));
+        }
+        (session.name, prs)
+    } else {
+        let number = gh::parse_pr_identifier(&target)
+            .map_err(|_| VibeError::SessionNotFound(target.clone()))?;
+        if state.workspace.is_multi_repo() {
+            return Err(VibeError::User(
+                "In a multi-repo workspace, pass the session name instead of a PR".into(),
+            ));
+        }
+        let pr_info = gh::get_pr_info(number, workspace_root).await?;
+        let session = pr::session_for_pr(workspace_root, number, &pr_info.head_ref_name, config).await?;
+        let prs = vec![FailingPr {
+            repo: None,
+            worktree: session.worktree_path.clone(),
+            number,
+        }];
+        (session.name, prs)
+    };
+
+    let mut sections = vec![];
+    let mut run_ids = BTreeSet::new();
+    for failing in &prs {
+        let status = gh::get_pr_status(failing.number, &failing.worktree).await?;
+        let failed: Vec<&CheckRun> = status
+            .status_check_rollup
+            .iter()
+            .filter(|check| check.outcome() == CheckOutcome::Failed)
+            .collect();
+        if failed.is_empty() {
+            continue;
+        }
+        println!(
+            "PR #{}: {} failed check(s)",
+            failing.number,
+            failed.len()
+        );
+        sections.push(failing_pr_section(failing, &failed, &mut run_ids).await);
+    }
+
+    if sections.is_empty() {
+        println!("No failed checks on {session_name}'s PRs.");
+        return Ok(());
+    }
+
+    let prompt = format!(
+        "CI is failing on the PRs of this session. Find the cause of each failure below and fix it. \
+         Reproduce the failing step locally where you can, run it again after the fix, and commit. \
+         Do not push.\n\n{}",
+        sections.join("\n")
+    );
+
+    let agent_id = spawn::execute(
+        workspace_root,
+        prompt,
+        Some(session_name.clone()),
+        Some("implementer".into()),
+        None,
+        false,
+        config,
+    )
+    .await?;
+
+    // Record the runs so the agent can be matched to the failures it was given
+    let state_manager = StateManager::new(workspace_root);
+    let mut state = state_manager.load().await?;
+    if let Some(agent) = state.find_agent_by_id_mut(agent_id) {
+        agent.ci_runs = run_ids.iter().copied().collect();
+    }
+    state_manager.save(&state).await?;
+    info!(agent = %agent_id, runs = ?run_ids, "ci fix agent spawned");
+
+    println!("  Implementer started in {session_name}.");
+    println!("  Run `vibe attach {session_name}` to follow along.");
+    Ok(())
+}
+
+/// Prompt section for one PR: its failed checks and the trimmed logs of their
+/// runs. Checks from other CI systems are listed with their link only.
+async fn failing_pr_section(
+    failing: &FailingPr,
+    failed: &[&CheckRun],
+    run_ids: &mut BTreeSet<u64>,
+) -> String {
+    let heading = match &failing.repo {
+        Some(repo) => format!("## {repo}: PR #{}", failing.number),
+        None => format!("## PR #{}", failing.number),
+    };
+    let names: Vec<&str> = failed.iter().map(|check| check.name.as_str()).collect();
+    let mut section = format!("{heading}\n\nFailed checks: {}\n\n", names.join(", "));
+
+    let runs: BTreeSet<u64> = failed.iter().filter_map(|check| check.run_id()).collect();
+    for run_id in runs {
+        run_ids.insert(run_id);
+        match gh::get_failed_run_log(run_id, &failing.worktree).await {
+            Ok(log) => {
+                println!("  Fetched logs of run {run_id}");
+                section.push_str(&format!(
+                    "### Run {run_id}\n\n{}",
+                    ci::trim_failed_log(&log, MAX_LOG_LINES)
+                ));
+            }
+            Err(e) => {
+                println!("  ✗ Logs of run {run_id}: {e}");
+                section.push_str(&format!("### Run {run_id}\n\n(logs unavailable: {e})\n\n"));
+            }
+        }
+    }
+
+    for check in failed.iter().filter(|check| check.run_id().is_none()) {
+        section.push_str(&format!(
+            "- {} failed outside GitHub Actions; no logs available{}\n",
+            check.name,
+            check
+                .details_url
+                .as_deref()
+                .map(|url| format!(" ({url})"))
+                .unwrap_or_default()
+        ));
+    }
+    section
+}
@@ -1,12 +1,17 @@
-use crate::domain::session::SessionStatus;
+use crate::commands::kill;
+use crate::config::MergedConfig;
+use crate::domain::agent::Agent;
+use crate::domain::session::{Session, SessionStatus};
 use crate::error::VibeError;
-use crate::infra::{git, state::StateManager};
+use crate::infra::{git, hooks, state::StateManager};
 use std::path::Path;
 
 pub async fn execute(
     workspace_root: &Path,
     all: bool,
     dry_run: bool,
+    purge: bool,
+    config: &MergedConfig,
 ) -> Result<(), VibeError> {
     let state_manager = StateManager::new(workspace_root);
     let mut state = state_manager.load().await?;
@@ -43,6 +48,9 @@ pub async fn execute(
         println!("  {} [{}]", session.name, session.status);
 
         if !dry_run {
+            if let Some(ref hook) = config.workspace.post_session_hook {
+                hooks::run_post_session(hook, session, workspace_root).await;
+            }
             if session.repo_worktrees.is_empty() {
                 // Single-repo: remove the single worktree
                 if session.worktree_path.exists() {
@@ -81,12 +89,19 @@ pub async fn execute(
 
     if !dry_run {
         // Remove cleaned sessions from state
+        let to_clean_agents: Vec<(&Session, Vec<Agent>)> = to_clean
+            .iter()
+            .map(|s| (s, state.agents_for_session(s.id).into_iter().cloned().collect()))
+            .collect();
         let clean_ids: Vec<_> = to_clean.iter().map(|s| s.id).collect();
         state.sessions.retain(|s| !clean_ids.contains(&s.id));
         state
             .agents
             .retain(|a| !clean_ids.contains(&a.parent_session));
         state_manager.save(&state).await?;
+        for (session, agents) in &to_clean_agents {
+            kill::retire_session_files(&state_manager, session, agents, purge).await;
+        }
 
         // Prune git worktree references
         if state.workspace.repos.is_empty() {
@@ -104,3 +119,36 @@ pub async fn execute(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{config, init_repo, init_state};
+
+    #[tokio::test]
+    async fn test_cleanup_keeps_transcripts() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        init_repo(root);
+        let mut state = init_state(root, vec![]).await;
+        let mut ids = vec![];
+        for name in ["old", "older"] {
+            let mut session = Session::new(name.into(), format!("feat/{name}"), root.join(name), "@1".into());
+            session.status = SessionStatus::Archived;
+            ids.push(session.id);
+            state.sessions.push(session);
+        }
+        let state_manager = StateManager::new(root);
+        state_manager.save(&state).await.unwrap();
+        let transcript = state_manager.session_transcript(ids[0]);
+        std::fs::create_dir_all(transcript.parent().unwrap()).unwrap();
+        std::fs::write(&transcript, "output\n").unwrap();
+
+        execute(root, false, false, false, &config(root)).await.unwrap();
+        assert!(state_manager.load().await.unwrap().sessions.is_empty());
+        assert!(transcript.exists());
+        assert_eq!(state_manager.find_archived_session("old").unwrap().id, ids[0]);
+        // Nothing was kept of the other one, so there's nothing to find
+        assert!(state_manager.find_archived_session("older").is_none());
+    }
+}
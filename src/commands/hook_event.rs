@@ -0,0 +1,86 @@
+use crate::domain::activity::ActivityEvent;
+use crate::error::VibeError;
+use std::ffi::OsString;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Events logs past this size are cut down to their newer half.
+const MAX_LOG_BYTES: u64 = 64 * 1024;
+
+/// Record a Claude Code hook firing in a session worktree: read the hook's
+/// JSON from stdin and append it to the session's events log as an
+/// [`ActivityEvent`]. Hooks vibe doesn't track are ignored.
+pub fn execute(events_file: &Path) -> Result<(), VibeError> {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    // Panes of the built-in multiplexer aren't tmux's
+    let pane = std::env::var("TMUX_PANE").or_else(|_| std::env::var("VIBE_PANE")).ok();
+    let Some(event) = ActivityEvent::from_hook_input(&input, pane, chrono::Utc::now()) else {
+        return Ok(());
+    };
+    append(events_file, &event)
+}
+
+/// Append `event` to the log, first cutting the log down if it grew too big.
+fn append(events_file: &Path, event: &ActivityEvent) -> Result<(), VibeError> {
+    if let Some(dir) = events_file.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    // Hooks of one session can fire at the same time; an append landing
+    // between reading the log and replacing it would be lost
+    let lock = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(with_suffix(events_file, ".lock"))?;
+    lock.lock()?;
+
+    if std::fs::metadata(events_file).is_ok_and(|m| m.len() > MAX_LOG_BYTES) {
+        let log = std::fs::read_to_string(events_file)?;
+        let lines: Vec<&str> = log.lines().collect();
+        let kept = lines[lines.len() / 2..].join("\n");
+        // Replaced whole, so the dashboard never reads a half-written log
+        let tmp = with_suffix(events_file, ".tmp");
+        std::fs::write(&tmp, format!("{kept}\n"))?;
+        std::fs::rename(&tmp, events_file)?;
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(events_file)?;
+    writeln!(file, "{}", serde_json::to_string(event)?)?;
+    Ok(())
+}
+
+fn with_suffix(file: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(file.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_cuts_big_log_in_half() {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = tmp.path().join("events/s1.jsonl");
+        let event = |hook: &str| {
+            let input = format!(r#"{{"hook_event_name": "{hook}"}}"#);
+            ActivityEvent::from_hook_input(&input, None, chrono::Utc::now()).unwrap()
+        };
+
+        append(&log, &event("Stop")).unwrap();
+        let line = std::fs::read_to_string(&log).unwrap();
+        let lines = (MAX_LOG_BYTES as usize / line.len()) + 2;
+        std::fs::write(&log, line.repeat(lines)).unwrap();
+
+        append(&log, &event("PreToolUse")).unwrap();
+        let kept = std::fs::read_to_string(&log).unwrap();
+        assert_eq!(kept.lines().count(), lines - lines / 2 + 1);
+        assert!(kept.lines().last().unwrap().contains("PreToolUse"), "{kept}");
+        assert!(!with_suffix(&log, ".tmp").exists());
+    }
+}
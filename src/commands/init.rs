@@ -67,7 +67,8 @@ async fn init_multi_repo(
     workspace_root: &Path,
     state_manager: &StateManager,
 ) -> Result<(), VibeError> {
-    let repos = git::discover_repos(workspace_root)?;
+    let config = load_config(Some(workspace_root))?;
+    let repos = git::discover_repos(workspace_root, &config.repo_discovery())?;
     if repos.is_empty() {
         return Err(VibeError::NoReposFound);
     }
@@ -79,7 +80,6 @@ async fn init_multi_repo(
         .to_string_lossy()
         .to_string();
 
-    let config = load_config(Some(workspace_root))?;
     let worktree_base_dir = config.worktree_base_dir(workspace_root);
     let tmux_session_name = format!("{}{}", config.tmux_session_prefix(), dir_name);
 
@@ -0,0 +1,360 @@
+use crate::commands::new;
+use crate::config::MergedConfig;
+use crate::domain::session::{Session, SessionStatus};
+use crate::error::VibeError;
+use crate::infra::mux::{self, Multiplexer};
+use crate::infra::{claude, git, state::StateManager, transcript};
+use std::path::Path;
+use tokio::process::Command;
+use tracing::info;
+
+/// Number of trailing check output lines kept for the report and fixer prompt.
+const CHECK_OUTPUT_LINES: usize = 40;
+
+/// Why integration stopped.
+enum Failure {
+    Conflict { files: Vec<String> },
+    CheckFailed { output: String },
+}
+
+impl Failure {
+    fn describe(&self) -> &'static str {
+        match self {
+            Self::Conflict { .. } => "merge conflict",
+            Self::CheckFailed { .. } => "check failed",
+        }
+    }
+}
+
+pub async fn execute(
+    workspace_root: &Path,
+    session_names: Vec<String>,
+    into: String,
+    base: Option<String>,
+    check: Option<String>,
+    fix: bool,
+    config: &MergedConfig,
+) -> Result<(), VibeError> {
+    let state_manager = StateManager::new(workspace_root);
+    let mut state = state_manager.load().await?;
+
+    if state.workspace.is_multi_repo() {
+        return Err(VibeError::User(
+            "vibe integrate does not support multi-repo workspaces yet".into(),
+        ));
+    }
+
+    let mut branches = Vec::new();
+    for name in &session_names {
+        let session = state
+            .find_session_by_name(name)
+            .ok_or_else(|| VibeError::SessionNotFound(name.clone()))?;
+        if session.is_main {
+            return Err(VibeError::User(
+                "The main session has no branch of its own to integrate".into(),
+            ));
+        }
+        branches.push((name.clone(), session.branch.clone()));
+    }
+
+    let check = check.or_else(|| config.workspace.integrate_check.clone());
+    let integration_name = format!("integrate-{}", into.replace('/', "-"));
+    if state.find_session_by_name(&integration_name).is_some() {
+        return Err(VibeError::User(format!(
+            "Session '{integration_name}' already exists. Remove it with `vibe kill {integration_name} --force` first."
+        )));
+    }
+
+    // A branch can only be checked out once; merging into someone's checkout
+    // behind their back isn't an option either
+    if let Some(path) = git::branch_checkout(workspace_root, &into).await? {
+        return Err(VibeError::User(format!(
+            "{into} is checked out in {}. Integrate into a new branch (e.g. --into integrate/next) and merge that into {into} once it passes.",
+            path.display()
+        )));
+    }
+
+    // Integration worktree, registered as a session so it shows up in the
+    // dashboard and `vibe kill` cleans it up
+    let worktree = git::create_worktree(
+        workspace_root,
+        &into,
+        base.as_deref(),
+        &config.worktree_base_dir(workspace_root),
+        &config.worktree_setup(),
+    )
+    .await?;
+    let worktree_path = worktree.path;
+    info!(worktree = %worktree_path.display(), "integration worktree created");
+
+    mux::current().ensure_session(&state.tmux_session_name).await?;
+    let window_id = mux::current().create_window(
+        &state.tmux_session_name,
+        &integration_name,
+        worktree_path.to_str().unwrap_or("."),
+    )
+    .await?;
+    let tmux_target = format!("{}:{}", state.tmux_session_name, integration_name);
+
+    let mut session = Session::new(
+        integration_name.clone(),
+        into.clone(),
+        worktree_path.clone(),
+        window_id,
+    );
+    session.status = SessionStatus::Active;
+    // For a fixer agent started here with --fix
+    new::install_activity_hooks(&state_manager, &session);
+    let _ = transcript::start(&tmux_target, &state_manager.session_transcript(session.id)).await;
+    state.sessions.push(session);
+    state_manager.save(&state).await?;
+
+    println!("Integrating {} session(s) into {into}", branches.len());
+    println!("  Worktree: {}", worktree_path.display());
+
+    let (merged, failure) = merge_in_order(&worktree_path, &into, &branches, check.as_deref()).await?;
+    if merged == branches.len() {
+        return Ok(());
+    }
+
+    let remaining: Vec<&str> = branches[merged + 1..]
+        .iter()
+        .map(|(_, b)| b.as_str())
+        .collect();
+    if !remaining.is_empty() {
+        println!("  Not attempted: {}", remaining.join(", "));
+    }
+
+    let Some((name, branch, failure)) = failure else {
+        return Ok(());
+    };
+    let stopped = format!(
+        "Integrating into {into} stopped at {name} ({branch}): {}",
+        failure.describe()
+    );
+
+    if !fix {
+        println!("  Resolve in {} (`vibe attach {integration_name}`).", worktree_path.display());
+        println!("  Pass --fix to hand failures like this to an agent instead.");
+        return Err(VibeError::User(stopped));
+    }
+
+    let prompt = fixer_prompt(&into, &name, &branch, &failure, check.as_deref(), &remaining);
+    let prompt_file = state_manager
+        .agents_dir()
+        .join(format!("{integration_name}-fix-prompt.md"));
+    tokio::fs::create_dir_all(state_manager.agents_dir()).await?;
+    tokio::fs::write(&prompt_file, &prompt).await?;
+
+    let cmd = claude::interactive_command(
+        config.claude_command(),
+        None,
+        &[],
+        &[],
+        None,
+        None,
+        &config.global.claude_extra_args,
+    );
+    let escaped_path = prompt_file.display().to_string().replace('\'', "'\\''");
+    mux::current().send_keys(&tmux_target, &format!("{cmd} \"$(cat '{escaped_path}')\"")).await?;
+
+    println!("  Fixer agent started in {integration_name}.");
+    println!("  Run `vibe attach {integration_name}` to follow along.");
+
+    // Not integrated yet: the fixer is still at work
+    Err(VibeError::User(format!(
+        "{stopped}; a fixer agent is working on it in {integration_name}"
+    )))
+}
+
+/// Merge each `(session, branch)` into the worktree in order, running the
+/// check after each. Stops at the first conflict or failing check and returns
+/// how many merged, and the failure if any.
+async fn merge_in_order(
+    worktree_path: &Path,
+    into: &str,
+    branches: &[(String, String)],
+    check: Option<&str>,
+) -> Result<(usize, Option<(String, String, Failure)>), VibeError> {
+    let mut merged = 0;
+    let mut failure = None;
+    for (name, branch) in branches {
+        let files = match git::merge_branch(worktree_path, branch).await {
+            Ok(files) => files,
+            Err(e) => {
+                println!("  ✗ {name} ({branch}): {e}");
+                println!("\nIntegrated {merged}/{} session(s) into {into}.", branches.len());
+                return Err(VibeError::Git(format!(
+                    "integrating into {into} stopped at {name}: merging {branch} failed: {e}"
+                )));
+            }
+        };
+        if !files.is_empty() {
+            println!("  ✗ {name} ({branch}): merge conflict");
+            for file in &files {
+                println!("      {file}");
+            }
+            failure = Some((name.clone(), branch.clone(), Failure::Conflict { files }));
+            break;
+        }
+
+        let checked = match check {
+            Some(cmd) => run_check(worktree_path, cmd).await,
+            None => Ok(()),
+        };
+        if let Err(output) = checked {
+            println!("  ✗ {name} ({branch}): merged, but the check failed");
+            for line in output.lines() {
+                println!("      {line}");
+            }
+            failure = Some((name.clone(), branch.clone(), Failure::CheckFailed { output }));
+            break;
+        }
+
+        println!("  ✓ {name} ({branch})");
+        merged += 1;
+    }
+
+    println!("\nIntegrated {merged}/{} session(s) into {into}.", branches.len());
+    Ok((merged, failure))
+}
+
+/// Run the check command in the worktree. On failure returns the tail of its
+/// combined output.
+async fn run_check(worktree_path: &Path, cmd: &str) -> Result<(), String> {
+    let output = Command::new("sh")
+        .current_dir(worktree_path)
+        .args(["-c", cmd])
+        .output()
+        .await
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let lines: Vec<&str> = combined.lines().collect();
+    let tail = &lines[lines.len().saturating_sub(CHECK_OUTPUT_LINES)..];
+    Err(tail.join("\n"))
+}
+
+fn fixer_prompt(
+    into: &str,
+    session: &str,
+    branch: &str,
+    failure: &Failure,
+    check: Option<&str>,
+    remaining: &[&str],
+) -> String {
+    let problem = match failure {
+        Failure::Conflict { files } => format!(
+            "Merging `{branch}` (session {session}) into `{into}` stopped with conflicts in:\n\n{}\n\n\
+             The merge is still in progress. Resolve the conflicts, keeping the intent of both sides, \
+             then commit the merge.",
+            files
+                .iter()
+                .map(|f| format!("- {f}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+        Failure::CheckFailed { output } => format!(
+            "`{branch}` (session {session}) was merged into `{into}`, but the check failed:\n\n\
+             ```\n{output}\n```\n\n\
+             Fix the breakage and commit the fix on `{into}`."
+        ),
+    };
+
+    let mut prompt = format!("You are integrating several feature branches.\n\n{problem}");
+    if let Some(cmd) = check {
+        prompt.push_str(&format!("\n\nMake sure `{cmd}` passes before you finish."));
+    }
+    if !remaining.is_empty() {
+        prompt.push_str(&format!(
+            "\n\nThen merge the remaining branches in order with `git merge --no-ff`, \
+             checking after each one: {}",
+            remaining.join(", ")
+        ));
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, config, git, init_repo, init_state};
+
+    /// A repo with a worktree to integrate into and `a`/`b` branches, each
+    /// adding a file and setting `shared.txt` to `a_content`/`b_content`.
+    fn setup(tmp: &Path, a_content: &str, b_content: &str) -> std::path::PathBuf {
+        let repo = tmp.join("repo");
+        init_repo(&repo);
+        commit_file(&repo, "shared.txt", "base\n");
+        for (branch, content) in [("a", a_content), ("b", b_content)] {
+            git(&repo, &["checkout", "-q", "-b", branch, "main"]);
+            commit_file(&repo, &format!("{branch}.txt"), branch);
+            if content != "base\n" {
+                commit_file(&repo, "shared.txt", content);
+            }
+        }
+        git(&repo, &["checkout", "-q", "main"]);
+        let worktree = tmp.join("integration");
+        git(&repo, &["worktree", "add", "-q", "-b", "integrate/next", worktree.to_str().unwrap(), "main"]);
+        worktree
+    }
+
+    fn branches() -> Vec<(String, String)> {
+        vec![("s-a".into(), "a".into()), ("s-b".into(), "b".into())]
+    }
+
+    #[tokio::test]
+    async fn test_clean_merge() {
+        let tmp = tempfile::tempdir().unwrap();
+        // Both branches leave shared.txt alone
+        let worktree = setup(tmp.path(), "base\n", "base\n");
+
+        let (merged, failure) = merge_in_order(&worktree, "integrate/next", &branches(), Some("test -f a.txt"))
+            .await
+            .unwrap();
+        assert_eq!(merged, 2);
+        assert!(failure.is_none());
+        assert!(worktree.join("a.txt").exists() && worktree.join("b.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_conflicting_merge_stops_in_progress() {
+        let tmp = tempfile::tempdir().unwrap();
+        let worktree = setup(tmp.path(), "from a\n", "from b\n");
+
+        let (merged, failure) = merge_in_order(&worktree, "integrate/next", &branches(), None)
+            .await
+            .unwrap();
+        assert_eq!(merged, 1);
+        let Some((name, branch, Failure::Conflict { files })) = failure else {
+            panic!("expected a conflict");
+        };
+        assert_eq!((name.as_str(), branch.as_str()), ("s-b", "b"));
+        assert_eq!(files, ["shared.txt"]);
+        // Left mid-merge to be resolved in place
+        assert!(git::has_unresolved_sync(&worktree));
+    }
+
+    #[tokio::test]
+    async fn test_into_a_checked_out_branch_is_refused() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path().join("repo");
+        init_repo(&repo);
+        git(&repo, &["branch", "feat/x"]);
+        let mut state = init_state(&repo, vec![]).await;
+        state.sessions.push(Session::new("x".into(), "feat/x".into(), repo.clone(), "@1".into()));
+        StateManager::new(&repo).save(&state).await.unwrap();
+
+        let err = execute(&repo, vec!["x".into()], "main".into(), None, None, false, &config(tmp.path()))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("main is checked out in"), "{err}");
+    }
+}
@@ -1,6 +1,9 @@
 use crate::config::MergedConfig;
+use crate::domain::agent::Agent;
+use crate::domain::session::{ArchivedAgent, ArchivedSession, Session};
 use crate::error::VibeError;
-use crate::infra::{git, state::StateManager, tmux::TmuxController};
+use crate::infra::mux::{self, Multiplexer};
+use crate::infra::{git, hooks, state::StateManager};
 use std::path::Path;
 use tracing::{info, warn};
 
@@ -9,7 +12,8 @@ pub async fn execute(
     target: String,
     force: bool,
     delete_branch: bool,
-    _config: &MergedConfig,
+    purge: bool,
+    config: &MergedConfig,
 ) -> Result<(), VibeError> {
     let state_manager = StateManager::new(workspace_root);
     let mut state = state_manager.load().await?;
@@ -29,17 +33,31 @@ pub async fn execute(
         return Ok(());
     }
 
+    let session_snapshot = session.clone();
     let worktree_path = session.worktree_path.clone();
     let repo_worktrees = session.repo_worktrees.clone();
     let session_name = session.name.clone();
     let tmux_window = format!("{}:{}", state.tmux_session_name, session_name);
+    // Agents in windows of their own don't close with the session's window
+    let agent_panes: Vec<String> = state
+        .agents_for_session(session_snapshot.id)
+        .iter()
+        .filter_map(|a| a.tmux_pane.clone())
+        .collect();
 
     info!(session = %session_name, "killing session");
 
     // Kill tmux window (best effort)
-    let _ = TmuxController::kill_window(&tmux_window).await;
+    let _ = mux::current().kill_window(&tmux_window).await;
+    for pane in &agent_panes {
+        let _ = mux::current().kill_pane(pane).await;
+    }
     info!(session = %session_name, "tmux window removed");
 
+    if let Some(ref hook) = config.workspace.post_session_hook {
+        hooks::run_post_session(hook, &session_snapshot, workspace_root).await;
+    }
+
     if repo_worktrees.is_empty() {
         // Single-repo mode: remove the single worktree
         if worktree_path.exists() {
@@ -81,15 +99,155 @@ pub async fn execute(
     }
 
     // Remove session and associated agents from state
-    let session_id = state
-        .find_session_by_name(&target)
-        .map(|s| s.id)
-        .unwrap();
+    let session_id = session_snapshot.id;
+    let agents: Vec<Agent> = state
+        .agents_for_session(session_id)
+        .into_iter()
+        .cloned()
+        .collect();
     state.agents.retain(|a| a.parent_session != session_id);
     state.sessions.retain(|s| s.id != session_id);
 
     state_manager.save(&state).await?;
+    retire_session_files(&state_manager, &session_snapshot, &agents, purge).await;
 
     info!(session = %session_name, "session killed and removed");
     Ok(())
 }
+
+/// Deal with the files of a session that was removed from the state. With
+/// `purge`, its transcript, its agents' transcripts and its activity log are
+/// deleted. Otherwise they are kept, recorded so `vibe log` and search can
+/// still find them by the session's name.
+pub async fn retire_session_files(
+    state_manager: &StateManager,
+    session: &Session,
+    agents: &[Agent],
+    purge: bool,
+) {
+    let events_file = state_manager.events_file(&session.name);
+    let Some(dir) = state_manager.session_transcript(session.id).parent().map(Path::to_path_buf) else {
+        return;
+    };
+
+    if purge {
+        let _ = tokio::fs::remove_file(&events_file).await;
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        for agent in agents {
+            if let Some(agent_dir) = state_manager.agent_transcript(agent.id).parent() {
+                let _ = tokio::fs::remove_dir_all(agent_dir).await;
+            }
+        }
+        return;
+    }
+
+    if events_file.exists() {
+        // Kept with the transcript; a new session of the same name starts a
+        // fresh activity log
+        let _ = tokio::fs::create_dir_all(&dir).await;
+        if let Err(e) = tokio::fs::rename(&events_file, dir.join("events.jsonl")).await {
+            warn!(error = %e, "failed to archive activity log");
+        }
+    }
+    let kept = dir.exists() || agents.iter().any(|a| state_manager.agent_transcript(a.id).exists());
+    if !kept {
+        return;
+    }
+    let archived = ArchivedSession {
+        id: session.id,
+        name: session.name.clone(),
+        branch: session.branch.clone(),
+        killed_at: chrono::Utc::now(),
+        agents: agents
+            .iter()
+            .map(|a| ArchivedAgent {
+                id: a.id,
+                name: a.name.clone(),
+            })
+            .collect(),
+    };
+    if let Err(e) = state_manager.save_archived_session(&archived).await {
+        warn!(session = %session.name, error = %e, "failed to record killed session");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::AgentMode;
+    use crate::infra::mux::fake::FakeMux;
+    use crate::infra::transcript;
+    use crate::test_support::{config, init_state};
+
+    /// A session `s1` with an open window, an agent, their transcripts and
+    /// an activity log. Returns the session's and the agent's ids.
+    async fn setup(root: &Path) -> (&'static FakeMux, StateManager, uuid::Uuid, uuid::Uuid) {
+        let mux = mux::fake_for_test();
+        let mut state = init_state(root, vec![]).await;
+        let window = mux.create_window("vibe-ws", "s1", "/tmp").await.unwrap();
+        let session = Session::new("s1".into(), "feat/s1".into(), root.join("gone"), window);
+        let id = session.id;
+        let state_manager = StateManager::new(root);
+        let agent = Agent::new(
+            id,
+            "helper".into(),
+            AgentMode::Interactive,
+            "help".into(),
+            root.join("gone"),
+            state_manager.agents_dir(),
+        );
+        let agent_id = agent.id;
+        state.sessions.push(session);
+        state.agents.push(agent);
+        state_manager.save(&state).await.unwrap();
+
+        for (file, text) in [
+            (state_manager.session_transcript(id), "session output\n"),
+            (state_manager.agent_transcript(agent_id), "agent output\n"),
+        ] {
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(&file, text).unwrap();
+        }
+        std::fs::create_dir_all(state_manager.events_dir()).unwrap();
+        std::fs::write(state_manager.events_file("s1"), "{}\n").unwrap();
+        (mux, state_manager, id, agent_id)
+    }
+
+    #[tokio::test]
+    async fn test_kill_keeps_transcript() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (mux, state_manager, id, agent_id) = setup(tmp.path()).await;
+
+        execute(tmp.path(), "s1".into(), true, false, false, &config(tmp.path())).await.unwrap();
+
+        assert_eq!(mux.calls().last().unwrap(), "kill-window vibe-ws:s1");
+        assert!(mux.window_names().is_empty());
+        let state = state_manager.load().await.unwrap();
+        assert!(state.sessions.is_empty() && state.agents.is_empty());
+        let dir = state_manager.session_transcript(id).parent().unwrap().to_path_buf();
+        assert!(dir.join("transcript.log").exists());
+        assert!(state_manager.agent_transcript(agent_id).exists());
+        // Moved out of the way of a new session named s1
+        assert!(!state_manager.events_file("s1").exists());
+        assert!(dir.join("events.jsonl").exists());
+
+        // Still found by name
+        assert_eq!(state_manager.find_archived_session("s1").unwrap().id, id);
+        let hits = transcript::search_all(&state_manager, &state, "output");
+        let sources: Vec<String> = hits.iter().map(|h| h.source()).collect();
+        assert_eq!(sources, ["s1 (killed)", "s1 (killed)/helper"]);
+    }
+
+    #[tokio::test]
+    async fn test_kill_purge() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (_, state_manager, id, agent_id) = setup(tmp.path()).await;
+
+        execute(tmp.path(), "s1".into(), true, false, true, &config(tmp.path())).await.unwrap();
+
+        assert!(!state_manager.session_transcript(id).exists());
+        assert!(!state_manager.agent_transcript(agent_id).parent().unwrap().exists());
+        assert!(!state_manager.events_file("s1").exists());
+        assert!(state_manager.find_archived_session("s1").is_none());
+    }
+}
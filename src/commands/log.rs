@@ -0,0 +1,88 @@
+use crate::error::VibeError;
+use crate::infra::{state::StateManager, transcript};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// How often `--follow` checks the transcript for new output.
+const FOLLOW_POLL: Duration = Duration::from_millis(500);
+
+/// Print a session's transcript, optionally waiting for new output. Killed
+/// sessions whose transcript was kept are found too.
+pub async fn execute(workspace_root: &Path, session_name: &str, follow: bool) -> Result<(), VibeError> {
+    let state_manager = StateManager::new(workspace_root);
+    let state = state_manager.load().await?;
+    let (session_id, follow) = match state.find_session_by_name(session_name) {
+        Some(session) => (session.id, follow),
+        // Nothing more is coming for a killed session
+        None => state_manager
+            .find_archived_session(session_name)
+            .map(|archived| (archived.id, false))
+            .ok_or_else(|| VibeError::SessionNotFound(session_name.to_string()))?,
+    };
+    let file = state_manager.session_transcript(session_id);
+
+    match transcript::read(&file) {
+        Some(text) => print!("{text}"),
+        None if !follow => {
+            return Err(VibeError::User(format!(
+                "No transcript for '{session_name}' yet. It is recorded while the session's window is open."
+            )));
+        }
+        None => {}
+    }
+    if !follow {
+        return Ok(());
+    }
+
+    let mut offset = std::fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
+    loop {
+        std::io::stdout().flush()?;
+        tokio::time::sleep(FOLLOW_POLL).await;
+        let len = std::fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
+        // Shrunk: rotated, start over on the new file
+        if len < offset {
+            offset = 0;
+        }
+        if len == offset {
+            continue;
+        }
+        let (new_output, read) = read_complete_lines(&file, offset)?;
+        print!("{new_output}");
+        offset += read;
+    }
+}
+
+/// The complete lines of `file` from byte `offset` on, and how many bytes
+/// they take. A line still being written (which may end mid-character) is
+/// left for the next read.
+fn read_complete_lines(file: &Path, offset: u64) -> std::io::Result<(String, u64)> {
+    let mut bytes = vec![];
+    let mut f = std::fs::File::open(file)?;
+    f.seek(SeekFrom::Start(offset))?;
+    f.read_to_end(&mut bytes)?;
+    let complete = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    Ok((
+        String::from_utf8_lossy(&bytes[..complete]).into_owned(),
+        complete as u64,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_complete_lines_stops_at_partial_line() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("transcript.log");
+        // Second line cut off inside the two bytes of "é"
+        let text = "one\ntwo é\n";
+        std::fs::write(&file, &text.as_bytes()[..9]).unwrap();
+
+        assert_eq!(read_complete_lines(&file, 0).unwrap(), ("one\n".to_string(), 4));
+        std::fs::write(&file, text).unwrap();
+        assert_eq!(read_complete_lines(&file, 4).unwrap(), ("two é\n".to_string(), 7));
+        assert_eq!(read_complete_lines(&file, 11).unwrap(), (String::new(), 0));
+    }
+}
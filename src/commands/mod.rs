@@ -1,12 +1,21 @@
 pub mod attach;
+pub mod ci;
 pub mod cleanup;
 pub mod doctor;
+pub mod hook_event;
 pub mod init;
+pub mod integrate;
 pub mod kill;
+pub mod log;
 pub mod new;
 pub mod refresh_repos;
 
 pub mod plan;
+pub mod pr;
 pub mod review;
+pub mod search;
+pub mod send;
+pub mod session;
 pub mod spawn;
 pub mod status;
+pub mod sync;
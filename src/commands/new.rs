@@ -1,24 +1,64 @@
 use crate::config::MergedConfig;
-use crate::domain::session::{Session, SessionStatus};
+use crate::domain::session::{RefOverrides, RepoRefs, Session, SessionStatus};
 use crate::domain::template::AgentTemplate;
-use crate::domain::workspace::WorkspaceKind;
+use crate::domain::workspace::{RepoInfo, WorkspaceKind};
 use crate::error::VibeError;
-use crate::infra::{claude, git, state::StateManager, tmux::TmuxController};
-use std::collections::BTreeMap;
+use crate::infra::hooks::{self, HookContext};
+use crate::infra::mux::{self, Multiplexer};
+use crate::infra::{claude, git, state::StateManager, transcript};
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 use tracing::{info, warn};
 
+/// What `vibe new` was asked for.
+#[derive(Debug, Default)]
+pub struct NewSessionOptions {
+    pub name: String,
+    pub branch: RefOverrides,
+    pub base: RefOverrides,
+    /// Multi-repo: repos to include; `None` for all of them.
+    pub repos: Option<Vec<String>>,
+    /// Roll back every worktree if any repo's fails.
+    pub all_or_nothing: bool,
+    pub template: Option<String>,
+    pub system_prompt: Option<String>,
+    pub headless: bool,
+    /// Task for a headless session.
+    pub prompt: Option<String>,
+}
+
+/// Worktrees created for a new session.
+struct SessionWorktrees {
+    /// The session's worktree, or the directory holding the repo worktrees of
+    /// a multi-repo session.
+    root: PathBuf,
+    /// Multi-repo: worktree per repo name.
+    repos: BTreeMap<String, PathBuf>,
+    /// Repos (`None` for single-repo) whose branch didn't exist before, so a
+    /// rollback deletes it again.
+    new_branches: BTreeSet<Option<String>>,
+    /// Multi-repo: repos whose worktree failed, with the error.
+    failed: BTreeMap<String, String>,
+}
+
+/// Create a session. Returns the repos (with errors) whose worktree could not
+/// be created; they are also recorded on the session for `vibe session repair`.
 pub async fn execute(
     workspace_root: &Path,
-    name: String,
-    branch: Option<String>,
-    base: Option<String>,
-    template_name: Option<String>,
-    system_prompt: Option<String>,
-    headless: bool,
-    prompt: Option<String>,
+    options: NewSessionOptions,
     config: &MergedConfig,
-) -> Result<(), VibeError> {
+) -> Result<BTreeMap<String, String>, VibeError> {
+    let NewSessionOptions {
+        name,
+        branch,
+        base,
+        repos,
+        all_or_nothing,
+        template: template_name,
+        system_prompt,
+        headless,
+        prompt,
+    } = options;
     let state_manager = StateManager::new(workspace_root);
     let mut state = state_manager.load().await?;
 
@@ -29,12 +69,23 @@ pub async fn execute(
         )));
     }
 
-    let branch_name = branch.unwrap_or_else(|| format!("feat/{name}"));
-    let base_ref = base.as_deref();
+    let branch_name = branch
+        .all
+        .clone()
+        .unwrap_or_else(|| format!("feat/{name}"));
+    let base_ref = base.all.as_deref();
+    let mut repo_refs = BTreeMap::new();
 
     info!(session = %name, "creating session");
 
-    let (session_worktree_path, repo_worktrees) = match state.workspace.kind {
+    let worktrees = match state.workspace.kind {
+        WorkspaceKind::SingleRepo
+            if repos.is_some() || !branch.per_repo.is_empty() || !base.per_repo.is_empty() =>
+        {
+            return Err(VibeError::User(
+                "--repos and repo=ref overrides only apply to multi-repo workspaces".into(),
+            ));
+        }
         WorkspaceKind::SingleRepo => {
             let worktree_base_dir = config.worktree_base_dir(workspace_root);
             let worktree = git::create_worktree(
@@ -42,47 +93,101 @@ pub async fn execute(
                 &branch_name,
                 base_ref,
                 &worktree_base_dir,
+                &config.worktree_setup(),
             )
             .await?;
             info!(worktree = %worktree.path.display(), "worktree created");
-            (worktree.path, BTreeMap::new())
+            SessionWorktrees {
+                root: worktree.path,
+                repos: BTreeMap::new(),
+                new_branches: worktree.created_branch.then_some(None).into_iter().collect(),
+                failed: BTreeMap::new(),
+            }
         }
         WorkspaceKind::MultiRepo => {
+            let selected = select_repos(&state.workspace.repos, repos.as_deref())?;
+            repo_refs = resolve_repo_refs(&selected, &name, &branch_name, &branch, &base, config)?;
             create_multi_repo_worktrees(
-                &state.workspace.repos,
-                &branch_name,
-                base_ref,
+                &selected,
+                &repo_refs,
                 &config.worktree_base_dir(workspace_root),
                 &state.workspace.name,
+                &config.worktree_setup(),
             )
             .await?
         }
     };
 
+    if !worktrees.failed.is_empty() && all_or_nothing {
+        warn!("some repo worktrees failed, rolling back (--all-or-nothing)");
+        remove_worktrees(workspace_root, &state.workspace.repos, &worktrees).await;
+        return Err(VibeError::Git(format!(
+            "Failed to create worktrees: {}. Rolled back the others.",
+            format_failures(&worktrees.failed)
+        )));
+    }
+
+    // Bootstrap each worktree before Claude starts; a failing hook aborts
+    if let Some(ref hook) = config.workspace.pre_session_hook {
+        let hook_worktrees: Vec<(Option<&str>, &Path)> = if worktrees.repos.is_empty() {
+            vec![(None, worktrees.root.as_path())]
+        } else {
+            worktrees
+                .repos
+                .iter()
+                .map(|(name, path)| (Some(name.as_str()), path.as_path()))
+                .collect()
+        };
+        for (repo, worktree) in hook_worktrees {
+            let repo_branch = repo
+                .and_then(|r| repo_refs.get(r))
+                .map(|refs: &RepoRefs| refs.branch.as_str())
+                .unwrap_or(&branch_name);
+            let ctx = HookContext {
+                session: &name,
+                branch: repo_branch,
+                worktree,
+                repo,
+                workspace_root,
+            };
+            if let Err(e) = hooks::run("pre_session_hook", hook, &ctx).await {
+                warn!(error = %e, "pre_session_hook failed, removing worktrees");
+                remove_worktrees(workspace_root, &state.workspace.repos, &worktrees).await;
+                return Err(e);
+            }
+        }
+    }
+
     // Ensure tmux session exists
-    TmuxController::ensure_session(&state.tmux_session_name).await?;
+    mux::current().ensure_session(&state.tmux_session_name).await?;
 
     // Create tmux window
-    let window_id = TmuxController::create_window(
+    let window_id = mux::current().create_window(
         &state.tmux_session_name,
         &name,
-        session_worktree_path.to_str().unwrap_or("."),
+        worktrees.root.to_str().unwrap_or("."),
     )
     .await?;
     info!(window = %name, "tmux window created");
 
-    // Lock the window name so tmux doesn't auto-rename it when Claude starts
     let window_target = format!("{}:{}", state.tmux_session_name, name);
-    let _ = TmuxController::disable_auto_rename_for(&window_target).await;
 
     // Create session record
-    let mut session = Session::new(
-        name.clone(),
-        branch_name,
-        session_worktree_path,
-        window_id.clone(),
-    );
+    let SessionWorktrees {
+        root,
+        repos: repo_worktrees,
+        failed: failed_repos,
+        ..
+    } = worktrees;
+    let mut session = Session::new(name.clone(), branch_name, root, window_id.clone());
+    // Keep refs for failed repos too so `vibe session repair` can retry them
+    repo_refs.retain(|repo, _| repo_worktrees.contains_key(repo) || failed_repos.contains_key(repo));
     session.repo_worktrees = repo_worktrees;
+    session.repo_refs = repo_refs;
+    session.failed_repos = failed_repos.clone();
+    if session.repo_worktrees.is_empty() {
+        session.base = base.all.clone();
+    }
 
     // Load template if specified
     let resolved_system_prompt = if let Some(sp) = system_prompt {
@@ -96,6 +201,9 @@ pub async fn execute(
         None
     };
 
+    install_activity_hooks(&state_manager, &session);
+    let _ = transcript::start(&window_target, &state_manager.session_transcript(session.id)).await;
+
     if headless {
         let task_prompt = prompt.ok_or_else(|| {
             VibeError::User("--prompt is required in headless mode".into())
@@ -112,7 +220,7 @@ pub async fn execute(
                 .unwrap_or_default(),
             task_prompt.replace('\'', "'\\''"),
         );
-        TmuxController::send_keys(&tmux_target, &cmd).await?;
+        mux::current().send_keys(&tmux_target, &cmd).await?;
         session.status = SessionStatus::Active;
         info!("started headless claude session");
     } else {
@@ -127,7 +235,7 @@ pub async fn execute(
             None,
             &config.global.claude_extra_args,
         );
-        TmuxController::send_keys(&tmux_target, &cmd).await?;
+        mux::current().send_keys(&tmux_target, &cmd).await?;
         session.status = SessionStatus::Active;
         info!("started interactive claude session");
     }
@@ -137,20 +245,120 @@ pub async fn execute(
 
     info!(session = %name, "session is ready");
 
-    Ok(())
+    Ok(failed_repos)
 }
 
-/// Create worktrees for all repos in a multi-repo workspace.
+/// Install the Claude Code hooks that report a session's activity to the
+/// dashboard. Without them the dashboard falls back to reading the pane, so
+/// failures are only logged.
+pub fn install_activity_hooks(state_manager: &StateManager, session: &Session) {
+    let events_file = state_manager.events_file(&session.name);
+    let mut result = claude::install_activity_hooks(&session.worktree_path, &events_file);
+    // Keep the file out of diffs when Claude runs in a git checkout
+    if result.is_ok() && session.worktree_path.join(".git").exists() {
+        result = git::exclude_locally(&session.worktree_path, ".claude/settings.local.json");
+    }
+    if let Err(e) = result {
+        warn!(session = %session.name, error = %e, "failed to install activity hooks");
+    }
+}
+
+/// Resolve `--repos` against the workspace's repos, keeping workspace order.
+/// `None` selects every repo.
+fn select_repos(
+    repos: &[RepoInfo],
+    names: Option<&[String]>,
+) -> Result<Vec<RepoInfo>, VibeError> {
+    let Some(names) = names else {
+        return Ok(repos.to_vec());
+    };
+
+    let unknown: Vec<&str> = names
+        .iter()
+        .filter(|n| !repos.iter().any(|r| r.name == **n))
+        .map(|n| n.as_str())
+        .collect();
+    if !unknown.is_empty() {
+        let available: Vec<&str> = repos.iter().map(|r| r.name.as_str()).collect();
+        return Err(VibeError::User(format!(
+            "Unknown repo(s): {}. Available: {}",
+            unknown.join(", "),
+            available.join(", ")
+        )));
+    }
+
+    let selected: Vec<RepoInfo> = repos
+        .iter()
+        .filter(|r| names.contains(&r.name))
+        .cloned()
+        .collect();
+    if selected.is_empty() {
+        return Err(VibeError::User("--repos must name at least one repo".into()));
+    }
+    Ok(selected)
+}
+
+/// Work out each repo's branch and base. Precedence: `repo=` CLI override,
+/// then a plain CLI value, then `[repos.<name>]` config, then the defaults
+/// (session branch, repo default branch).
+fn resolve_repo_refs(
+    repos: &[RepoInfo],
+    session_name: &str,
+    session_branch: &str,
+    branch: &RefOverrides,
+    base: &RefOverrides,
+    config: &MergedConfig,
+) -> Result<BTreeMap<String, RepoRefs>, VibeError> {
+    let unknown: Vec<&str> = branch
+        .per_repo
+        .keys()
+        .chain(base.per_repo.keys())
+        .filter(|name| !repos.iter().any(|r| r.name == **name))
+        .map(|name| name.as_str())
+        .collect();
+    if !unknown.is_empty() {
+        return Err(VibeError::User(format!(
+            "Overrides for repo(s) not in this session: {}",
+            unknown.join(", ")
+        )));
+    }
+
+    Ok(repos
+        .iter()
+        .map(|repo| {
+            let repo_config = config.workspace.repos.get(&repo.name);
+            let repo_branch = branch
+                .for_repo(&repo.name)
+                .map(|b| b.to_string())
+                .or_else(|| repo_config.and_then(|c| c.branch_for(session_name)))
+                .unwrap_or_else(|| session_branch.to_string());
+            let repo_base = base
+                .for_repo(&repo.name)
+                .map(|b| b.to_string())
+                .or_else(|| repo_config.and_then(|c| c.base.clone()));
+            (
+                repo.name.clone(),
+                RepoRefs {
+                    branch: repo_branch,
+                    base: repo_base,
+                },
+            )
+        })
+        .collect())
+}
+
+/// Create worktrees for the given repos of a multi-repo workspace.
 ///
 /// Layout: `{worktree_base_dir}/{parent_name}-vibe-{short_id}/{repo_name}/`
-/// Returns (session_root_path, repo_name -> worktree_path map).
+/// Repos that fail are recorded in the result; errors only if every repo
+/// failed.
 async fn create_multi_repo_worktrees(
-    repos: &[crate::domain::workspace::RepoInfo],
-    branch_name: &str,
-    base_ref: Option<&str>,
+    repos: &[RepoInfo],
+    repo_refs: &BTreeMap<String, RepoRefs>,
     worktree_base_dir: &Path,
     parent_name: &str,
-) -> Result<(PathBuf, BTreeMap<String, PathBuf>), VibeError> {
+    setup: &git::WorktreeSetup,
+) -> Result<SessionWorktrees, VibeError> {
     let short_id = &uuid::Uuid::new_v4().to_string()[..8];
     let session_dir_name = format!("{parent_name}-vibe-{short_id}");
     let session_root = worktree_base_dir.join(&session_dir_name);
@@ -159,17 +367,19 @@ async fn create_multi_repo_worktrees(
     tokio::fs::create_dir_all(&session_root).await?;
 
     let mut repo_worktrees = BTreeMap::new();
-    let mut successes = 0;
-    let mut errors = Vec::new();
+    let mut new_branches = BTreeSet::new();
+    let mut failed = BTreeMap::new();
 
     // Create worktrees concurrently using JoinSet
     let mut join_set = tokio::task::JoinSet::new();
     for repo in repos {
         let repo_root = repo.root.clone();
         let repo_name = repo.name.clone();
-        let branch = branch_name.to_string();
-        let base = base_ref.map(|s| s.to_string());
+        let refs = &repo_refs[&repo.name];
+        let branch = refs.branch.clone();
+        let base = refs.base.clone();
         let target_path = session_root.join(&repo_name);
+        let setup = setup.clone();
 
         join_set.spawn(async move {
             let result = git::create_worktree_at(
@@ -177,6 +387,7 @@ async fn create_multi_repo_worktrees(
                 &branch,
                 base.as_deref(),
                 &target_path,
+                &setup,
             )
             .await;
             (repo_name, target_path, result)
@@ -185,38 +396,193 @@ async fn create_multi_repo_worktrees(
 
     while let Some(result) = join_set.join_next().await {
         match result {
-            Ok((repo_name, target_path, Ok(_wt))) => {
+            Ok((repo_name, target_path, Ok(wt))) => {
                 info!(repo = %repo_name, path = %target_path.display(), "repo worktree created");
+                if wt.created_branch {
+                    new_branches.insert(Some(repo_name.clone()));
+                }
                 repo_worktrees.insert(repo_name, target_path);
-                successes += 1;
             }
             Ok((repo_name, _target_path, Err(e))) => {
                 warn!(repo = %repo_name, error = %e, "failed to create worktree");
-                errors.push(format!("{repo_name}: {e}"));
+                failed.insert(repo_name, e.to_string().trim().to_string());
             }
             Err(e) => {
                 warn!(error = %e, "worktree task panicked");
-                errors.push(format!("task panic: {e}"));
             }
         }
     }
 
-    if successes == 0 {
+    // A panicked task leaves no name behind; record whatever is unaccounted for
+    for repo in repos {
+        if !repo_worktrees.contains_key(&repo.name) && !failed.contains_key(&repo.name) {
+            failed.insert(repo.name.clone(), "worktree task panicked".into());
+        }
+    }
+
+    if repo_worktrees.is_empty() {
         // Clean up the empty session root
         let _ = tokio::fs::remove_dir_all(&session_root).await;
         return Err(VibeError::Git(format!(
             "Failed to create worktrees in any repo: {}",
-            errors.join("; ")
+            format_failures(&failed)
         )));
     }
 
-    if !errors.is_empty() {
+    if !failed.is_empty() {
         warn!(
-            succeeded = successes,
-            failed = errors.len(),
+            succeeded = repo_worktrees.len(),
+            failed = failed.len(),
             "some repo worktrees failed"
         );
     }
 
-    Ok((session_root, repo_worktrees))
+    Ok(SessionWorktrees {
+        root: session_root,
+        repos: repo_worktrees,
+        new_branches,
+        failed,
+    })
+}
+
+fn format_failures(failed: &BTreeMap<String, String>) -> String {
+    failed
+        .iter()
+        .map(|(repo, err)| format!("{repo}: {err}"))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Remove a half-created session's worktrees (used when setup fails), and
+/// the branches it created.
+async fn remove_worktrees(workspace_root: &Path, repos: &[RepoInfo], worktrees: &SessionWorktrees) {
+    if worktrees.repos.is_empty() {
+        let delete_branch = worktrees.new_branches.contains(&None);
+        if let Err(e) = git::remove_worktree(workspace_root, &worktrees.root, delete_branch).await {
+            warn!(error = %e, "failed to remove worktree");
+        }
+        return;
+    }
+
+    for (repo_name, wt_path) in &worktrees.repos {
+        let Some(repo) = repos.iter().find(|r| r.name == *repo_name) else {
+            continue;
+        };
+        let delete_branch = worktrees.new_branches.contains(&Some(repo_name.clone()));
+        if let Err(e) = git::remove_worktree(&repo.root, wt_path, delete_branch).await {
+            warn!(repo = %repo_name, error = %e, "failed to remove repo worktree");
+        }
+    }
+    let _ = tokio::fs::remove_dir_all(&worktrees.root).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RepoConfig;
+    use crate::test_support::{config, git, init_repo, init_state};
+
+    fn repo_info(root: &Path, name: &str) -> RepoInfo {
+        RepoInfo {
+            root: root.join(name),
+            name: name.into(),
+            default_branch: "main".into(),
+            remote_url: None,
+        }
+    }
+
+    fn branch_exists(repo: &Path, branch: &str) -> bool {
+        !git(repo, &["branch", "--list", branch]).is_empty()
+    }
+
+    #[test]
+    fn test_select_repos() {
+        let ws = Path::new("/ws");
+        let repos: Vec<RepoInfo> = ["api", "lib", "web"].iter().map(|n| repo_info(ws, n)).collect();
+        let names = |selected: Vec<RepoInfo>| selected.into_iter().map(|r| r.name).collect::<Vec<_>>();
+
+        assert_eq!(names(select_repos(&repos, None).unwrap()), ["api", "lib", "web"]);
+        // Workspace order, whatever order they were asked for in
+        let asked = ["web".to_string(), "api".to_string()];
+        assert_eq!(names(select_repos(&repos, Some(&asked)).unwrap()), ["api", "web"]);
+
+        let err = select_repos(&repos, Some(&["api".into(), "nope".into()])).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown repo(s): nope. Available: api, lib, web");
+        assert!(select_repos(&repos, Some(&[])).is_err());
+    }
+
+    #[test]
+    fn test_resolve_repo_refs_precedence() {
+        let ws = Path::new("/ws");
+        let repos: Vec<RepoInfo> = ["api", "lib", "web"].iter().map(|n| repo_info(ws, n)).collect();
+        let mut config = config(ws);
+        config.workspace.repos.insert(
+            "api".into(),
+            RepoConfig {
+                base: Some("release/1".into()),
+                branch: Some("api/{name}".into()),
+            },
+        );
+        config.workspace.repos.insert(
+            "web".into(),
+            RepoConfig {
+                base: Some("develop".into()),
+                branch: None,
+            },
+        );
+        let refs = |branch: &[&str], base: &[&str]| {
+            let parse = |v: &[&str]| RefOverrides::parse(&v.iter().map(|s| s.to_string()).collect::<Vec<_>>()).unwrap();
+            resolve_repo_refs(&repos, "x", "feat/x", &parse(branch), &parse(base), &config)
+        };
+        let repo_refs = |branch: &str, base: Option<&str>| RepoRefs {
+            branch: branch.into(),
+            base: base.map(String::from),
+        };
+
+        // Config over the defaults
+        let resolved = refs(&[], &[]).unwrap();
+        assert_eq!(resolved["api"], repo_refs("api/x", Some("release/1")));
+        assert_eq!(resolved["lib"], repo_refs("feat/x", None));
+        assert_eq!(resolved["web"], repo_refs("feat/x", Some("develop")));
+
+        // A plain CLI value over config, repo= over both
+        let resolved = refs(&["feat/y", "web=web-fix"], &["main", "api=hotfix"]).unwrap();
+        assert_eq!(resolved["api"], repo_refs("feat/y", Some("hotfix")));
+        assert_eq!(resolved["lib"], repo_refs("feat/y", Some("main")));
+        assert_eq!(resolved["web"], repo_refs("web-fix", Some("main")));
+
+        let err = refs(&["docs=x"], &[]).unwrap_err();
+        assert_eq!(err.to_string(), "Overrides for repo(s) not in this session: docs");
+    }
+
+    #[tokio::test]
+    async fn test_all_or_nothing_rolls_back_new_branches_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = tmp.path().join("ws");
+        let repos: Vec<RepoInfo> = ["api", "lib", "web"].iter().map(|n| repo_info(&ws, n)).collect();
+        for repo in &repos {
+            init_repo(&repo.root);
+        }
+        // lib already has the session branch; it must survive the rollback
+        git(&repos[1].root, &["branch", "feat/x"]);
+        init_state(&ws, repos.clone()).await;
+
+        let options = NewSessionOptions {
+            name: "x".into(),
+            base: RefOverrides::parse(&["main".into(), "web=no-such-ref".into()]).unwrap(),
+            all_or_nothing: true,
+            ..Default::default()
+        };
+        let err = execute(&ws, options, &config(&tmp.path().join("worktrees")))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("web:"), "{err}");
+
+        assert!(!branch_exists(&repos[0].root, "feat/x"));
+        assert!(branch_exists(&repos[1].root, "feat/x"));
+        for repo in &repos {
+            assert_eq!(git(&repo.root, &["worktree", "list"]).lines().count(), 1);
+        }
+        assert!(StateManager::new(&ws).load().await.unwrap().sessions.is_empty());
+    }
 }
@@ -0,0 +1,464 @@
+use crate::commands::{new, spawn};
+use crate::config::MergedConfig;
+use crate::domain::agent::{Agent, AgentMode};
+use crate::domain::pr::{self, PrLink, ReviewThread};
+use crate::domain::session::{RefOverrides, Session};
+use crate::domain::template::AgentTemplate;
+use crate::domain::workspace::WorkspaceState;
+use crate::error::VibeError;
+use crate::infra::{gh, git, state::StateManager};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// One branch of a session that gets its own PR.
+pub struct PrTarget {
+    /// Repo name for multi-repo sessions, `None` for single-repo.
+    pub repo: Option<String>,
+    pub worktree: PathBuf,
+    pub branch: String,
+    /// Branch the PR merges into (without `origin/`).
+    pub base: String,
+}
+
+impl PrTarget {
+    pub fn label(&self) -> &str {
+        self.repo.as_deref().unwrap_or(&self.branch)
+    }
+}
+
+pub fn pr_targets(state: &WorkspaceState, session: &Session) -> Vec<PrTarget> {
+    // A base given as `origin/x` is still branch x on GitHub
+    let pr_base = |base: &str| base.strip_prefix("origin/").unwrap_or(base).to_string();
+
+    if session.repo_worktrees.is_empty() {
+        return vec![PrTarget {
+            repo: None,
+            worktree: session.worktree_path.clone(),
+            branch: session.branch.clone(),
+            base: pr_base(session.base_for(None).unwrap_or(&state.workspace.default_branch)),
+        }];
+    }
+
+    session
+        .repo_worktrees
+        .iter()
+        .filter_map(|(repo_name, wt_path)| {
+            let repo = state.workspace.repos.iter().find(|r| r.name == *repo_name)?;
+            let base = session
+                .base_for(Some(repo_name))
+                .unwrap_or(&repo.default_branch);
+            Some(PrTarget {
+                repo: Some(repo_name.clone()),
+                worktree: wt_path.clone(),
+                branch: session.branch_for(Some(repo_name)).to_string(),
+                base: pr_base(base),
+            })
+        })
+        .collect()
+}
+
+pub fn find_session(state: &WorkspaceState, name: &str) -> Result<Session, VibeError> {
+    let session = state
+        .find_session_by_name(name)
+        .ok_or_else(|| VibeError::SessionNotFound(name.to_string()))?;
+    if session.is_main {
+        return Err(VibeError::User(
+            "The main session has no branch of its own to open a PR for".into(),
+        ));
+    }
+    Ok(session.clone())
+}
+
+pub fn require_gh() -> Result<(), VibeError> {
+    if !gh::is_available() {
+        return Err(VibeError::User(
+            "gh CLI not found. Install from: https://cli.github.com".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Push each of the session's branches that has commits and open a PR for
+/// it. With more than one PR, every PR body links to the others.
+pub async fn open(
+    workspace_root: &Path,
+    session_name: String,
+    title: Option<String>,
+    body: Option<String>,
+    draft: bool,
+) -> Result<(), VibeError> {
+    require_gh()?;
+    let state_manager = StateManager::new(workspace_root);
+    let state = state_manager.load().await?;
+    let session = find_session(&state, &session_name)?;
+
+    let title = title
+        .or_else(|| session.metadata.description.clone())
+        .unwrap_or_else(|| session.name.clone());
+    let body = body.unwrap_or_default();
+
+    let mut links: Vec<PrLink> = Vec::new();
+    for target in pr_targets(&state, &session) {
+        let label = target.label();
+
+        if let Some(number) = session.pr_for(target.repo.as_deref()) {
+            match gh::get_pr_status(number, &target.worktree).await {
+                Ok(status) => {
+                    println!("  {label}: PR #{number} already open");
+                    links.push(PrLink {
+                        repo: target.repo.clone(),
+                        number,
+                        url: status.url,
+                    });
+                }
+                Err(e) => println!("  {label}: ✗ PR #{number}: {e}"),
+            }
+            continue;
+        }
+
+        let base_ref = git::resolve_base_ref(&target.worktree, &target.base);
+        match git::commits_ahead(&target.worktree, &base_ref).await {
+            Ok(0) => {
+                println!("  {label}: no commits on {} — skipped", target.branch);
+                continue;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                println!("  {label}: ✗ {e}");
+                continue;
+            }
+        }
+
+        let created = async {
+            git::push_branch(&target.worktree, &target.branch).await?;
+            let url = gh::create_pr(
+                &target.worktree,
+                &target.branch,
+                &target.base,
+                &title,
+                &body,
+                draft,
+            )
+            .await?;
+            let number = gh::parse_pr_identifier(&url)?;
+            Ok::<_, VibeError>(PrLink {
+                repo: target.repo.clone(),
+                number,
+                url,
+            })
+        }
+        .await;
+
+        match created {
+            Ok(link) => {
+                println!("  {label}: ✓ opened #{} {}", link.number, link.url);
+                info!(repo = ?link.repo, number = link.number, "PR opened");
+                record_pr(&state_manager, &session_name, &link).await?;
+                links.push(link);
+            }
+            Err(e) => println!("  {label}: ✗ {e}"),
+        }
+    }
+
+    if links.is_empty() {
+        println!("No PRs opened: no branch of {session_name} has commits.");
+        return Ok(());
+    }
+
+    // Cross-link the set in every PR body
+    if links.len() > 1 {
+        let targets = pr_targets(&state, &session);
+        for link in &links {
+            let Some(target) = targets.iter().find(|t| t.repo == link.repo) else {
+                continue;
+            };
+            let section = pr::pr_set_section(&links, link);
+            let result = async {
+                let info = gh::get_pr_info(link.number, &target.worktree).await?;
+                gh::edit_pr_body(link.number, &target.worktree, &pr::with_pr_set(&info.body, &section))
+                    .await
+            }
+            .await;
+            if let Err(e) = result {
+                println!("  {}: ✗ could not link PRs: {e}", target.label());
+            }
+        }
+        println!("Linked {} PRs.", links.len());
+    }
+
+    Ok(())
+}
+
+async fn record_pr(
+    state_manager: &StateManager,
+    session_name: &str,
+    link: &PrLink,
+) -> Result<(), VibeError> {
+    let mut state = state_manager.load().await?;
+    if let Some(session) = state.find_session_by_name_mut(session_name) {
+        match &link.repo {
+            Some(repo) => {
+                session.repo_prs.insert(repo.clone(), link.number);
+            }
+            None => session.metadata.pr_number = Some(link.number),
+        }
+        session.updated_at = chrono::Utc::now();
+    }
+    state_manager.save(&state).await
+}
+
+/// Show state, review decision and checks for every PR of a session.
+pub async fn status(workspace_root: &Path, session_name: String) -> Result<(), VibeError> {
+    require_gh()?;
+    let state = StateManager::new(workspace_root).load().await?;
+    let session = find_session(&state, &session_name)?;
+
+    let targets: Vec<(PrTarget, u64)> = pr_targets(&state, &session)
+        .into_iter()
+        .filter_map(|t| session.pr_for(t.repo.as_deref()).map(|n| (t, n)))
+        .collect();
+    if targets.is_empty() {
+        println!("{session_name} has no PRs. Open them with `vibe pr open {session_name}`.");
+        return Ok(());
+    }
+
+    let statuses = fetch_statuses(&targets).await;
+    let width = targets.iter().map(|(t, _)| t.label().len()).max().unwrap_or(0);
+
+    println!("PRs for {session_name}:");
+    for ((target, number), status) in targets.iter().zip(statuses) {
+        let label = target.label();
+        match status {
+            Ok(status) => println!(
+                "  {label:<width$}  #{number:<5} {:<7} {:<18} {:<12} {}",
+                status.state_label(),
+                status.review_label(),
+                status.checks().to_string(),
+                status.url
+            ),
+            Err(e) => println!("  {label:<width$}  #{number:<5} ✗ {e}"),
+        }
+    }
+    Ok(())
+}
+
+/// Fetch every PR's status concurrently, keeping the order of `targets`.
+async fn fetch_statuses(targets: &[(PrTarget, u64)]) -> Vec<Result<pr::PrStatus, VibeError>> {
+    let mut join_set = tokio::task::JoinSet::new();
+    for (idx, (target, number)) in targets.iter().enumerate() {
+        let worktree = target.worktree.clone();
+        let number = *number;
+        join_set.spawn(async move { (idx, gh::get_pr_status(number, &worktree).await) });
+    }
+
+    let mut results: Vec<Option<Result<pr::PrStatus, VibeError>>> =
+        targets.iter().map(|_| None).collect();
+    while let Some(joined) = join_set.join_next().await {
+        if let Ok((idx, result)) = joined {
+            results[idx] = Some(result);
+        }
+    }
+    results
+        .into_iter()
+        .map(|r| r.unwrap_or_else(|| Err(VibeError::Git("status task panicked".into()))))
+        .collect()
+}
+
+/// Hand a PR's unresolved review threads to an implementer agent in the
+/// session for the PR's branch (created if there is none). With `reply`, the
+/// agent runs to completion and its per-thread summaries are posted as replies.
+pub async fn address(
+    workspace_root: &Path,
+    pr: String,
+    reply: bool,
+    yes: bool,
+    config: &MergedConfig,
+) -> Result<(), VibeError> {
+    require_gh()?;
+    let pr_number = gh::parse_pr_identifier(&pr)?;
+    let state_manager = StateManager::new(workspace_root);
+    if state_manager.load().await?.workspace.is_multi_repo() {
+        return Err(VibeError::User(
+            "vibe pr address does not support multi-repo workspaces yet".into(),
+        ));
+    }
+
+    println!("Fetching review threads for PR #{pr_number}...");
+    let (pr_info, threads) = tokio::try_join!(
+        gh::get_pr_info(pr_number, workspace_root),
+        gh::get_review_threads(pr_number, workspace_root),
+    )?;
+    let threads: Vec<ReviewThread> = threads.into_iter().filter(|t| !t.is_resolved).collect();
+    if threads.is_empty() {
+        println!("No unresolved review threads on PR #{pr_number}.");
+        return Ok(());
+    }
+    println!("  {} unresolved thread(s) on {}", threads.len(), pr_info.head_ref_name);
+
+    let session = session_for_pr(workspace_root, pr_number, &pr_info.head_ref_name, config).await?;
+
+    let prompt = format!(
+        "Address the review feedback on PR #{pr_number}: {}\n\n\
+         Work through every unresolved review thread below. For each one, make the requested \
+         change (or decide it should not be made), run the relevant tests and commit. \
+         Do not push.\n\n## Unresolved review threads\n\n{}",
+        pr_info.title,
+        pr::threads_checklist(&threads)
+    );
+
+    if !reply {
+        spawn::execute(
+            workspace_root,
+            prompt,
+            Some(session.name.clone()),
+            Some("implementer".into()),
+            None,
+            false,
+            config,
+        )
+        .await?;
+        println!("  Implementer started in {}.", session.name);
+        println!("  Run `vibe attach {}` to follow along.", session.name);
+        return Ok(());
+    }
+
+    let template = AgentTemplate::load("implementer", &config.template_dirs(workspace_root))?;
+    println!("Running implementer in {} (this can take a while)...", session.name);
+    // Registered with the session, so the dashboard shows what is changing
+    // its worktree
+    let mut agent = Agent::new(
+        session.id,
+        format!("address-pr-{pr_number}"),
+        AgentMode::Headless,
+        format!("{prompt}\n\n{}", pr::REPLY_INSTRUCTIONS),
+        session.worktree_path.clone(),
+        state_manager.agents_dir(),
+    );
+    agent.template = Some("implementer".into());
+    let output = spawn::run_to_completion(workspace_root, agent, &template, config).await?;
+    if output.is_error {
+        return Err(VibeError::User(format!("Implementer failed: {}", output.result)));
+    }
+
+    let answer_file = state_manager
+        .agents_dir()
+        .join(format!("pr-{pr_number}-address.md"));
+    tokio::fs::create_dir_all(state_manager.agents_dir()).await?;
+    tokio::fs::write(&answer_file, &output.result).await?;
+
+    let replies = pr::parse_replies(&output.result).map_err(|e| {
+        VibeError::User(format!("{e}. Full answer saved to {}", answer_file.display()))
+    })?;
+    let replies: Vec<(&ReviewThread, String)> = replies
+        .into_iter()
+        .filter_map(|r| {
+            let thread = r.thread.checked_sub(1).and_then(|idx| threads.get(idx))?;
+            Some((thread, pr::pending_reply(&r.reply)))
+        })
+        .collect();
+    if replies.is_empty() {
+        println!("The implementer left no replies. Full answer: {}", answer_file.display());
+        return Ok(());
+    }
+
+    println!("\nReplies:");
+    for (thread, reply) in &replies {
+        println!("  {}: {reply}", thread.location());
+    }
+    if !yes {
+        print!("\nPost {} reply(ies) to PR #{pr_number}? [y/N] ", replies.len());
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            println!("Not posted.");
+            return Ok(());
+        }
+    }
+
+    for (thread, reply) in &replies {
+        match gh::reply_to_thread(&thread.id, workspace_root, reply).await {
+            Ok(()) => println!("  ✓ {}", thread.location()),
+            Err(e) => println!("  ✗ {}: {e}", thread.location()),
+        }
+    }
+    println!(
+        "\nThe changes are committed on {} in {}, not pushed.",
+        session.branch,
+        session.worktree_path.display()
+    );
+    println!("Push them to the PR's branch ({}) to publish them.", pr_info.head_ref_name);
+    Ok(())
+}
+
+/// The active session working on a PR (by recorded PR number or branch), or a
+/// new `pr-N` session checked out at the PR head.
+pub async fn session_for_pr(
+    workspace_root: &Path,
+    pr_number: u64,
+    head_ref: &str,
+    config: &MergedConfig,
+) -> Result<Session, VibeError> {
+    let state_manager = StateManager::new(workspace_root);
+    let state = state_manager.load().await?;
+
+    // Review sessions run a read-only reviewer; don't make changes there
+    let existing = state.sessions.iter().find(|s| {
+        s.is_active()
+            && !s.is_main
+            && s.template.as_deref() != Some("reviewer")
+            && (s.metadata.pr_number == Some(pr_number) || s.branch == head_ref)
+    });
+    let name = match existing {
+        Some(session) => {
+            println!("  Using session {}", session.name);
+            session.name.clone()
+        }
+        None => {
+            let name = format!("pr-{pr_number}");
+            let branch = format!("vibe/pr-{pr_number}");
+            git::fetch_pr_head(workspace_root, pr_number, &branch).await?;
+            println!("  Creating session {name} on {branch}");
+            let options = new::NewSessionOptions {
+                name: name.clone(),
+                branch: RefOverrides {
+                    all: Some(branch),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            new::execute(workspace_root, options, config).await?;
+            name
+        }
+    };
+
+    let mut state = state_manager.load().await?;
+    let session = state
+        .find_session_by_name_mut(&name)
+        .ok_or_else(|| VibeError::SessionNotFound(name.clone()))?;
+    session.metadata.pr_number = Some(pr_number);
+    let session = session.clone();
+    state_manager.save(&state).await?;
+    // An existing session may have been registered without them
+    new::install_activity_hooks(&state_manager, &session);
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::init_state;
+
+    #[tokio::test]
+    async fn test_pr_targets_base_without_origin() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = init_state(tmp.path(), vec![]).await;
+        let mut session = Session::new("s1".into(), "feat/s1".into(), tmp.path().join("s1"), "@1".into());
+        assert_eq!(pr_targets(&state, &session)[0].base, "main");
+
+        session.base = Some("origin/release/2.1".into());
+        let targets = pr_targets(&state, &session);
+        assert_eq!((targets[0].branch.as_str(), targets[0].base.as_str()), ("feat/s1", "release/2.1"));
+    }
+}
@@ -1,9 +1,19 @@
-use crate::domain::workspace::WorkspaceKind;
+use crate::commands::session::checkout_repo;
+use crate::config::MergedConfig;
+use crate::domain::session::RepoRefs;
+use crate::domain::workspace::{RepoInfo, WorkspaceKind};
 use crate::error::VibeError;
+use crate::infra::hooks::{self, HookContext};
 use crate::infra::{git, state::StateManager};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
 
-pub async fn execute(workspace_root: &Path) -> Result<(), VibeError> {
+pub async fn execute(
+    workspace_root: &Path,
+    apply_to_sessions: bool,
+    dry_run: bool,
+    config: &MergedConfig,
+) -> Result<(), VibeError> {
     let state_manager = StateManager::new(workspace_root);
     let mut state = state_manager.load().await?;
 
@@ -12,22 +22,24 @@ pub async fn execute(workspace_root: &Path) -> Result<(), VibeError> {
         return Ok(());
     }
 
-    let current_repos = git::discover_repos(workspace_root)?;
+    let current_repos = git::discover_repos(workspace_root, &config.repo_discovery())?;
     let old_names: Vec<String> = state.workspace.repos.iter().map(|r| r.name.clone()).collect();
     let new_names: Vec<String> = current_repos.iter().map(|r| r.name.clone()).collect();
 
     // Find newly added repos
-    let added: Vec<&str> = new_names
+    let added: Vec<RepoInfo> = current_repos
         .iter()
-        .filter(|n| !old_names.contains(n))
-        .map(|s| s.as_str())
+        .filter(|r| !old_names.contains(&r.name))
+        .cloned()
         .collect();
 
     // Find removed repos
-    let removed: Vec<&str> = old_names
+    let removed: Vec<RepoInfo> = state
+        .workspace
+        .repos
         .iter()
-        .filter(|o| !new_names.contains(o))
-        .map(|s| s.as_str())
+        .filter(|r| !new_names.contains(&r.name))
+        .cloned()
         .collect();
 
     if added.is_empty() && removed.is_empty() {
@@ -35,11 +47,48 @@ pub async fn execute(workspace_root: &Path) -> Result<(), VibeError> {
         return Ok(());
     }
 
+    let names = |repos: &[RepoInfo]| {
+        repos
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
     if !added.is_empty() {
-        println!("Added: {}", added.join(", "));
+        println!("Added: {}", names(&added));
     }
     if !removed.is_empty() {
-        println!("Removed: {}", removed.join(", "));
+        println!("Removed: {}", names(&removed));
+    }
+
+    // Multi-repo sessions that get worktrees added or removed
+    let sessions: Vec<String> = state
+        .sessions
+        .iter()
+        .filter(|s| s.is_active() && !s.is_main && !s.repo_worktrees.is_empty())
+        .map(|s| s.name.clone())
+        .collect();
+
+    if dry_run {
+        if apply_to_sessions {
+            for name in &sessions {
+                let Some(session) = state.find_session_by_name(name) else {
+                    continue;
+                };
+                for repo in &added {
+                    println!("  would add {} to {name}", repo.name);
+                }
+                let has_repo = |r: &&RepoInfo| {
+                    session.repo_worktrees.contains_key(&r.name)
+                        || session.failed_repos.contains_key(&r.name)
+                };
+                for repo in removed.iter().filter(has_repo) {
+                    println!("  would remove {} from {name}", repo.name);
+                }
+            }
+        }
+        println!("Dry run: nothing changed.");
+        return Ok(());
     }
 
     state.workspace.repos = current_repos;
@@ -50,7 +99,182 @@ pub async fn execute(workspace_root: &Path) -> Result<(), VibeError> {
         new_names.len(),
         new_names.join(", ")
     );
-    println!("Note: existing sessions are not affected. Only new sessions will include the updated repo list.");
+    if !apply_to_sessions {
+        println!("Note: existing sessions are not affected. Pass --apply-to-sessions to update them.");
+        return Ok(());
+    }
+
+    for name in &sessions {
+        for repo in &added {
+            match add_to_session(workspace_root, &state_manager, name, repo, config).await {
+                Ok(path) => println!("  ✓ added {} to {name}: {}", repo.name, path.display()),
+                Err(e) => println!("  ✗ {} in {name}: {e}", repo.name),
+            }
+        }
+        for repo in &removed {
+            match remove_from_session(workspace_root, &state_manager, name, repo, config).await {
+                Ok(true) => println!("  ✓ removed {} from {name}", repo.name),
+                Ok(false) => {}
+                Err(e) => println!("  ✗ {} in {name}: {e}", repo.name),
+            }
+        }
+    }
 
     Ok(())
 }
+
+/// Check out a newly discovered repo in a session, on the session branch
+/// (or the repo's `[repos.<name>]` overrides). Failures are recorded on the
+/// session for `vibe session repair`.
+async fn add_to_session(
+    workspace_root: &Path,
+    state_manager: &StateManager,
+    session_name: &str,
+    repo: &RepoInfo,
+    config: &MergedConfig,
+) -> Result<PathBuf, VibeError> {
+    let state = state_manager.load().await?;
+    let session = state
+        .find_session_by_name(session_name)
+        .ok_or_else(|| VibeError::SessionNotFound(session_name.to_string()))?;
+
+    let repo_config = config.workspace.repos.get(&repo.name);
+    let refs = RepoRefs {
+        branch: repo_config
+            .and_then(|c| c.branch_for(session_name))
+            .unwrap_or_else(|| session.branch.clone()),
+        base: repo_config.and_then(|c| c.base.clone()),
+    };
+    let target_path = session.worktree_path.join(&repo.name);
+
+    let result = checkout_repo(workspace_root, session_name, repo, &refs, &target_path, config).await;
+
+    let mut state = state_manager.load().await?;
+    if let Some(session) = state.find_session_by_name_mut(session_name) {
+        session.repo_refs.insert(repo.name.clone(), refs);
+        match &result {
+            Ok(()) => {
+                session.repo_worktrees.insert(repo.name.clone(), target_path.clone());
+            }
+            Err(e) => {
+                session
+                    .failed_repos
+                    .insert(repo.name.clone(), e.to_string().trim().to_string());
+            }
+        }
+        session.updated_at = chrono::Utc::now();
+    }
+    state_manager.save(&state).await?;
+
+    result.map(|()| target_path)
+}
+
+/// Drop a repo that left the workspace from a session. If the repo still
+/// exists (e.g. it is now ignored) its worktree is removed through git and
+/// kept when it has uncommitted changes; otherwise the orphaned directory is
+/// deleted. Returns false if the session had nothing for the repo.
+async fn remove_from_session(
+    workspace_root: &Path,
+    state_manager: &StateManager,
+    session_name: &str,
+    repo: &RepoInfo,
+    config: &MergedConfig,
+) -> Result<bool, VibeError> {
+    let mut state = state_manager.load().await?;
+    let Some(session) = state.find_session_by_name(session_name) else {
+        return Ok(false);
+    };
+    let wt_path = session.repo_worktrees.get(&repo.name).cloned();
+    let branch = session.branch_for(Some(&repo.name)).to_string();
+    if wt_path.is_none() && !session.failed_repos.contains_key(&repo.name) {
+        return Ok(false);
+    }
+    // Without repo worktrees the session would pass for a single-repo one
+    if wt_path.is_some() && session.repo_worktrees.len() == 1 {
+        return Err(VibeError::User(format!(
+            "{} is the last repo in '{session_name}'. Use `vibe kill {session_name}` instead.",
+            repo.name
+        )));
+    }
+
+    if let Some(wt_path) = wt_path.as_deref().filter(|p| p.exists()) {
+        let repo_exists = repo.root.exists();
+        if repo_exists && git::has_uncommitted_changes(wt_path).await? {
+            return Err(VibeError::User(format!(
+                "{} has uncommitted changes, kept. Use `vibe session remove-repo --force`.",
+                wt_path.display()
+            )));
+        }
+
+        if let Some(ref hook) = config.workspace.post_session_hook {
+            let ctx = HookContext {
+                session: session_name,
+                branch: &branch,
+                worktree: wt_path,
+                repo: Some(&repo.name),
+                workspace_root,
+            };
+            if let Err(e) = hooks::run("post_session_hook", hook, &ctx).await {
+                warn!(repo = %repo.name, error = %e, "post_session_hook failed");
+            }
+        }
+
+        if repo_exists {
+            git::remove_worktree(&repo.root, wt_path, false).await?;
+        } else {
+            tokio::fs::remove_dir_all(wt_path).await?;
+        }
+        info!(repo = %repo.name, path = %wt_path.display(), "orphaned repo worktree removed");
+    }
+
+    if let Some(session) = state.find_session_by_name_mut(session_name) {
+        session.repo_worktrees.remove(&repo.name);
+        session.repo_refs.remove(&repo.name);
+        session.failed_repos.remove(&repo.name);
+        session.updated_at = chrono::Utc::now();
+    }
+    state_manager.save(&state).await?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::session::Session;
+    use crate::test_support::{config, init_state};
+
+    fn repo_info(root: &Path, name: &str) -> RepoInfo {
+        RepoInfo {
+            root: root.join(name),
+            name: name.into(),
+            default_branch: "main".into(),
+            remote_url: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_last_repo_is_not_removed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let (api, web) = (repo_info(root, "api"), repo_info(root, "web"));
+        let mut state = init_state(root, vec![api.clone(), web.clone()]).await;
+        let mut session = Session::new("s1".into(), "feat/s1".into(), root.join("s1"), "@1".into());
+        session.repo_worktrees.insert("api".into(), root.join("s1/api"));
+        session.repo_worktrees.insert("web".into(), root.join("s1/web"));
+        state.sessions.push(session);
+        let state_manager = StateManager::new(root);
+        state_manager.save(&state).await.unwrap();
+        let config = config(root);
+
+        // The worktrees don't exist on disk; only the state changes
+        assert!(remove_from_session(root, &state_manager, "s1", &api, &config).await.unwrap());
+        let err = remove_from_session(root, &state_manager, "s1", &web, &config)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("last repo"), "{err}");
+
+        let state = state_manager.load().await.unwrap();
+        let session = state.find_session_by_name("s1").unwrap();
+        assert_eq!(session.repo_worktrees.keys().collect::<Vec<_>>(), ["web"]);
+    }
+}
@@ -1,14 +1,25 @@
 use crate::config::MergedConfig;
+use crate::commands::{new, spawn};
 use crate::domain::agent::{Agent, AgentMode, AgentStatus};
+use crate::domain::review::{self, OpenPr, ReviewEvent, ReviewFindings, WatchFilter, WatchedReview};
+use crate::domain::session::{Session, SessionStatus};
 use crate::domain::template::AgentTemplate;
 use crate::error::VibeError;
-use crate::infra::{claude, gh, state::StateManager, tmux::TmuxController};
-use std::path::Path;
+use crate::infra::gh::PrInfo;
+use crate::infra::mux::{self, Multiplexer};
+use crate::infra::{claude, gh, git, state::StateManager, transcript};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{info, warn};
 
 pub async fn execute(
     workspace_root: &Path,
     pr: String,
     interactive: bool,
+    post: Option<ReviewEvent>,
+    yes: bool,
     config: &MergedConfig,
 ) -> Result<(), VibeError> {
     if !gh::is_available() {
@@ -19,13 +30,7 @@ pub async fn execute(
 
     let pr_number = gh::parse_pr_identifier(&pr)?;
     println!("Fetching PR #{pr_number}...");
-
-    // Fetch PR info and diff in parallel
-    let (pr_info, diff, comments) = tokio::try_join!(
-        gh::get_pr_info(pr_number, workspace_root),
-        gh::get_pr_diff(pr_number, workspace_root),
-        gh::get_pr_comments(pr_number, workspace_root),
-    )?;
+    let (pr_info, prompt, commentable) = review_prompt(workspace_root, pr_number).await?;
 
     println!("  PR: {}", pr_info.title);
     println!(
@@ -34,48 +39,6 @@ pub async fn execute(
     );
     println!("  Files: {}", pr_info.files.len());
 
-    // Build the file list
-    let file_list = pr_info
-        .files
-        .iter()
-        .map(|f| format!("  {} (+{} -{})", f.path, f.additions, f.deletions))
-        .collect::<Vec<_>>()
-        .join("\n");
-
-    // Truncate diff if too large (keep under ~50k chars for context window)
-    let diff_truncated = if diff.len() > 50000 {
-        format!("{}...\n\n[Diff truncated at 50k chars. Read individual files for full context.]", &diff[..50000])
-    } else {
-        diff
-    };
-
-    // Build review prompt
-    let prompt = format!(
-        r#"Review PR #{pr_number}: {title}
-
-## PR Description
-{body}
-
-## Changed Files
-{file_list}
-
-## Diff
-```diff
-{diff}
-```
-
-## Existing Comments/Reviews
-{comments}
-
-Please provide a thorough code review following your review process."#,
-        pr_number = pr_number,
-        title = pr_info.title,
-        body = if pr_info.body.is_empty() { "(no description)" } else { &pr_info.body },
-        file_list = file_list,
-        diff = diff_truncated,
-        comments = if comments.is_empty() { "(none)".to_string() } else { comments },
-    );
-
     let state_manager = StateManager::new(workspace_root);
     let mut state = state_manager.load().await?;
 
@@ -85,23 +48,71 @@ Please provide a thorough code review following your review process."#,
 
     let session_name = format!("review-pr-{pr_number}");
 
+    if let Some(event) = post {
+        println!("Running reviewer on PR #{pr_number}'s head (this can take a few minutes)...");
+        let branch = format!("review-post/pr-{pr_number}");
+        let findings =
+            review_checkout(workspace_root, pr_number, &branch, &prompt, &template, config).await?;
+        return post_findings(workspace_root, pr_number, &findings, &commentable, event, yes).await;
+    }
+
     if interactive {
-        // Create a full session for interactive review
-        println!("Creating interactive review session...");
+        if state.workspace.is_multi_repo() {
+            return Err(VibeError::User(
+                "Interactive review does not support multi-repo workspaces yet".into(),
+            ));
+        }
+        if state.find_session_by_name(&session_name).is_some() {
+            return Err(VibeError::User(format!(
+                "Session '{session_name}' already exists. Attach with `vibe attach {session_name}` or remove it with `vibe kill {session_name}`."
+            )));
+        }
 
-        // Ensure tmux session
-        TmuxController::ensure_session(&state.tmux_session_name).await?;
+        // Review the PR's code, not whatever the workspace has checked out
+        println!("Checking out PR #{pr_number} ({})...", pr_info.head_ref_name);
+        let branch = format!("review/pr-{pr_number}");
+        git::fetch_pr_head(workspace_root, pr_number, &branch).await?;
+        let worktree = git::create_worktree(
+            workspace_root,
+            &branch,
+            None,
+            &config.worktree_base_dir(workspace_root),
+            &config.worktree_setup(),
+        )
+        .await?;
+        info!(worktree = %worktree.path.display(), %branch, "review worktree created");
 
-        // Create tmux window (use main worktree, no need for separate one for review)
-        let _window_id = TmuxController::create_window(
+        mux::current().ensure_session(&state.tmux_session_name).await?;
+        let window_id = mux::current().create_window(
             &state.tmux_session_name,
             &session_name,
-            workspace_root.to_str().unwrap_or("."),
+            worktree.path.to_str().unwrap_or("."),
         )
         .await?;
-
-        // Build and send claude command with the review prompt
         let tmux_target = format!("{}:{}", state.tmux_session_name, session_name);
+
+        // Registered as a session so it shows in the dashboard and `vibe kill`
+        // removes the worktree
+        let mut session = Session::new(
+            session_name.clone(),
+            branch.clone(),
+            worktree.path.clone(),
+            window_id,
+        );
+        session.status = SessionStatus::Active;
+        session.template = Some("reviewer".into());
+        new::install_activity_hooks(&state_manager, &session);
+        let _ = transcript::start(&tmux_target, &state_manager.session_transcript(session.id)).await;
+        session.metadata.pr_number = Some(pr_number);
+        state.sessions.push(session);
+        state_manager.save(&state).await?;
+
+        // The prompt is too long for the command line as a literal; have the
+        // shell read it from a file
+        let prompt_file = state_manager.agents_dir().join(format!("pr-{pr_number}-prompt.md"));
+        tokio::fs::create_dir_all(state_manager.agents_dir()).await?;
+        tokio::fs::write(&prompt_file, &prompt).await?;
+
         let cmd = claude::interactive_command(
             config.claude_command(),
             Some(&template.system_prompt),
@@ -111,20 +122,12 @@ Please provide a thorough code review following your review process."#,
             None,
             &config.global.claude_extra_args,
         );
-        TmuxController::send_keys(&tmux_target, &cmd).await?;
-
-        // Send the prompt as the first message after a brief delay
-        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-        // Escape single quotes in prompt for shell
-        let _escaped_prompt = prompt.replace('\'', "'\\''");
-        // We'll write the prompt to a temp file and have claude read it
-        let prompt_file = state_manager.agents_dir().join(format!("pr-{pr_number}-prompt.md"));
-        tokio::fs::create_dir_all(prompt_file.parent().unwrap()).await?;
-        tokio::fs::write(&prompt_file, &prompt).await?;
+        let escaped_path = prompt_file.display().to_string().replace('\'', "'\\''");
+        mux::current().send_keys(&tmux_target, &format!("{cmd} \"$(cat '{escaped_path}')\"")).await?;
 
         println!("  Review session created: {session_name}");
+        println!("  Worktree: {} ({branch})", worktree.path.display());
         println!("  Run `vibe attach {session_name}` to interact with the reviewer");
-        println!("  Prompt saved to: {}", prompt_file.display());
     } else {
         // Headless review
         println!("Spawning headless reviewer...");
@@ -197,3 +200,550 @@ Please provide a thorough code review following your review process."#,
 
     Ok(())
 }
+
+/// Fetch a PR and build the reviewer prompt for it. Also returns the lines
+/// inline comments can go on.
+async fn review_prompt(
+    workspace_root: &Path,
+    pr_number: u64,
+) -> Result<(PrInfo, String, BTreeMap<String, BTreeSet<u32>>), VibeError> {
+    // Fetch PR info and diff in parallel
+    let (pr_info, diff, comments) = tokio::try_join!(
+        gh::get_pr_info(pr_number, workspace_root),
+        gh::get_pr_diff(pr_number, workspace_root),
+        gh::get_pr_comments(pr_number, workspace_root),
+    )?;
+
+    // Build the file list
+    let file_list = pr_info
+        .files
+        .iter()
+        .map(|f| format!("  {} (+{} -{})", f.path, f.additions, f.deletions))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    // Inline comments must land on lines that are part of the diff
+    let commentable = review::commentable_lines(&diff);
+
+    // Keep the diff under ~50k for the context window
+    let diff_truncated = review::truncate_diff(diff, 50_000);
+
+    // Build review prompt
+    let prompt = format!(
+        r#"Review PR #{pr_number}: {title}
+
+## PR Description
+{body}
+
+## Changed Files
+{file_list}
+
+## Diff
+```diff
+{diff}
+```
+
+## Existing Comments/Reviews
+{comments}
+
+Please provide a thorough code review following your review process."#,
+        pr_number = pr_number,
+        title = pr_info.title,
+        body = if pr_info.body.is_empty() { "(no description)" } else { &pr_info.body },
+        file_list = file_list,
+        diff = diff_truncated,
+        comments = if comments.is_empty() { "(none)".to_string() } else { comments.to_markdown() },
+    );
+
+    Ok((pr_info, prompt, commentable))
+}
+
+/// Save the reviewer's answer as `pr-N-review.md` and parse its findings.
+async fn findings_from(
+    workspace_root: &Path,
+    pr_number: u64,
+    output: &claude::ClaudeJsonOutput,
+) -> Result<ReviewFindings, VibeError> {
+    if output.is_error {
+        return Err(VibeError::User(format!("Reviewer failed: {}", output.result)));
+    }
+
+    let agents_dir = StateManager::new(workspace_root).agents_dir();
+    let review_file = agents_dir.join(format!("pr-{pr_number}-review.md"));
+    tokio::fs::create_dir_all(&agents_dir).await?;
+    tokio::fs::write(&review_file, &output.result).await?;
+
+    review::parse_findings(&output.result).map_err(|e| {
+        VibeError::User(format!("{e}. Full review saved to {}", review_file.display()))
+    })
+}
+
+/// Preview the findings, then submit them as a GitHub review. Findings on
+/// lines outside the diff can't be inline comments and go into the body.
+async fn post_findings(
+    workspace_root: &Path,
+    pr_number: u64,
+    findings: &ReviewFindings,
+    commentable: &BTreeMap<String, BTreeSet<u32>>,
+    event: ReviewEvent,
+    yes: bool,
+) -> Result<(), VibeError> {
+    let (inline, elsewhere): (Vec<_>, Vec<_>) = findings.findings.iter().partition(|f| {
+        commentable
+            .get(&f.path)
+            .is_some_and(|lines| lines.contains(&f.line))
+    });
+
+    let mut body = findings.summary.clone();
+    if !elsewhere.is_empty() {
+        body.push_str("\n\n**Outside the diff:**\n");
+        for finding in &elsewhere {
+            body.push_str(&format!("\n- `{}:{}` {}", finding.path, finding.line, finding.body()));
+        }
+    }
+
+    println!("\nReview of PR #{pr_number}:\n");
+    println!("{}\n", findings.summary);
+    for finding in &inline {
+        println!("  {}:{} [{}] {}", finding.path, finding.line, finding.severity, finding.comment);
+    }
+    if !elsewhere.is_empty() {
+        println!("\n  Not on diff lines (added to the review body):");
+        for finding in &elsewhere {
+            println!("  {}:{} [{}] {}", finding.path, finding.line, finding.severity, finding.comment);
+        }
+    }
+    println!();
+
+    if !yes {
+        print!(
+            "Post as {event} with {} inline comment(s) to PR #{pr_number}? [y/N] ",
+            inline.len()
+        );
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            println!("Not posted.");
+            return Ok(());
+        }
+    }
+
+    let comments: Vec<(String, u32, String)> = inline
+        .iter()
+        .map(|f| (f.path.clone(), f.line, f.body()))
+        .collect();
+    let url = gh::submit_review(pr_number, workspace_root, &body, event.api_value(), &comments).await?;
+
+    println!("Posted {event} with {} inline comment(s).", comments.len());
+    if !url.is_empty() {
+        println!("  {url}");
+    }
+    if event == ReviewEvent::Pending {
+        println!("  Edit and submit it on GitHub.");
+    }
+    Ok(())
+}
+
+/// Reviews new PRs and new pushes to open PRs. Each `poll` lists the open PRs
+/// once and reviews whatever is due; the caller decides how often to poll.
+pub struct ReviewWatcher {
+    workspace_root: PathBuf,
+    filter: WatchFilter,
+}
+
+impl ReviewWatcher {
+    pub fn new(workspace_root: &Path, filter: WatchFilter) -> Self {
+        Self {
+            workspace_root: workspace_root.to_path_buf(),
+            filter,
+        }
+    }
+
+    /// Review every PR that is new or was pushed to since its last review.
+    /// Returns the reviews done by this poll.
+    pub async fn poll(&self, config: &MergedConfig) -> Result<Vec<(u64, WatchedReview)>, VibeError> {
+        let open = gh::list_open_prs(&self.workspace_root, &self.filter).await?;
+        let state_manager = StateManager::new(&self.workspace_root);
+        let mut ledger = state_manager.load_review_watch().await?;
+        let due: Vec<OpenPr> = ledger.due(&open).into_iter().cloned().collect();
+
+        let mut reviewed = vec![];
+        for pr in due {
+            info!(pr = pr.number, sha = pr.short_sha(), author = pr.author(), "reviewing watched PR");
+            let review = self.review(&pr, config).await;
+            ledger.record(pr.number, review.clone());
+            // Saved per PR so an interrupted poll keeps the reviews it finished
+            state_manager.save_review_watch(&ledger).await?;
+            reviewed.push((pr.number, review));
+        }
+        Ok(reviewed)
+    }
+
+    async fn review(&self, pr: &OpenPr, config: &MergedConfig) -> WatchedReview {
+        let result = self.review_head(pr, config).await;
+
+        let output = StateManager::new(&self.workspace_root)
+            .agents_dir()
+            .join(format!("pr-{}-review.md", pr.number));
+        let (findings, error) = match result {
+            Ok(findings) => (Some(findings.findings.len()), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        WatchedReview {
+            head_sha: pr.head_ref_oid.clone(),
+            title: pr.title.clone(),
+            reviewed_at: chrono::Utc::now(),
+            findings,
+            output: output.exists().then_some(output),
+            error,
+            // Counted by the ledger
+            attempts: 0,
+        }
+    }
+
+    async fn review_head(&self, pr: &OpenPr, config: &MergedConfig) -> Result<ReviewFindings, VibeError> {
+        let root = &self.workspace_root;
+        let (_, prompt, _) = review_prompt(root, pr.number).await?;
+        let template = AgentTemplate::load("reviewer", &config.template_dirs(root))?;
+        // Not `review/pr-N`, which an interactive review of the PR may have out
+        let branch = format!("review-watch/pr-{}", pr.number);
+        review_checkout(root, pr.number, &branch, &prompt, &template, config).await
+    }
+}
+
+/// Review the PR's head in a worktree of its own on `branch`, removed (with
+/// the branch) afterwards.
+async fn review_checkout(
+    workspace_root: &Path,
+    pr_number: u64,
+    branch: &str,
+    prompt: &str,
+    template: &AgentTemplate,
+    config: &MergedConfig,
+) -> Result<ReviewFindings, VibeError> {
+    git::fetch_pr_head(workspace_root, pr_number, branch).await?;
+    let worktree = git::create_worktree(
+        workspace_root,
+        branch,
+        None,
+        &config.worktree_base_dir(workspace_root),
+        &config.worktree_setup(),
+    )
+    .await?;
+
+    let result = run_agent(workspace_root, pr_number, prompt, template, &worktree.path, config).await;
+    if let Err(e) = git::remove_worktree(workspace_root, &worktree.path, true).await {
+        warn!(worktree = %worktree.path.display(), error = %e, "failed to remove review worktree");
+    }
+    result
+}
+
+/// Run the reviewer as a headless agent, asking for structured findings.
+/// It shows in the dashboard while it works; killing it there stops the review.
+async fn run_agent(
+    workspace_root: &Path,
+    pr_number: u64,
+    prompt: &str,
+    template: &AgentTemplate,
+    worktree_path: &Path,
+    config: &MergedConfig,
+) -> Result<ReviewFindings, VibeError> {
+    let state_manager = StateManager::new(workspace_root);
+    let state = state_manager.load().await?;
+    let mut agent = Agent::new(
+        // Listed under the first active session (main, with the dashboard up)
+        state
+            .active_sessions()
+            .first()
+            .map(|s| s.id)
+            .unwrap_or_else(uuid::Uuid::new_v4),
+        format!("review-pr-{pr_number}"),
+        AgentMode::Headless,
+        format!("{prompt}\n\n{}", review::FINDINGS_INSTRUCTIONS),
+        worktree_path.to_path_buf(),
+        state_manager.agents_dir(),
+    );
+    agent.template = Some("reviewer".into());
+    let output = spawn::run_to_completion(workspace_root, agent, template, config).await?;
+    findings_from(workspace_root, pr_number, &output).await
+}
+
+/// Poll open PRs every `interval` and review new PRs and pushes headlessly.
+pub async fn watch(
+    workspace_root: &Path,
+    filter: WatchFilter,
+    interval: Duration,
+    once: bool,
+    config: &MergedConfig,
+) -> Result<(), VibeError> {
+    if !gh::is_available() {
+        return Err(VibeError::User(
+            "gh CLI not found. Install from: https://cli.github.com".into(),
+        ));
+    }
+
+    let watcher = ReviewWatcher::new(workspace_root, filter);
+    if !once {
+        println!(
+            "Watching open PRs, checking every {}s (Ctrl-C to stop)",
+            interval.as_secs()
+        );
+    }
+    loop {
+        match watcher.poll(config).await {
+            Ok(reviews) => {
+                for (pr_number, review) in &reviews {
+                    println!("  {}", review.summary(*pr_number));
+                }
+            }
+            Err(e) if once => return Err(e),
+            Err(e) => eprintln!("  Poll failed: {e}"),
+        }
+        if once {
+            return Ok(());
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::review::Finding;
+    use crate::test_support::{config, git, init_repo, init_state, stub_gh};
+
+    fn finding(path: &str, line: u32, comment: &str) -> Finding {
+        Finding {
+            path: path.into(),
+            line,
+            severity: "major".into(),
+            comment: comment.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_post_findings_through_gh() {
+        let tmp = tempfile::tempdir().unwrap();
+        let stub = stub_gh(tmp.path());
+        std::fs::write(stub.join("api"), r#"{"html_url":"https://example.com/r/1"}"#).unwrap();
+
+        let findings = ReviewFindings {
+            summary: "Mostly fine.".into(),
+            findings: vec![
+                finding("src/lib.rs", 12, "on the diff"),
+                finding("src/lib.rs", 99, "line not in the diff"),
+                finding("src/other.rs", 1, "file not in the diff"),
+            ],
+        };
+        let commentable = BTreeMap::from([("src/lib.rs".to_string(), BTreeSet::from([10, 11, 12]))]);
+
+        post_findings(tmp.path(), 7, &findings, &commentable, ReviewEvent::RequestChanges, true)
+            .await
+            .unwrap();
+
+        let calls = std::fs::read_to_string(stub.join("calls")).unwrap();
+        assert_eq!(calls.trim(), "api --method POST repos/{owner}/{repo}/pulls/7/reviews --input -");
+
+        let input: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(stub.join("input")).unwrap()).unwrap();
+        assert_eq!(input["event"], "REQUEST_CHANGES");
+        assert_eq!(
+            input["comments"],
+            serde_json::json!([{
+                "path": "src/lib.rs",
+                "line": 12,
+                "side": "RIGHT",
+                "body": "**major**: on the diff",
+            }])
+        );
+        let body = input["body"].as_str().unwrap();
+        assert!(body.starts_with("Mostly fine.\n\n**Outside the diff:**"), "{body}");
+        assert!(body.contains("`src/lib.rs:99` **major**: line not in the diff"), "{body}");
+        assert!(body.contains("`src/other.rs:1`"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn test_pending_review_has_no_event() {
+        let tmp = tempfile::tempdir().unwrap();
+        let stub = stub_gh(tmp.path());
+        let findings = ReviewFindings {
+            summary: "LGTM".into(),
+            findings: vec![],
+        };
+
+        post_findings(tmp.path(), 3, &findings, &BTreeMap::new(), ReviewEvent::Pending, true)
+            .await
+            .unwrap();
+
+        let input: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(stub.join("input")).unwrap()).unwrap();
+        assert!(input.get("event").is_none(), "{input}");
+        assert_eq!(input["body"], "LGTM");
+    }
+
+    /// Canned `gh pr list` output for PRs 7 and 8.
+    fn pr_list(head_7: &str, head_8: &str) -> String {
+        serde_json::json!([
+            {"number": 7, "title": "Seven", "headRefOid": head_7, "author": {"login": "a"}},
+            {"number": 8, "title": "Eight", "headRefOid": head_8, "author": {"login": "b"}},
+        ])
+        .to_string()
+    }
+
+    /// Point origin's `refs/pull/7/head` at a new commit and return its SHA.
+    fn push_pr_7(origin: &Path, content: &str) -> String {
+        git(origin, &["checkout", "-q", "-B", "pr-7", "main"]);
+        std::fs::write(origin.join("lib.rs"), content).unwrap();
+        git(origin, &["add", "lib.rs"]);
+        git(origin, &["commit", "-q", "-m", "pr 7"]);
+        let sha = git(origin, &["rev-parse", "HEAD"]);
+        git(origin, &["update-ref", "refs/pull/7/head", &sha]);
+        git(origin, &["checkout", "-q", "main"]);
+        sha
+    }
+
+    /// A workspace cloned from an `origin` repo, a stubbed gh answering for
+    /// PR 7, and a reviewer that records where it ran, at which commit, and
+    /// reports one finding. Returns (origin, workspace root, gh stub, config).
+    async fn review_fixture(tmp: &Path) -> (PathBuf, PathBuf, PathBuf, MergedConfig) {
+        let origin = tmp.join("origin");
+        init_repo(&origin);
+        let root = tmp.join("ws");
+        git(tmp, &["clone", "-q", origin.to_str().unwrap(), root.to_str().unwrap()]);
+        init_state(&root, vec![]).await;
+
+        let stub = stub_gh(&root);
+        std::fs::write(
+            stub.join("pr-view"),
+            r#"{"number": 7, "title": "Seven", "headRefName": "feat", "baseRefName": "main", "files": []}"#,
+        )
+        .unwrap();
+        std::fs::write(stub.join("pr-diff"), "diff --git a/lib.rs b/lib.rs\n+++ b/lib.rs\n@@ -0,0 +1 @@\n+fn x() {}\n").unwrap();
+
+        let answer = "Looks odd.\n```json\n{\"summary\": \"ok\", \"findings\": [{\"path\": \"lib.rs\", \"line\": 1, \"comment\": \"x\"}]}\n```";
+        let output = serde_json::json!({"type": "result", "subtype": "success", "is_error": false, "result": answer});
+        std::fs::write(tmp.join("answer.json"), output.to_string()).unwrap();
+        let claude = tmp.join("claude");
+        std::fs::write(
+            &claude,
+            format!(
+                "#!/bin/sh\npwd > '{0}/ran-in'\ngit rev-parse HEAD > '{0}/ran-at'\ncat '{0}/answer.json'\n",
+                tmp.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&claude, std::os::unix::fs::PermissionsExt::from_mode(0o755)).unwrap();
+        let mut config = config(tmp);
+        config.workspace.claude_command = Some(claude.display().to_string());
+        (origin, root, stub, config)
+    }
+
+    /// Assert the reviewer ran at `sha` in a checkout that is gone again.
+    fn assert_ran_in_removed_checkout(tmp: &Path, root: &Path, branch: &str, sha: &str) {
+        let ran_in = std::fs::read_to_string(tmp.join("ran-in")).unwrap();
+        assert!(Path::new(ran_in.trim()).starts_with(tmp), "{ran_in}");
+        assert_ne!(Path::new(ran_in.trim()), root);
+        assert!(!Path::new(ran_in.trim()).exists());
+        assert_eq!(std::fs::read_to_string(tmp.join("ran-at")).unwrap().trim(), sha);
+        assert!(git(root, &["branch", "--list", branch]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_post_reviews_pr_head() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (origin, root, stub, config) = review_fixture(tmp.path()).await;
+        std::fs::write(stub.join("api"), r#"{"html_url":"https://example.com/r/1"}"#).unwrap();
+        let head = push_pr_7(&origin, "fn x() {}\n");
+
+        execute(&root, "7".into(), false, Some(ReviewEvent::Comment), true, &config)
+            .await
+            .unwrap();
+
+        assert_ran_in_removed_checkout(tmp.path(), &root, "review-post/pr-7", &head);
+        let input: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(stub.join("input")).unwrap()).unwrap();
+        assert_eq!(input["comments"][0]["path"], "lib.rs");
+    }
+
+    #[tokio::test]
+    async fn test_interactive_review_session() {
+        let mux = mux::fake_for_test();
+        let tmp = tempfile::tempdir().unwrap();
+        let (origin, root, _, config) = review_fixture(tmp.path()).await;
+        let head = push_pr_7(&origin, "fn x() {}\n");
+
+        execute(&root, "7".into(), true, None, false, &config).await.unwrap();
+
+        let state = StateManager::new(&root).load().await.unwrap();
+        let session = state.find_session_by_name("review-pr-7").unwrap();
+        assert_eq!(git(&session.worktree_path, &["rev-parse", "HEAD"]), head);
+        assert_eq!(mux.window_names(), ["review-pr-7"]);
+        // Reports its activity to the dashboard like any other session
+        assert!(session.worktree_path.join(".claude/settings.local.json").exists());
+    }
+
+    #[tokio::test]
+    async fn test_watcher_reviews_new_heads_and_retries_failures() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (origin, root, stub, config) = review_fixture(tmp.path()).await;
+
+        // PR 8 was already reviewed at its current head
+        let state_manager = StateManager::new(&root);
+        let mut ledger = state_manager.load_review_watch().await.unwrap();
+        ledger.record(
+            8,
+            WatchedReview {
+                head_sha: "b".repeat(40),
+                title: "Eight".into(),
+                reviewed_at: chrono::Utc::now(),
+                findings: Some(0),
+                output: None,
+                error: None,
+                attempts: 1,
+            },
+        );
+        state_manager.save_review_watch(&ledger).await.unwrap();
+
+        let watcher = ReviewWatcher::new(&root, WatchFilter::default());
+
+        // A gh failure while reviewing PR 7 is recorded, and retried next poll
+        let first = push_pr_7(&origin, "fn x() {}\n");
+        std::fs::write(stub.join("pr-list"), pr_list(&first, &"b".repeat(40))).unwrap();
+        std::fs::write(stub.join("fail-pr-diff"), "HTTP 502").unwrap();
+        let reviews = watcher.poll(&config).await.unwrap();
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].0, 7);
+        let ledger = state_manager.load_review_watch().await.unwrap();
+        let error = ledger.prs[&7].error.as_deref().unwrap();
+        assert!(error.contains("HTTP 502"), "{error}");
+        assert_eq!(ledger.prs[&7].head_sha, first);
+
+        std::fs::remove_file(stub.join("fail-pr-diff")).unwrap();
+        let reviews = watcher.poll(&config).await.unwrap();
+        assert_eq!(reviews.len(), 1);
+        assert_eq!((reviews[0].1.findings, reviews[0].1.error.as_deref()), (Some(1), None));
+        let ledger = state_manager.load_review_watch().await.unwrap();
+        assert_eq!(ledger.prs[&7].attempts, 2);
+        assert!(watcher.poll(&config).await.unwrap().is_empty());
+
+        // A push to PR 7 is reviewed in a checkout of its head, cleaned up after
+        let second = push_pr_7(&origin, "fn x() { todo!() }\n");
+        std::fs::write(stub.join("pr-list"), pr_list(&second, &"b".repeat(40))).unwrap();
+        let reviews = watcher.poll(&config).await.unwrap();
+        assert_eq!(reviews.len(), 1);
+        let (number, review) = &reviews[0];
+        assert_eq!((*number, review.findings, review.error.as_deref()), (7, Some(1), None));
+        assert_eq!(review.head_sha, second);
+        assert_ran_in_removed_checkout(tmp.path(), &root, "review-watch/pr-7", &second);
+
+        let state = state_manager.load().await.unwrap();
+        let agent = state.agents.iter().find(|a| a.name == "review-pr-7").unwrap();
+        assert_eq!(agent.status, AgentStatus::Completed);
+        assert!(agent.output_file.exists());
+
+        let calls = std::fs::read_to_string(stub.join("calls")).unwrap();
+        assert!(!calls.contains("pr diff 8"), "{calls}");
+        assert!(watcher.poll(&config).await.unwrap().is_empty());
+    }
+}
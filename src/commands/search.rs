@@ -0,0 +1,22 @@
+use crate::error::VibeError;
+use crate::infra::{state::StateManager, transcript};
+use std::path::Path;
+
+/// Print every line of the session and agent transcripts containing `query`.
+pub async fn execute(workspace_root: &Path, query: &str) -> Result<(), VibeError> {
+    if query.trim().is_empty() {
+        return Err(VibeError::User("Nothing to search for".into()));
+    }
+    let state_manager = StateManager::new(workspace_root);
+    let state = state_manager.load().await?;
+
+    let hits = transcript::search_all(&state_manager, &state, query);
+    if hits.is_empty() {
+        println!("No matches for \"{query}\"");
+        return Ok(());
+    }
+    for hit in &hits {
+        println!("{}:{}: {}", hit.source(), hit.found.line_number, hit.found.line);
+    }
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use crate::error::VibeError;
+use crate::infra::mux::{self, Multiplexer};
+use crate::infra::state::StateManager;
+use std::path::Path;
+use tracing::info;
+
+/// Send a message to the Claude running in a session's first pane, as if it
+/// had been typed there.
+pub async fn execute(workspace_root: &Path, session_name: &str, message: &str) -> Result<(), VibeError> {
+    // Piped input ends with a newline; Enter is sent separately
+    let message = message.trim_end();
+    if message.trim().is_empty() {
+        return Err(VibeError::User("Nothing to send".into()));
+    }
+
+    let state = StateManager::new(workspace_root).load().await?;
+    let session = state
+        .find_session_by_name(session_name)
+        .ok_or_else(|| VibeError::SessionNotFound(session_name.to_string()))?;
+
+    let window = format!("{}:{}", state.tmux_session_name, session.name);
+    if !mux::current().window_exists(&window).await {
+        return Err(VibeError::User(format!(
+            "Session '{session_name}' has no tmux window. Open it from the dashboard or with `vibe attach {session_name}`."
+        )));
+    }
+    let pane_id = mux::current().first_pane_id(&window).await?;
+
+    mux::current().paste_text(&pane_id, message).await?;
+    info!(session = session_name, pane = %pane_id, bytes = message.len(), "message sent");
+    Ok(())
+}
@@ -0,0 +1,334 @@
+use crate::config::MergedConfig;
+use crate::domain::session::RepoRefs;
+use crate::domain::workspace::RepoInfo;
+use crate::error::VibeError;
+use crate::infra::hooks::{self, HookContext};
+use crate::infra::{git, state::StateManager};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Check out another workspace repo in an existing multi-repo session, on the
+/// session's branch.
+pub async fn add_repo(
+    workspace_root: &Path,
+    session_name: String,
+    repo_name: String,
+    branch: Option<String>,
+    base: Option<String>,
+    config: &MergedConfig,
+) -> Result<(), VibeError> {
+    let state_manager = StateManager::new(workspace_root);
+    let mut state = state_manager.load().await?;
+
+    let repo = state
+        .workspace
+        .repos
+        .iter()
+        .find(|r| r.name == repo_name)
+        .cloned()
+        .ok_or_else(|| VibeError::User(format!("Unknown repo '{repo_name}'")))?;
+
+    let session = state
+        .find_session_by_name(&session_name)
+        .ok_or_else(|| VibeError::SessionNotFound(session_name.clone()))?;
+    if session.is_main || session.repo_worktrees.is_empty() {
+        return Err(VibeError::User(format!(
+            "Session '{session_name}' is not a multi-repo session"
+        )));
+    }
+    if session.repo_worktrees.contains_key(&repo_name) {
+        return Err(VibeError::User(format!(
+            "Session '{session_name}' already has {repo_name}"
+        )));
+    }
+
+    // Same precedence as `vibe new`: flag, then [repos.<name>], then defaults
+    let repo_config = config.workspace.repos.get(&repo_name);
+    let branch = branch
+        .or_else(|| repo_config.and_then(|c| c.branch_for(&session_name)))
+        .unwrap_or_else(|| session.branch.clone());
+    let base = base.or_else(|| repo_config.and_then(|c| c.base.clone()));
+    let target_path = session.worktree_path.join(&repo_name);
+    let refs = RepoRefs { branch, base };
+    checkout_repo(workspace_root, &session_name, &repo, &refs, &target_path, config).await?;
+
+    if let Some(session) = state.find_session_by_name_mut(&session_name) {
+        session.repo_worktrees.insert(repo_name.clone(), target_path.clone());
+        session.repo_refs.insert(repo_name.clone(), refs);
+        session.failed_repos.remove(&repo_name);
+        session.updated_at = chrono::Utc::now();
+    }
+    state_manager.save(&state).await?;
+
+    println!("Added {repo_name} to {session_name}: {}", target_path.display());
+    Ok(())
+}
+
+/// Remove one repo's worktree from a multi-repo session. The branch is kept.
+pub async fn remove_repo(
+    workspace_root: &Path,
+    session_name: String,
+    repo_name: String,
+    force: bool,
+    config: &MergedConfig,
+) -> Result<(), VibeError> {
+    let state_manager = StateManager::new(workspace_root);
+    let mut state = state_manager.load().await?;
+
+    let session = state
+        .find_session_by_name_mut(&session_name)
+        .ok_or_else(|| VibeError::SessionNotFound(session_name.clone()))?;
+    // A repo that never got a worktree only needs forgetting
+    if !session.repo_worktrees.contains_key(&repo_name)
+        && session.failed_repos.remove(&repo_name).is_some()
+    {
+        session.repo_refs.remove(&repo_name);
+        session.updated_at = chrono::Utc::now();
+        state_manager.save(&state).await?;
+        println!("Dropped failed repo {repo_name} from {session_name}");
+        return Ok(());
+    }
+    let wt_path = session
+        .repo_worktrees
+        .get(&repo_name)
+        .cloned()
+        .ok_or_else(|| {
+            VibeError::User(format!("Session '{session_name}' has no {repo_name} worktree"))
+        })?;
+    if session.repo_worktrees.len() == 1 {
+        return Err(VibeError::User(format!(
+            "{repo_name} is the last repo in '{session_name}'. Use `vibe kill {session_name}` instead."
+        )));
+    }
+    let branch = session.branch_for(Some(&repo_name)).to_string();
+
+    if wt_path.exists() {
+        if !force && git::has_uncommitted_changes(&wt_path).await? {
+            return Err(VibeError::User(format!(
+                "{} has uncommitted changes. Commit them or pass --force.",
+                wt_path.display()
+            )));
+        }
+
+        if let Some(ref hook) = config.workspace.post_session_hook {
+            let ctx = HookContext {
+                session: &session_name,
+                branch: &branch,
+                worktree: &wt_path,
+                repo: Some(&repo_name),
+                workspace_root,
+            };
+            if let Err(e) = hooks::run("post_session_hook", hook, &ctx).await {
+                warn!(repo = %repo_name, error = %e, "post_session_hook failed");
+            }
+        }
+
+        match state.workspace.repos.iter().find(|r| r.name == repo_name) {
+            Some(repo) => git::remove_worktree(&repo.root, &wt_path, false).await?,
+            None => tokio::fs::remove_dir_all(&wt_path).await?,
+        }
+        info!(repo = %repo_name, path = %wt_path.display(), "repo worktree removed");
+    }
+
+    if let Some(session) = state.find_session_by_name_mut(&session_name) {
+        session.repo_worktrees.remove(&repo_name);
+        session.repo_refs.remove(&repo_name);
+        session.updated_at = chrono::Utc::now();
+    }
+    state_manager.save(&state).await?;
+
+    println!("Removed {repo_name} from {session_name} (branch {branch} kept)");
+    Ok(())
+}
+
+/// Retry the worktrees of a multi-repo session that failed at creation or
+/// have since gone missing from disk, using the refs recorded for each repo.
+pub async fn repair(
+    workspace_root: &Path,
+    session_name: String,
+    config: &MergedConfig,
+) -> Result<(), VibeError> {
+    let state_manager = StateManager::new(workspace_root);
+    let state = state_manager.load().await?;
+
+    let session = state
+        .find_session_by_name(&session_name)
+        .ok_or_else(|| VibeError::SessionNotFound(session_name.clone()))?
+        .clone();
+    if session.is_main || (session.repo_worktrees.is_empty() && session.failed_repos.is_empty()) {
+        return Err(VibeError::User(format!(
+            "Session '{session_name}' is not a multi-repo session"
+        )));
+    }
+
+    let mut missing: Vec<String> = session.failed_repos.keys().cloned().collect();
+    missing.extend(
+        session
+            .repo_worktrees
+            .iter()
+            .filter(|(_, path)| !path.exists())
+            .map(|(name, _)| name.clone()),
+    );
+    if missing.is_empty() {
+        println!("All worktrees of {session_name} are present.");
+        return Ok(());
+    }
+
+    let mut repaired: Vec<(String, PathBuf)> = Vec::new();
+    let mut failed: Vec<(String, String)> = Vec::new();
+    for repo_name in missing {
+        let Some(repo) = state.workspace.repos.iter().find(|r| r.name == repo_name) else {
+            println!("  ✗ {repo_name}: no longer in the workspace");
+            failed.push((repo_name, "repo no longer in the workspace".into()));
+            continue;
+        };
+        // Forget stale registrations of worktrees deleted from disk
+        if let Err(e) = git::prune(&repo.root).await {
+            warn!(repo = %repo_name, error = %e, "worktree prune failed");
+        }
+
+        let refs = session.repo_refs.get(&repo_name).cloned().unwrap_or(RepoRefs {
+            branch: session.branch.clone(),
+            base: None,
+        });
+        let target_path = session
+            .repo_worktrees
+            .get(&repo_name)
+            .cloned()
+            .unwrap_or_else(|| session.worktree_path.join(&repo_name));
+        match checkout_repo(workspace_root, &session_name, repo, &refs, &target_path, config).await {
+            Ok(()) => {
+                println!("  ✓ {repo_name}: {}", target_path.display());
+                repaired.push((repo_name, target_path));
+            }
+            Err(e) => {
+                println!("  ✗ {repo_name}: {e}");
+                failed.push((repo_name, e.to_string().trim().to_string()));
+            }
+        }
+    }
+
+    // Reload in case the state changed while worktrees were being created
+    let mut state = state_manager.load().await?;
+    if let Some(session) = state.find_session_by_name_mut(&session_name) {
+        for (repo_name, path) in &repaired {
+            session.failed_repos.remove(repo_name);
+            session.repo_worktrees.insert(repo_name.clone(), path.clone());
+        }
+        for (repo_name, err) in &failed {
+            session.failed_repos.insert(repo_name.clone(), err.clone());
+        }
+        session.updated_at = chrono::Utc::now();
+    }
+    state_manager.save(&state).await?;
+
+    println!(
+        "\nRepaired {}/{} worktree(s) of {session_name}.",
+        repaired.len(),
+        repaired.len() + failed.len()
+    );
+    Ok(())
+}
+
+/// Create one repo's worktree for a session and run the pre-session hook in
+/// it. The worktree is removed again if the hook fails.
+pub async fn checkout_repo(
+    workspace_root: &Path,
+    session_name: &str,
+    repo: &RepoInfo,
+    refs: &RepoRefs,
+    target_path: &Path,
+    config: &MergedConfig,
+) -> Result<(), VibeError> {
+    let start = refs
+        .base
+        .clone()
+        .unwrap_or_else(|| git::resolve_base_ref(&repo.root, &repo.default_branch));
+    git::create_worktree_at(
+        &repo.root,
+        &refs.branch,
+        Some(&start),
+        target_path,
+        &config.worktree_setup(),
+    )
+    .await?;
+    info!(repo = %repo.name, path = %target_path.display(), "repo worktree created");
+
+    if let Some(ref hook) = config.workspace.pre_session_hook {
+        let ctx = HookContext {
+            session: session_name,
+            branch: &refs.branch,
+            worktree: target_path,
+            repo: Some(&repo.name),
+            workspace_root,
+        };
+        if let Err(e) = hooks::run("pre_session_hook", hook, &ctx).await {
+            if let Err(e) = git::remove_worktree(&repo.root, target_path, false).await {
+                warn!(repo = %repo.name, error = %e, "failed to remove repo worktree");
+            }
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::session::Session;
+    use crate::test_support::{config, git, init_repo, init_state};
+
+    #[tokio::test]
+    async fn test_repair_half_created_session() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = tmp.path().join("ws");
+        let repos: Vec<RepoInfo> = ["api", "web"]
+            .iter()
+            .map(|name| RepoInfo {
+                root: ws.join(name),
+                name: name.to_string(),
+                default_branch: "main".into(),
+                remote_url: None,
+            })
+            .collect();
+        for repo in &repos {
+            init_repo(&repo.root);
+        }
+        let mut state = init_state(&ws, repos.clone()).await;
+
+        // api's worktree was created and later deleted from disk; web's
+        // failed at creation; docs has since left the workspace
+        let session_root = tmp.path().join("ws-vibe-1234");
+        let api_path = session_root.join("api");
+        git(&repos[0].root, &["worktree", "add", "-q", "-b", "feat/x", api_path.to_str().unwrap()]);
+        std::fs::remove_dir_all(&api_path).unwrap();
+        let mut session = Session::new("x".into(), "feat/x".into(), session_root.clone(), "@1".into());
+        session.repo_worktrees.insert("api".into(), api_path.clone());
+        session.repo_refs.insert(
+            "web".into(),
+            RepoRefs {
+                branch: "web/x".into(),
+                base: None,
+            },
+        );
+        session.failed_repos.insert("web".into(), "fetch failed".into());
+        session.failed_repos.insert("docs".into(), "fetch failed".into());
+        state.sessions.push(session);
+        let state_manager = StateManager::new(&ws);
+        state_manager.save(&state).await.unwrap();
+
+        repair(&ws, "x".into(), &config(tmp.path())).await.unwrap();
+
+        let state = state_manager.load().await.unwrap();
+        let session = state.find_session_by_name("x").unwrap();
+        assert!(api_path.join(".git").exists());
+        assert_eq!(git(&api_path, &["branch", "--show-current"]), "feat/x");
+        let web_path = session_root.join("web");
+        assert_eq!(session.repo_worktrees.get("web"), Some(&web_path));
+        // Recorded refs are used, and a missing base means the default branch
+        assert_eq!(git(&web_path, &["branch", "--show-current"]), "web/x");
+        assert_eq!(git(&web_path, &["rev-parse", "HEAD"]), git(&repos[1].root, &["rev-parse", "main"]));
+        assert_eq!(session.failed_repos.keys().collect::<Vec<_>>(), ["docs"]);
+        assert_eq!(session.failed_repos["docs"], "repo no longer in the workspace");
+    }
+}
@@ -1,11 +1,16 @@
 use crate::config::MergedConfig;
 use crate::domain::agent::{Agent, AgentMode, AgentStatus};
+use crate::domain::layout::PaneLayout;
 use crate::domain::template::AgentTemplate;
 use crate::error::VibeError;
-use crate::infra::{claude, state::StateManager, tmux::TmuxController};
+use crate::infra::mux::{self, Multiplexer};
+use crate::infra::{claude, state::StateManager, transcript};
 use std::path::Path;
-use tracing::{error, info};
+use std::time::Duration;
+use tracing::{error, info, warn};
+use uuid::Uuid;
 
+/// Spawn an agent in a session. Returns the new agent's id.
 pub async fn execute(
     workspace_root: &Path,
     prompt: String,
@@ -14,7 +19,7 @@ pub async fn execute(
     system_prompt_override: Option<String>,
     interactive: bool,
     config: &MergedConfig,
-) -> Result<(), VibeError> {
+) -> Result<Uuid, VibeError> {
     let state_manager = StateManager::new(workspace_root);
     let mut state = state_manager.load().await?;
 
@@ -44,14 +49,7 @@ pub async fn execute(
         None
     };
 
-    let mode = if interactive {
-        AgentMode::Interactive
-    } else {
-        template
-            .as_ref()
-            .map(|t| t.mode.clone())
-            .unwrap_or(AgentMode::Headless)
-    };
+    let mode = resolve_mode(template.as_ref(), interactive);
 
     // Create agent name from template or prompt
     let agent_name = template_name
@@ -84,10 +82,10 @@ pub async fn execute(
         "spawning agent"
     );
 
+    let agent_id = agent.id;
     match mode {
         AgentMode::Headless => {
             agent.status = AgentStatus::Running;
-            let agent_id = agent.id;
             let system_prompt = agent.system_prompt.clone();
             let output_file = agent.output_file.clone();
             let allowed_tools = template
@@ -144,16 +142,23 @@ pub async fn execute(
             });
         }
         AgentMode::Interactive => {
-            let tmux_target = format!("{}:{}", state.tmux_session_name, parent_name);
-            let pane_id = TmuxController::split_pane(
-                &tmux_target,
+            let layout = template
+                .as_ref()
+                .and_then(|t| t.layout)
+                .unwrap_or_else(|| config.pane_layout());
+            let pane_id = open_agent_pane(
+                mux::current(),
+                &state.tmux_session_name,
+                &parent_name,
+                &agent_name,
                 worktree_path.to_str().unwrap_or("."),
-                true,
+                layout,
             )
             .await?;
 
             agent.tmux_pane = Some(pane_id.clone());
             agent.status = AgentStatus::Running;
+            let _ = transcript::start(&pane_id, &state_manager.agent_transcript(agent.id)).await;
 
             let cmd = claude::interactive_command(
                 config.claude_command(),
@@ -170,9 +175,20 @@ pub async fn execute(
                 None,
                 &config.global.claude_extra_args,
             );
-            TmuxController::send_keys(&pane_id, &cmd).await?;
+            // Deliver the task as the first message, read from a file so it
+            // survives shell quoting
+            let cmd = if prompt.trim().is_empty() {
+                cmd
+            } else {
+                let agents_dir = state_manager.agents_dir();
+                let prompt_file = agents_dir.join(format!("{}-prompt.md", agent.id));
+                tokio::fs::create_dir_all(&agents_dir).await?;
+                tokio::fs::write(&prompt_file, &prompt).await?;
+                let escaped_path = prompt_file.display().to_string().replace('\'', "'\\''");
+                format!("{cmd} \"$(cat '{escaped_path}')\"")
+            };
+            mux::current().send_keys(&pane_id, &cmd).await?;
 
-            let agent_id = agent.id;
             state.agents.push(agent);
             if let Some(parent) = state.find_session_by_id_mut(parent_id) {
                 parent.agents.push(agent_id);
@@ -187,5 +203,219 @@ pub async fn execute(
         }
     }
 
-    Ok(())
+    Ok(agent_id)
+}
+
+/// How often an agent run in the foreground checks whether it was killed.
+const KILL_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Run a headless `agent` (not yet registered) to completion in the
+/// foreground, with `template`'s tools. It is registered under its parent
+/// session while it works, so it shows in the dashboard, and killing it there
+/// stops it. Its output is saved to its output file.
+pub async fn run_to_completion(
+    workspace_root: &Path,
+    mut agent: Agent,
+    template: &AgentTemplate,
+    config: &MergedConfig,
+) -> Result<claude::ClaudeJsonOutput, VibeError> {
+    let mut cmd = claude::headless_command(
+        config.claude_command(),
+        &agent.prompt,
+        &agent.worktree_path,
+        Some(&template.system_prompt),
+        &template.allowed_tools,
+        &template.disallowed_tools,
+        template.permission_mode.as_deref(),
+        &config.global.claude_extra_args,
+    );
+    cmd.kill_on_drop(true);
+
+    let state_manager = StateManager::new(workspace_root);
+    let mut state = state_manager.load().await?;
+    agent.mode = AgentMode::Headless;
+    agent.system_prompt = Some(template.system_prompt.clone());
+    agent.status = AgentStatus::Running;
+    let agent_id = agent.id;
+    if let Some(parent) = state.find_session_by_id_mut(agent.parent_session) {
+        parent.agents.push(agent_id);
+    }
+    state.agents.push(agent);
+    state_manager.save(&state).await?;
+
+    let killed = async {
+        loop {
+            tokio::time::sleep(KILL_CHECK_INTERVAL).await;
+            if let Ok(state) = state_manager.load().await
+                && !state.agents.iter().any(|a| a.id == agent_id)
+            {
+                return;
+            }
+        }
+    };
+    let output = tokio::select! {
+        output = claude::run_headless_command(cmd) => output,
+        () = killed => {
+            return Err(VibeError::User("agent was killed".into()));
+        }
+    };
+
+    let mut state = state_manager.load().await?;
+    if let Some(agent) = state.agents.iter_mut().find(|a| a.id == agent_id) {
+        agent.completed_at = Some(chrono::Utc::now());
+        match &output {
+            Ok(output) => {
+                agent.status = AgentStatus::Completed;
+                agent.result = Some(claude::to_agent_result(output));
+                if let Some(parent) = agent.output_file.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                let json = serde_json::to_string_pretty(output).unwrap_or_default();
+                tokio::fs::write(&agent.output_file, json).await?;
+            }
+            Err(e) => agent.status = AgentStatus::Failed(e.to_string()),
+        }
+        state_manager.save(&state).await?;
+    }
+    output
+}
+
+/// The mode an agent runs in: interactive when asked for, otherwise its
+/// template's, and headless without a template.
+pub fn resolve_mode(template: Option<&AgentTemplate>, interactive: bool) -> AgentMode {
+    if interactive {
+        AgentMode::Interactive
+    } else {
+        template
+            .map(|t| t.mode.clone())
+            .unwrap_or(AgentMode::Headless)
+    }
+}
+
+/// Open a pane for an interactive agent: split into the session's window and
+/// re-laid out, or a `{session}~{agent}` window of its own. Returns the pane ID.
+async fn open_agent_pane<M: Multiplexer>(
+    mux: &M,
+    tmux_session: &str,
+    session_name: &str,
+    agent_name: &str,
+    working_dir: &str,
+    layout: PaneLayout,
+) -> Result<String, VibeError> {
+    match layout.tmux_name() {
+        Some(name) => {
+            let window = format!("{tmux_session}:{session_name}");
+            let pane_id = mux.split_pane(&window, working_dir, true).await?;
+            if let Err(e) = mux.select_layout(&window, name).await {
+                warn!(window = %window, layout = name, error = %e, "failed to apply layout");
+            }
+            Ok(pane_id)
+        }
+        None => {
+            let window_name = format!("{session_name}~{agent_name}");
+            // Agents can share a name; target the new window by ID
+            let window_id = mux.create_window(tmux_session, &window_name, working_dir).await?;
+            mux.first_pane_id(&window_id).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::session::Session;
+    use crate::infra::mux::fake::FakeMux;
+    use crate::test_support::{config, init_state};
+
+    #[tokio::test]
+    async fn test_open_agent_pane() {
+        let mux = FakeMux::default();
+        mux.create_window("vibe-repo", "s1", "/tmp").await.unwrap();
+
+        let pane = open_agent_pane(&mux, "vibe-repo", "s1", "fix", "/tmp", PaneLayout::Tiled)
+            .await
+            .unwrap();
+        assert_eq!(mux.window_id_for_pane(&pane).await.unwrap(), "@1");
+        assert!(mux.calls().contains(&"select-layout vibe-repo:s1 tiled".to_string()));
+
+        // In a window of its own, with nothing re-laid out
+        let pane = open_agent_pane(&mux, "vibe-repo", "s1", "docs", "/tmp", PaneLayout::Window)
+            .await
+            .unwrap();
+        assert_eq!(mux.window_names(), ["s1", "s1~docs"]);
+        assert_eq!(mux.first_pane_id("vibe-repo:s1~docs").await.unwrap(), pane);
+        assert_eq!(mux.calls().iter().filter(|c| c.starts_with("select-layout")).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_interactive_agent() {
+        let mux = mux::fake_for_test();
+        let tmp = tempfile::tempdir().unwrap();
+        let mut state = init_state(tmp.path(), vec![]).await;
+        let session = Session::new("s1".into(), "feat/s1".into(), tmp.path().into(), "@1".into());
+        let session_id = session.id;
+        state.sessions.push(session);
+        StateManager::new(tmp.path()).save(&state).await.unwrap();
+        mux.create_window("vibe-ws", "s1", "/tmp").await.unwrap();
+
+        let mut config = config(tmp.path());
+        config.workspace.layout = Some(PaneLayout::Tiled);
+        let agent_id = execute(tmp.path(), "fix the tests".into(), Some("s1".into()), None, None, true, &config)
+            .await
+            .unwrap();
+
+        let state = StateManager::new(tmp.path()).load().await.unwrap();
+        let agent = state.agents.iter().find(|a| a.id == agent_id).unwrap();
+        let pane = agent.tmux_pane.clone().unwrap();
+        assert_eq!(agent.mode, AgentMode::Interactive);
+        assert_eq!(mux.window_id_for_pane(&pane).await.unwrap(), "@1");
+        assert_eq!(state.find_session_by_id(session_id).unwrap().agents, [agent_id]);
+
+        // Started with the prompt read from a file
+        let prompt_file = StateManager::new(tmp.path()).agents_dir().join(format!("{agent_id}-prompt.md"));
+        assert_eq!(std::fs::read_to_string(&prompt_file).unwrap(), "fix the tests");
+        let calls = mux.calls();
+        let started = calls.iter().find(|c| c.starts_with(&format!("send-keys {pane} "))).unwrap();
+        assert!(started.contains(&prompt_file.display().to_string()), "{started}");
+    }
+
+    #[tokio::test]
+    async fn test_run_to_completion_registers_agent() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut state = init_state(tmp.path(), vec![]).await;
+        let session = Session::new("s1".into(), "feat/s1".into(), tmp.path().into(), "@1".into());
+        let session_id = session.id;
+        state.sessions.push(session);
+        let state_manager = StateManager::new(tmp.path());
+        state_manager.save(&state).await.unwrap();
+
+        let claude = tmp.path().join("claude");
+        std::fs::write(
+            &claude,
+            "#!/bin/sh\necho '{\"type\": \"result\", \"subtype\": \"success\", \"is_error\": false, \"result\": \"done\"}'\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&claude, std::os::unix::fs::PermissionsExt::from_mode(0o755)).unwrap();
+        let mut config = config(tmp.path());
+        config.workspace.claude_command = Some(claude.display().to_string());
+
+        let agent = Agent::new(
+            session_id,
+            "address-pr-7".into(),
+            AgentMode::Headless,
+            "fix it".into(),
+            tmp.path().into(),
+            state_manager.agents_dir(),
+        );
+        let agent_id = agent.id;
+        let template = AgentTemplate::load("implementer", &config.template_dirs(tmp.path())).unwrap();
+        let output = run_to_completion(tmp.path(), agent, &template, &config).await.unwrap();
+        assert_eq!(output.result, "done");
+
+        let state = state_manager.load().await.unwrap();
+        let agent = state.agents.iter().find(|a| a.id == agent_id).unwrap();
+        assert_eq!(agent.status, AgentStatus::Completed);
+        assert!(agent.output_file.exists());
+        assert_eq!(state.find_session_by_id(session_id).unwrap().agents, [agent_id]);
+    }
 }
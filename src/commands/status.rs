@@ -1,11 +1,23 @@
+use crate::domain::overlap::{find_overlaps, ChangeSet, SessionOverlap};
+use crate::domain::workspace::WorkspaceState;
 use crate::error::VibeError;
+use crate::infra::git;
 use crate::infra::state::StateManager;
 use std::path::Path;
 
-pub async fn execute(workspace_root: &Path, json: bool) -> Result<(), VibeError> {
+pub async fn execute(
+    workspace_root: &Path,
+    json: bool,
+    conflicts: bool,
+    trial_merge: bool,
+) -> Result<(), VibeError> {
     let state_manager = StateManager::new(workspace_root);
     let state = state_manager.load().await?;
 
+    if conflicts {
+        return print_overlaps(state, json, trial_merge).await;
+    }
+
     if json {
         let output = serde_json::to_string_pretty(&state)
             .map_err(|e| VibeError::State(e.to_string()))?;
@@ -60,6 +72,27 @@ pub async fn execute(workspace_root: &Path, json: bool) -> Result<(), VibeError>
             status_icon, session.name, session.status, session.branch,
         );
 
+        for (repo_name, err) in &session.failed_repos {
+            println!(
+                "    ! {repo_name} worktree missing: {}",
+                err.lines().next().unwrap_or_default()
+            );
+        }
+
+        for conflict in &session.sync_conflicts {
+            let repo = conflict
+                .repo
+                .as_deref()
+                .map(|r| format!(" in {r}"))
+                .unwrap_or_default();
+            println!(
+                "    ! {} conflict{repo} onto {}: {}",
+                conflict.strategy,
+                conflict.onto,
+                conflict.files.join(", ")
+            );
+        }
+
         let agents = state.agents_for_session(session.id);
         for agent in agents {
             let agent_icon = match &agent.status {
@@ -78,3 +111,125 @@ pub async fn execute(workspace_root: &Path, json: bool) -> Result<(), VibeError>
 
     Ok(())
 }
+
+async fn print_overlaps(state: WorkspaceState, json: bool, trial_merge: bool) -> Result<(), VibeError> {
+    let overlaps = tokio::task::spawn_blocking(move || detect_overlaps(&state, trial_merge))
+        .await
+        .map_err(|e| VibeError::Git(e.to_string()))?;
+
+    if json {
+        let output = serde_json::to_string_pretty(&overlaps)
+            .map_err(|e| VibeError::State(e.to_string()))?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    if overlaps.is_empty() {
+        println!("No overlapping changes between active sessions.");
+        return Ok(());
+    }
+
+    println!("Overlapping changes ({}):", overlaps.len());
+    for overlap in &overlaps {
+        let [a, b] = &overlap.sessions;
+        let repo = overlap
+            .repo
+            .as_deref()
+            .map(|r| format!(" in {r}"))
+            .unwrap_or_default();
+        let marker = if overlap.conflicts() { "✗" } else { "!" };
+        println!("  {marker} {a} and {b} both touch{repo}:");
+        for file in &overlap.files {
+            let conflicted = overlap
+                .merge_conflicts
+                .as_ref()
+                .is_some_and(|c| c.contains(file));
+            if conflicted {
+                println!("      {file}  (merge conflict)");
+            } else {
+                println!("      {file}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Compare the changed-file sets of all active sessions and report pairs that
+/// touch the same files. With `trial_merge`, each overlapping pair of branches
+/// is also merged in memory to find real conflicts.
+///
+/// Blocking — opens every session worktree with git2.
+pub fn detect_overlaps(state: &WorkspaceState, trial_merge: bool) -> Vec<SessionOverlap> {
+    let mut changes = Vec::new();
+    for session in state.active_sessions() {
+        if session.is_main {
+            continue;
+        }
+        let worktrees: Vec<(Option<String>, &Path, &str)> = if session.repo_worktrees.is_empty() {
+            vec![(
+                None,
+                session.worktree_path.as_path(),
+                session
+                    .base_for(None)
+                    .unwrap_or(&state.workspace.default_branch),
+            )]
+        } else {
+            session
+                .repo_worktrees
+                .iter()
+                .filter_map(|(repo_name, wt_path)| {
+                    let repo = state.workspace.repos.iter().find(|r| r.name == *repo_name)?;
+                    Some((
+                        Some(repo_name.clone()),
+                        wt_path.as_path(),
+                        session
+                            .base_for(Some(repo_name))
+                            .unwrap_or(&repo.default_branch),
+                    ))
+                })
+                .collect()
+        };
+
+        for (repo, worktree, base) in worktrees {
+            let base_ref = git::resolve_base_ref(worktree, base);
+            match git::changed_files(worktree, &base_ref) {
+                Ok(files) if !files.is_empty() => changes.push(ChangeSet {
+                    session: session.name.clone(),
+                    branch: session.branch_for(repo.as_deref()).to_string(),
+                    repo,
+                    worktree: worktree.to_path_buf(),
+                    files,
+                }),
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::debug!(session = %session.name, error = %e, "skipping overlap check");
+                }
+            }
+        }
+    }
+
+    let mut overlaps = find_overlaps(&changes);
+    if trial_merge {
+        for overlap in &mut overlaps {
+            // Worktrees of one repo share refs, so either side can resolve both branches
+            let Some(side) = changes
+                .iter()
+                .find(|c| c.session == overlap.sessions[0] && c.repo == overlap.repo)
+            else {
+                continue;
+            };
+            let Some(other) = changes
+                .iter()
+                .find(|c| c.session == overlap.sessions[1] && c.repo == overlap.repo)
+            else {
+                continue;
+            };
+            match git::merge_conflicts(&side.worktree, &side.branch, &other.branch) {
+                Ok(paths) => overlap.merge_conflicts = Some(paths),
+                Err(e) => tracing::debug!(error = %e, "trial merge failed"),
+            }
+        }
+    }
+    overlaps
+}
@@ -0,0 +1,319 @@
+use crate::config::MergedConfig;
+use crate::domain::session::Session;
+use crate::domain::sync::{SyncConflict, SyncOutcome, SyncStrategy};
+use crate::error::VibeError;
+use crate::infra::{git, state::StateManager};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Result of syncing one worktree of one session.
+#[derive(Debug, Clone)]
+pub struct SyncReport {
+    pub session: String,
+    /// Repo name for multi-repo sessions, `None` for single-repo.
+    pub repo: Option<String>,
+    pub worktree: PathBuf,
+    pub onto: String,
+    pub outcome: SyncOutcome,
+}
+
+impl SyncReport {
+    pub fn label(&self) -> String {
+        match &self.repo {
+            Some(repo) => format!("{}/{repo}", self.session),
+            None => self.session.clone(),
+        }
+    }
+}
+
+/// One worktree to sync, with the repo it belongs to.
+struct SyncTarget {
+    repo: Option<String>,
+    repo_root: PathBuf,
+    worktree: PathBuf,
+    /// Branch to sync onto, before `origin/` resolution.
+    base: String,
+}
+
+pub async fn execute(
+    workspace_root: &Path,
+    session_name: Option<String>,
+    all: bool,
+    strategy: Option<SyncStrategy>,
+    abort_on_conflict: bool,
+    config: &MergedConfig,
+) -> Result<(), VibeError> {
+    let strategy = strategy.unwrap_or_else(|| config.sync_strategy());
+
+    let names = match (session_name, all) {
+        (Some(name), _) => vec![name],
+        (None, true) => {
+            let state = StateManager::new(workspace_root).load().await?;
+            state
+                .sessions
+                .iter()
+                .filter(|s| s.is_active() && !s.is_main)
+                .map(|s| s.name.clone())
+                .collect()
+        }
+        (None, false) => {
+            return Err(VibeError::User(
+                "Specify a session name or --all".into(),
+            ));
+        }
+    };
+
+    if names.is_empty() {
+        println!("No active sessions to sync.");
+        return Ok(());
+    }
+
+    println!("Syncing {} session(s) ({strategy})...", names.len());
+    let reports = run(workspace_root, &names, strategy, abort_on_conflict).await?;
+
+    let mut conflicts = 0;
+    for report in &reports {
+        if report.outcome.needs_attention() {
+            conflicts += 1;
+        }
+        let label = report.label();
+        match &report.outcome {
+            SyncOutcome::UpToDate => println!("  {label}: up to date with {}", report.onto),
+            SyncOutcome::Synced => println!("  {label}: {strategy}d onto {}", report.onto),
+            SyncOutcome::Conflicted { files, aborted } => {
+                if *aborted {
+                    println!("  {label}: CONFLICT — {strategy} aborted, branch unchanged");
+                } else {
+                    println!("  {label}: CONFLICT — {strategy} in progress");
+                }
+                for file in files {
+                    println!("      {file}");
+                }
+                if !aborted {
+                    println!(
+                        "    Resolve in {}, then `git {strategy} --continue` (or `--abort`)",
+                        report.worktree.display()
+                    );
+                }
+            }
+            SyncOutcome::InProgress => {
+                println!(
+                    "  {label}: skipped — a rebase/merge is already in progress in {}",
+                    report.worktree.display()
+                );
+            }
+            SyncOutcome::Skipped(reason) => println!("  {label}: skipped — {reason}"),
+            SyncOutcome::Failed(err) => println!("  {label}: FAILED — {err}"),
+        }
+    }
+
+    // Exit non-zero so scripts notice branches left mid-rebase/merge
+    if conflicts > 0 {
+        return Err(VibeError::User(format!("{conflicts} worktree(s) need attention")));
+    }
+
+    Ok(())
+}
+
+/// Sync the named sessions onto their base branches and record any
+/// conflicts on the sessions. Fetches each underlying repo once.
+pub async fn run(
+    workspace_root: &Path,
+    session_names: &[String],
+    strategy: SyncStrategy,
+    abort_on_conflict: bool,
+) -> Result<Vec<SyncReport>, VibeError> {
+    let state_manager = StateManager::new(workspace_root);
+    let state = state_manager.load().await?;
+
+    let mut sessions: Vec<Session> = Vec::new();
+    for name in session_names {
+        let session = state
+            .find_session_by_name(name)
+            .ok_or_else(|| VibeError::SessionNotFound(name.clone()))?;
+        if session.is_main {
+            return Err(VibeError::User(
+                "The main session tracks the workspace checkout and cannot be synced".into(),
+            ));
+        }
+        sessions.push(session.clone());
+    }
+
+    let targets: Vec<(String, Vec<SyncTarget>)> = sessions
+        .iter()
+        .map(|session| {
+            let targets = if session.repo_worktrees.is_empty() {
+                vec![SyncTarget {
+                    repo: None,
+                    repo_root: workspace_root.to_path_buf(),
+                    worktree: session.worktree_path.clone(),
+                    base: session
+                        .base_for(None)
+                        .unwrap_or(&state.workspace.default_branch)
+                        .to_string(),
+                }]
+            } else {
+                session
+                    .repo_worktrees
+                    .iter()
+                    .filter_map(|(repo_name, wt_path)| {
+                        let repo = state.workspace.repos.iter().find(|r| r.name == *repo_name);
+                        if repo.is_none() {
+                            warn!(repo = %repo_name, "repo not found in workspace, skipping sync");
+                        }
+                        repo.map(|r| SyncTarget {
+                            repo: Some(repo_name.clone()),
+                            repo_root: r.root.clone(),
+                            worktree: wt_path.clone(),
+                            base: session
+                                .base_for(Some(repo_name))
+                                .unwrap_or(&r.default_branch)
+                                .to_string(),
+                        })
+                    })
+                    .collect()
+            };
+            (session.name.clone(), targets)
+        })
+        .collect();
+
+    // Fetch once per repo
+    let repo_roots: BTreeSet<&PathBuf> = targets
+        .iter()
+        .flat_map(|(_, t)| t.iter().map(|t| &t.repo_root))
+        .collect();
+    for repo_root in repo_roots {
+        if let Err(e) = git::fetch_origin(repo_root).await {
+            warn!(repo = %repo_root.display(), error = %e, "fetch failed, syncing against local refs");
+        }
+    }
+
+    let mut reports = Vec::new();
+    for (session_name, session_targets) in targets {
+        for target in session_targets {
+            let onto = git::resolve_base_ref(&target.worktree, &target.base);
+            let outcome = if target.worktree.exists() {
+                git::sync_worktree(&target.worktree, &onto, strategy, abort_on_conflict).await
+            } else {
+                SyncOutcome::Failed("worktree missing".into())
+            };
+            info!(session = %session_name, repo = ?target.repo, ?outcome, "worktree synced");
+            reports.push(SyncReport {
+                session: session_name.clone(),
+                repo: target.repo,
+                worktree: target.worktree,
+                onto,
+                outcome,
+            });
+        }
+    }
+
+    // Record conflicts. Reload in case the state changed while we were syncing.
+    let mut state = state_manager.load().await?;
+    for name in session_names {
+        let Some(session) = state.find_session_by_name_mut(name) else {
+            continue;
+        };
+        let previous = std::mem::take(&mut session.sync_conflicts);
+        for report in reports.iter().filter(|r| r.session == *name) {
+            match &report.outcome {
+                SyncOutcome::Conflicted {
+                    files,
+                    aborted: false,
+                } => session.sync_conflicts.push(SyncConflict {
+                    repo: report.repo.clone(),
+                    strategy,
+                    onto: report.onto.clone(),
+                    files: files.clone(),
+                }),
+                // Still unresolved from an earlier sync (or a manual one)
+                SyncOutcome::InProgress => {
+                    let record = previous
+                        .iter()
+                        .find(|c| c.repo == report.repo)
+                        .cloned()
+                        .unwrap_or_else(|| SyncConflict {
+                            repo: report.repo.clone(),
+                            strategy,
+                            onto: report.onto.clone(),
+                            files: vec![],
+                        });
+                    session.sync_conflicts.push(record);
+                }
+                _ => {}
+            }
+        }
+        session.updated_at = chrono::Utc::now();
+    }
+    state_manager.save(&state).await?;
+
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, config, git, init_repo, init_state};
+
+    /// Workspace whose `main` and the session branches `feat/<name>` both
+    /// changed `shared.txt`, so syncing any of them conflicts.
+    async fn conflicting_sessions(root: &Path, names: &[&str]) -> StateManager {
+        init_repo(root);
+        commit_file(root, "shared.txt", "base\n");
+        let mut state = init_state(root, vec![]).await;
+        for name in names {
+            let branch = format!("feat/{name}");
+            let path = root.with_file_name(format!("ws-{name}"));
+            git(root, &["worktree", "add", "-q", "-b", &branch, path.to_str().unwrap()]);
+            commit_file(&path, "shared.txt", &format!("{name}\n"));
+            let mut session = Session::new(name.to_string(), branch, path, "@1".into());
+            session.status = crate::domain::session::SessionStatus::Active;
+            state.sessions.push(session);
+        }
+        commit_file(root, "shared.txt", "main\n");
+        let state_manager = StateManager::new(root);
+        state_manager.save(&state).await.unwrap();
+        state_manager
+    }
+
+    #[tokio::test]
+    async fn test_conflicts_fail_and_are_recorded() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("ws");
+        let state_manager = conflicting_sessions(&root, &["a"]).await;
+        let config = config(tmp.path());
+
+        let err = execute(&root, Some("a".into()), false, Some(SyncStrategy::Rebase), false, &config)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("1 worktree(s) need attention"), "{err}");
+        let state = state_manager.load().await.unwrap();
+        let conflicts = &state.find_session_by_name("a").unwrap().sync_conflicts;
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].files, ["shared.txt"]);
+
+        // Still unresolved on the next sync, and still recorded
+        assert!(execute(&root, None, true, None, false, &config).await.is_err());
+        let state = state_manager.load().await.unwrap();
+        assert_eq!(state.find_session_by_name("a").unwrap().sync_conflicts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_aborted_conflicts_leave_branches_alone() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("ws");
+        let state_manager = conflicting_sessions(&root, &["a", "b"]).await;
+        let wt = root.with_file_name("ws-a");
+        let head = git(&wt, &["rev-parse", "HEAD"]);
+
+        execute(&root, None, true, Some(SyncStrategy::Merge), true, &config(tmp.path()))
+            .await
+            .unwrap();
+
+        assert_eq!(git(&wt, &["rev-parse", "HEAD"]), head);
+        assert!(!git::has_unresolved_sync(&wt));
+        let state = state_manager.load().await.unwrap();
+        assert!(state.sessions.iter().all(|s| s.sync_conflicts.is_empty()));
+    }
+}
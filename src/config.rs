@@ -1,5 +1,11 @@
+use crate::domain::keymap::{self, Action, KeyList};
+use crate::domain::layout::PaneLayout;
+use crate::domain::sync::SyncStrategy;
 use crate::error::VibeError;
+use crate::infra::git::{RepoDiscovery, WorktreeSetup};
+use crate::infra::mux::MuxKind;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 /// Global config: ~/.config/vibe/config.toml
@@ -22,6 +28,18 @@ pub struct GlobalConfig {
     /// tmux escape-time in milliseconds (default: 100).
     /// Higher values improve reliability over SSH at the cost of Escape key latency.
     pub escape_time_ms: u32,
+    /// Seconds between dashboard checks for sessions changing the same files
+    /// (default: 60). 0 disables the check.
+    pub overlap_check_secs: u64,
+    /// Also trial-merge overlapping branches to tell real conflicts apart
+    /// from files that merely changed on both sides (default: false).
+    pub overlap_trial_merge: bool,
+    /// What runs session terminals: "tmux" (default) or "pty" for
+    /// terminals kept inside the dashboard.
+    pub multiplexer: MuxKind,
+    /// TUI keys by action (`[keys]`), replacing the defaults of the actions
+    /// listed. `?` in the dashboard shows the keys in effect.
+    pub keys: BTreeMap<Action, KeyList>,
 }
 
 impl GlobalConfig {
@@ -35,41 +53,29 @@ impl GlobalConfig {
         csi_display_name(&self.overview_key)
     }
 
+    /// Check if a crossterm KeyCode matches the configured dashboard key.
+    pub fn matches_dashboard_key(&self, code: &crossterm::event::KeyCode) -> bool {
+        csi_matches(&self.dashboard_key, code)
+    }
+
     /// Check if a crossterm KeyCode matches the configured overview key.
     pub fn matches_overview_key(&self, code: &crossterm::event::KeyCode) -> bool {
-        csi_to_keycode(&self.overview_key)
-            .is_some_and(|expected| std::mem::discriminant(&expected) == std::mem::discriminant(code) && expected == *code)
+        csi_matches(&self.overview_key, code)
     }
 }
 
-/// Map a CSI suffix like "[29~" to the crossterm KeyCode it produces.
-fn csi_to_keycode(suffix: &str) -> Option<crossterm::event::KeyCode> {
-    use crossterm::event::KeyCode;
-    // CSI sequences of the form \e[N~ map to function keys
-    let inner = suffix.strip_prefix('[')?.strip_suffix('~')?;
-    let n: u8 = inner.parse().ok()?;
-    // Standard xterm mapping: 11-15→F1-F5, 17-21→F6-F10, 23-24→F11-F12,
-    // 25-26→F13-F14, 28-29→F15-F16, 31-34→F17-F20
-    let fkey = match n {
-        11..=15 => n - 10,        // F1-F5
-        17..=21 => n - 11,        // F6-F10
-        23..=24 => n - 12,        // F11-F12
-        25..=26 => n - 12,        // F13-F14
-        28..=29 => n - 13,        // F15-F16
-        31..=34 => n - 14,        // F17-F20
-        _ => return None,
-    };
-    Some(KeyCode::F(fkey))
-}
-
 /// Human-readable name for a CSI suffix.
 fn csi_display_name(suffix: &str) -> String {
-    if let Some(kc) = csi_to_keycode(suffix) {
-        if let crossterm::event::KeyCode::F(n) = kc {
-            return format!("F{n}");
-        }
+    match keymap::csi_function_key(suffix) {
+        Some((name, _)) => format!("F{name}"),
+        None => suffix.to_string(),
     }
-    suffix.to_string()
+}
+
+/// Whether crossterm's `code` is the key a CSI suffix is sent for.
+fn csi_matches(suffix: &str, code: &crossterm::event::KeyCode) -> bool {
+    matches!(code, crossterm::event::KeyCode::F(n)
+        if keymap::csi_function_key(suffix).is_some_and(|(_, received)| received == *n))
 }
 
 impl Default for GlobalConfig {
@@ -86,6 +92,10 @@ impl Default for GlobalConfig {
             dashboard_key: "[29~".into(),
             overview_key: "[33~".into(),
             escape_time_ms: 100,
+            overlap_check_secs: 60,
+            overlap_trial_merge: false,
+            multiplexer: MuxKind::default(),
+            keys: BTreeMap::new(),
         }
     }
 }
@@ -98,8 +108,51 @@ pub struct WorkspaceConfig {
     pub default_branch: Option<String>,
     pub template_dir: Option<PathBuf>,
     pub claude_command: Option<String>,
+    /// Shell command run in each new worktree before Claude starts. A
+    /// non-zero exit aborts session creation.
     pub pre_session_hook: Option<String>,
+    /// Shell command run in each worktree before it is removed.
     pub post_session_hook: Option<String>,
+    /// Untracked files copied from the main checkout into new worktrees.
+    pub copy_files: Vec<String>,
+    /// Directories symlinked from the main checkout into new worktrees.
+    pub symlink_dirs: Vec<String>,
+    /// How `vibe sync` updates session branches: "rebase" (default) or "merge".
+    pub sync_strategy: Option<SyncStrategy>,
+    /// Command `vibe integrate` runs after each merge (e.g. "cargo test").
+    pub integrate_check: Option<String>,
+    /// How interactive agents are laid out in a session's window:
+    /// "main-vertical" (default), "tiled", "even-horizontal", or "window"
+    /// for a window per agent. Templates can override it.
+    pub layout: Option<PaneLayout>,
+    /// Overrides the global `multiplexer` for this workspace.
+    pub multiplexer: Option<MuxKind>,
+    /// Per-repo overrides for multi-repo workspaces (`[repos.api]`).
+    pub repos: BTreeMap<String, RepoConfig>,
+    /// Directory levels searched for repos in multi-repo workspaces (default 1).
+    pub discovery_depth: Option<usize>,
+    /// Directory names, relative paths or `*`/`?` globs skipped when
+    /// discovering repos.
+    pub discovery_ignore: Vec<String>,
+}
+
+/// `[repos.<name>]` section of the workspace config.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RepoConfig {
+    /// Base ref for new session branches in this repo (e.g. "release/2.1").
+    pub base: Option<String>,
+    /// Branch name for new sessions; `{name}` is replaced by the session name.
+    pub branch: Option<String>,
+}
+
+impl RepoConfig {
+    /// Branch for a new session in this repo, if overridden.
+    pub fn branch_for(&self, session_name: &str) -> Option<String> {
+        self.branch
+            .as_ref()
+            .map(|b| b.replace("{name}", session_name))
+    }
 }
 
 /// Merged config with resolved values
@@ -123,6 +176,32 @@ impl MergedConfig {
             .unwrap_or("claude")
     }
 
+    pub fn worktree_setup(&self) -> WorktreeSetup {
+        WorktreeSetup {
+            copy_files: self.workspace.copy_files.clone(),
+            symlink_dirs: self.workspace.symlink_dirs.clone(),
+        }
+    }
+
+    pub fn repo_discovery(&self) -> RepoDiscovery {
+        RepoDiscovery {
+            depth: self.workspace.discovery_depth.unwrap_or(1).max(1),
+            ignore: self.workspace.discovery_ignore.clone(),
+        }
+    }
+
+    pub fn sync_strategy(&self) -> SyncStrategy {
+        self.workspace.sync_strategy.unwrap_or_default()
+    }
+
+    pub fn pane_layout(&self) -> PaneLayout {
+        self.workspace.layout.unwrap_or_default()
+    }
+
+    pub fn multiplexer(&self) -> MuxKind {
+        self.workspace.multiplexer.unwrap_or(self.global.multiplexer)
+    }
+
     pub fn worktree_base_dir(&self, workspace_root: &Path) -> PathBuf {
         self.workspace
             .worktree_base_dir
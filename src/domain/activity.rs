@@ -0,0 +1,146 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Claude Code hook events vibe installs into session worktrees.
+pub const HOOK_EVENTS: &[&str] = &[
+    "SessionStart",
+    "PreToolUse",
+    "PostToolUse",
+    "Notification",
+    "UserPromptSubmit",
+    "Stop",
+];
+
+/// What a session's Claude is doing, as reported by its hooks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Activity {
+    /// Running tools or thinking about a prompt.
+    Working,
+    /// Blocked on a permission prompt.
+    NeedsPermission,
+    /// Finished its turn, or waiting for input.
+    Idle,
+}
+
+/// One line of `.vibe/events/{session}.jsonl`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityEvent {
+    pub at: DateTime<Utc>,
+    /// Hook that fired (`hook_event_name` from Claude Code), or `vibe` for
+    /// prompts answered from the dashboard.
+    pub hook: String,
+    pub activity: Activity,
+    /// Pane the hook ran in (`$TMUX_PANE`, or `$VIBE_PANE` under the built-in
+    /// multiplexer). Agents spawned into a session's window share its
+    /// worktree, so this tells them apart.
+    #[serde(default)]
+    pub pane: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ActivityEvent {
+    /// Build an event from the JSON a Claude Code hook receives on stdin.
+    /// Returns `None` for input that isn't a hook vibe understands.
+    pub fn from_hook_input(input: &str, pane: Option<String>, at: DateTime<Utc>) -> Option<Self> {
+        let value: Value = serde_json::from_str(input).ok()?;
+        let field = |name: &str| value.get(name).and_then(Value::as_str).map(str::to_string);
+        let hook = field("hook_event_name")?;
+        let message = field("message");
+
+        let activity = match hook.as_str() {
+            "PreToolUse" | "PostToolUse" | "UserPromptSubmit" => Activity::Working,
+            // A fresh Claude waits at its prompt
+            "SessionStart" | "Stop" => Activity::Idle,
+            "Notification" => {
+                let kind = field("notification_type");
+                let permission = match kind.as_deref() {
+                    Some(kind) => kind == "permission_prompt",
+                    // Older Claude Code versions only send the message
+                    None => message.as_deref().is_some_and(|m| m.contains("permission")),
+                };
+                if permission {
+                    Activity::NeedsPermission
+                } else {
+                    Activity::Idle
+                }
+            }
+            _ => return None,
+        };
+
+        Some(Self {
+            at,
+            hook,
+            activity,
+            pane,
+            tool: field("tool_name"),
+            message,
+        })
+    }
+
+    /// The most recent event in an events log from `pane`.
+    pub fn latest_for_pane(log: &str, pane: &str) -> Option<Self> {
+        log.lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str::<Self>(line).ok())
+            .find(|event| event.pane.as_deref() == Some(pane))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_hook_input() {
+        let at = Utc::now();
+        let pane = || Some("%3".to_string());
+        let event = |input: &str| ActivityEvent::from_hook_input(input, pane(), at).unwrap();
+
+        let pre = event(r#"{"hook_event_name":"PreToolUse","tool_name":"Bash","tool_input":{}}"#);
+        assert_eq!(pre.activity, Activity::Working);
+        assert_eq!(pre.tool.as_deref(), Some("Bash"));
+
+        let asked = event(
+            r#"{"hook_event_name":"Notification","message":"Claude needs your permission to use Bash"}"#,
+        );
+        assert_eq!(asked.activity, Activity::NeedsPermission);
+
+        let typed = event(
+            r#"{"hook_event_name":"Notification","notification_type":"idle_prompt","message":"Claude is waiting for your input"}"#,
+        );
+        assert_eq!(typed.activity, Activity::Idle);
+
+        assert_eq!(event(r#"{"hook_event_name":"Stop"}"#).activity, Activity::Idle);
+        assert!(ActivityEvent::from_hook_input(r#"{"hook_event_name":"SessionEnd"}"#, pane(), at).is_none());
+        assert!(ActivityEvent::from_hook_input("not json", pane(), at).is_none());
+    }
+
+    #[test]
+    fn test_latest_for_pane() {
+        let at = Utc::now();
+        let line = |hook: &str, pane: &str| {
+            let input = format!(r#"{{"hook_event_name":"{hook}"}}"#);
+            let event = ActivityEvent::from_hook_input(&input, Some(pane.into()), at).unwrap();
+            serde_json::to_string(&event).unwrap()
+        };
+        let log = [
+            line("PreToolUse", "%1"),
+            line("Stop", "%1"),
+            "garbage".to_string(),
+            // An agent pane sharing the worktree
+            line("PreToolUse", "%2"),
+        ]
+        .join("\n");
+
+        let main = ActivityEvent::latest_for_pane(&log, "%1").unwrap();
+        assert_eq!(main.activity, Activity::Idle);
+        let agent = ActivityEvent::latest_for_pane(&log, "%2").unwrap();
+        assert_eq!(agent.activity, Activity::Working);
+        assert!(ActivityEvent::latest_for_pane(&log, "%9").is_none());
+    }
+}
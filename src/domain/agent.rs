@@ -22,6 +22,9 @@ pub struct Agent {
     pub result: Option<AgentResult>,
     pub created_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
+    /// CI runs whose failures this agent was asked to fix (`vibe ci fix`).
+    #[serde(default)]
+    pub ci_runs: Vec<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -99,6 +102,7 @@ impl Agent {
             result: None,
             created_at: Utc::now(),
             completed_at: None,
+            ci_runs: vec![],
         }
     }
 
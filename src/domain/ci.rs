@@ -0,0 +1,157 @@
+use crate::domain::transcript;
+use chrono::DateTime;
+
+/// Lines kept before and after each line that looks like an error.
+const CONTEXT_LINES: usize = 3;
+/// Lines kept from the end of each failed step, where the failure usually is.
+const TAIL_LINES: usize = 30;
+
+/// One failed step's log from `gh run view --log-failed`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepLog {
+    pub job: String,
+    pub step: String,
+    pub lines: Vec<String>,
+}
+
+/// Split `gh run view --log-failed` output ("job<TAB>step<TAB>timestamp text"
+/// per line) into steps, without timestamps, colour codes or group markers.
+pub fn parse_failed_log(log: &str) -> Vec<StepLog> {
+    let mut steps: Vec<StepLog> = vec![];
+    for line in log.lines() {
+        let mut parts = line.splitn(3, '\t');
+        let (Some(job), Some(step), Some(text)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        let text = transcript::clean_line(strip_timestamp(text));
+        if text.starts_with("##[group]") || text.starts_with("##[endgroup]") {
+            continue;
+        }
+        match steps.last_mut() {
+            Some(last) if last.job == job && last.step == step => last.lines.push(text),
+            _ => steps.push(StepLog {
+                job: job.to_string(),
+                step: step.to_string(),
+                lines: vec![text],
+            }),
+        }
+    }
+    steps
+}
+
+fn strip_timestamp(text: &str) -> &str {
+    match text.split_once(' ') {
+        Some((stamp, rest)) if DateTime::parse_from_rfc3339(stamp).is_ok() => rest,
+        _ => text,
+    }
+}
+
+fn looks_like_error(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    line.starts_with("##[error]")
+        || lower.contains("error")
+        || lower.contains("failed")
+        || lower.contains("failure")
+        || lower.contains("panicked")
+}
+
+impl StepLog {
+    /// The lines worth showing: each error-looking line with some context,
+    /// plus the end of the step. Gaps are marked with "…".
+    pub fn excerpt(&self) -> Vec<String> {
+        let n = self.lines.len();
+        let mut keep = vec![false; n];
+        for (idx, line) in self.lines.iter().enumerate() {
+            if looks_like_error(line) {
+                let end = (idx + CONTEXT_LINES + 1).min(n);
+                keep[idx.saturating_sub(CONTEXT_LINES)..end].fill(true);
+            }
+        }
+        keep[n.saturating_sub(TAIL_LINES)..].fill(true);
+
+        let mut excerpt = vec![];
+        for (idx, line) in self.lines.iter().enumerate() {
+            if keep[idx] {
+                excerpt.push(line.clone());
+            } else if idx == 0 || keep[idx - 1] {
+                excerpt.push("…".into());
+            }
+        }
+        excerpt
+    }
+}
+
+/// Markdown excerpt of a failed run's log, at most `max_lines` log lines.
+/// Later steps are dropped (with a note) once the budget is used up.
+pub fn trim_failed_log(log: &str, max_lines: usize) -> String {
+    let mut out = String::new();
+    let mut budget = max_lines;
+    let steps = parse_failed_log(log);
+    for (idx, step) in steps.iter().enumerate() {
+        if budget == 0 {
+            out.push_str(&format!("[{} more failed step(s) not shown]\n", steps.len() - idx));
+            break;
+        }
+        let mut excerpt = step.excerpt();
+        if excerpt.len() > budget {
+            // Keep the end, closest to where the step failed
+            excerpt.drain(..excerpt.len() - budget);
+            excerpt.insert(0, "…".into());
+        }
+        budget = budget.saturating_sub(excerpt.len());
+        out.push_str(&format!("#### {} / {}\n\n```\n{}\n```\n\n", step.job, step.step, excerpt.join("\n")));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_line(step: &str, text: &str) -> String {
+        format!("test\t{step}\t2024-05-01T12:00:00.1234567Z {text}\n")
+    }
+
+    #[test]
+    fn test_parse_failed_log() {
+        let log = log_line("Build", "##[group]Run cargo build")
+            + &log_line("Build", "\x1b[1m\x1b[32mCompiling\x1b[0m vibe")
+            + &log_line("Test", "\x1b]8;;https://example.com\x1b\\running\x1b]8;;\x07 2 tests")
+            + "not a log line\n";
+        let steps = parse_failed_log(&log);
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].step, "Build");
+        assert_eq!(steps[0].lines, vec!["Compiling vibe"]);
+        assert_eq!(steps[1].lines, vec!["running 2 tests"]);
+    }
+
+    #[test]
+    fn test_excerpt_keeps_errors_and_tail() {
+        let mut lines: Vec<String> = (0..100).map(|i| format!("line {i}")).collect();
+        lines[10] = "error[E0308]: mismatched types".into();
+        let step = StepLog {
+            job: "test".into(),
+            step: "Build".into(),
+            lines,
+        };
+        let excerpt = step.excerpt();
+        assert_eq!(excerpt[0], "…");
+        assert_eq!(excerpt[1], "line 7");
+        assert!(excerpt.contains(&"error[E0308]: mismatched types".to_string()));
+        assert_eq!(excerpt[7], "line 13");
+        assert_eq!(excerpt[8], "…");
+        assert_eq!(excerpt.last().unwrap(), "line 99");
+        assert_eq!(excerpt.len(), 1 + 7 + 1 + TAIL_LINES);
+    }
+
+    #[test]
+    fn test_trim_failed_log_budget() {
+        let log: String = (0..50).map(|i| log_line("A", &format!("a{i}"))).collect::<String>()
+            + &log_line("B", "b0");
+        let trimmed = trim_failed_log(&log, 10);
+        assert!(trimmed.contains("#### test / A"));
+        assert!(trimmed.contains("a49"));
+        assert!(!trimmed.contains("a39\n"));
+        assert!(trimmed.contains("[1 more failed step(s) not shown]"));
+    }
+}
@@ -0,0 +1,206 @@
+use std::path::PathBuf;
+
+/// Which side of a session's history a diff is taken against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffScope {
+    /// Everything the session changed since it forked from its base branch
+    /// (merge-base of HEAD and the base ref → working tree).
+    Base,
+    /// Uncommitted changes only (HEAD → working tree, staged and unstaged).
+    Head,
+}
+
+impl DiffScope {
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Base => Self::Head,
+            Self::Head => Self::Base,
+        }
+    }
+}
+
+impl std::fmt::Display for DiffScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Base => write!(f, "vs base"),
+            Self::Head => write!(f, "uncommitted"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChange {
+    Added,
+    Deleted,
+    Modified,
+    Renamed,
+    Untracked,
+    Other,
+}
+
+impl FileChange {
+    /// Single-letter marker in the style of `git status --short`.
+    pub fn marker(self) -> &'static str {
+        match self {
+            Self::Added => "A",
+            Self::Deleted => "D",
+            Self::Modified => "M",
+            Self::Renamed => "R",
+            Self::Untracked => "?",
+            Self::Other => "~",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub content: String,
+}
+
+/// A single `@@` hunk. The new-side range (1-based start, line count)
+/// identifies the hunk in the working tree for staging and discarding.
+#[derive(Debug, Clone)]
+pub struct DiffHunk {
+    pub header: String,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub lines: Vec<DiffLine>,
+}
+
+/// What staging or discarding one hunk did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HunkApply {
+    Applied,
+    /// No unstaged changes at the hunk's lines: already staged or committed.
+    NothingUnstaged,
+    /// The unstaged changes there no longer match the hunk, e.g. because part
+    /// of it was staged. Reloading the diff shows the hunk as it is now.
+    Changed,
+}
+
+/// All changes to one file within a worktree.
+#[derive(Debug, Clone)]
+pub struct FileDiff {
+    /// Worktree the file lives in (a repo worktree for multi-repo sessions).
+    pub worktree: PathBuf,
+    /// Repo name for multi-repo sessions, `None` for single-repo.
+    pub repo: Option<String>,
+    /// Path relative to `worktree`.
+    pub path: String,
+    pub change: FileChange,
+    pub binary: bool,
+    pub hunks: Vec<DiffHunk>,
+}
+
+impl FileDiff {
+    /// Path as shown in the file list, prefixed with the repo name if any.
+    pub fn display_path(&self) -> String {
+        match &self.repo {
+            Some(repo) => format!("{repo}/{}", self.path),
+            None => self.path.clone(),
+        }
+    }
+
+    pub fn additions(&self) -> usize {
+        self.count_lines(DiffLineKind::Added)
+    }
+
+    pub fn deletions(&self) -> usize {
+        self.count_lines(DiffLineKind::Removed)
+    }
+
+    fn count_lines(&self, kind: DiffLineKind) -> usize {
+        self.hunks
+            .iter()
+            .flat_map(|h| &h.lines)
+            .filter(|l| l.kind == kind)
+            .count()
+    }
+
+    /// Row offset of a hunk when all hunks are rendered one after another
+    /// (header line + body lines each). Used to scroll the selected hunk into view.
+    pub fn hunk_offset(&self, hunk_idx: usize) -> usize {
+        self.hunks
+            .iter()
+            .take(hunk_idx)
+            .map(|h| h.lines.len() + 1)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(kind: DiffLineKind) -> DiffLine {
+        DiffLine {
+            kind,
+            content: "x".into(),
+        }
+    }
+
+    fn make_file() -> FileDiff {
+        FileDiff {
+            worktree: PathBuf::from("/tmp/wt"),
+            repo: None,
+            path: "src/lib.rs".into(),
+            change: FileChange::Modified,
+            binary: false,
+            hunks: vec![
+                DiffHunk {
+                    header: "@@ -1,2 +1,3 @@".into(),
+                    new_start: 1,
+                    new_lines: 3,
+                    lines: vec![
+                        line(DiffLineKind::Context),
+                        line(DiffLineKind::Added),
+                        line(DiffLineKind::Context),
+                    ],
+                },
+                DiffHunk {
+                    header: "@@ -10,2 +11,1 @@".into(),
+                    new_start: 11,
+                    new_lines: 1,
+                    lines: vec![line(DiffLineKind::Removed), line(DiffLineKind::Context)],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_line_counts() {
+        let file = make_file();
+        assert_eq!(file.additions(), 1);
+        assert_eq!(file.deletions(), 1);
+    }
+
+    #[test]
+    fn test_hunk_offset() {
+        let file = make_file();
+        assert_eq!(file.hunk_offset(0), 0);
+        assert_eq!(file.hunk_offset(1), 4);
+        assert_eq!(file.hunk_offset(2), 7);
+    }
+
+    #[test]
+    fn test_display_path() {
+        let mut file = make_file();
+        assert_eq!(file.display_path(), "src/lib.rs");
+        file.repo = Some("api".into());
+        assert_eq!(file.display_path(), "api/src/lib.rs");
+    }
+
+    #[test]
+    fn test_scope_toggle() {
+        assert_eq!(DiffScope::Base.toggle(), DiffScope::Head);
+        assert_eq!(DiffScope::Head.toggle(), DiffScope::Base);
+    }
+}
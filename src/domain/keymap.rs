@@ -0,0 +1,568 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A key, without modifiers. Letters are case-sensitive: `A` is Shift-a.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Esc,
+    Tab,
+    BackTab,
+    Backspace,
+    Delete,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    F(u8),
+}
+
+/// A key with the modifiers held for it, e.g. `ctrl-p`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub key: Key,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl KeyChord {
+    pub fn new(key: Key) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            alt: false,
+        }
+    }
+
+    /// Parse a chord as written in config: a character (`n`, `A`, `/`) or a
+    /// key name (`enter`, `esc`, `tab`, `backtab`, `backspace`, `delete`,
+    /// `up`, `down`, `left`, `right`, `home`, `end`, `pageup`, `pagedown`,
+    /// `space`, `f1`..`f20`), after any `ctrl-` and `alt-` prefixes.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut chord = Self::new(Key::Esc);
+        let mut rest = s;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            if lower.starts_with("ctrl-") && rest.len() > 5 {
+                chord.ctrl = true;
+                rest = &rest[5..];
+            } else if lower.starts_with("alt-") && rest.len() > 4 {
+                chord.alt = true;
+                rest = &rest[4..];
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = rest.chars();
+        chord.key = match (chars.next(), chars.next()) {
+            (Some(c), None) => Key::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" | "return" => Key::Enter,
+                "esc" | "escape" => Key::Esc,
+                "tab" => Key::Tab,
+                "backtab" | "shift-tab" => Key::BackTab,
+                "backspace" => Key::Backspace,
+                "delete" | "del" => Key::Delete,
+                "up" => Key::Up,
+                "down" => Key::Down,
+                "left" => Key::Left,
+                "right" => Key::Right,
+                "home" => Key::Home,
+                "end" => Key::End,
+                "pageup" => Key::PageUp,
+                "pagedown" => Key::PageDown,
+                "space" => Key::Char(' '),
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=20) => Key::F(n),
+                    _ => return Err(format!("unknown key '{s}'")),
+                },
+            },
+        };
+        Ok(chord)
+    }
+
+    /// Short label for hints and the help overlay, e.g. `⏎` or `Ctrl-p`.
+    pub fn label(&self) -> String {
+        let key = match self.key {
+            Key::Char(' ') => "Space".to_string(),
+            Key::Char(c) => c.to_string(),
+            Key::Enter => "⏎".into(),
+            Key::Esc => "Esc".into(),
+            Key::Tab => "Tab".into(),
+            Key::BackTab => "S-Tab".into(),
+            Key::Backspace => "⌫".into(),
+            Key::Delete => "Del".into(),
+            Key::Up => "↑".into(),
+            Key::Down => "↓".into(),
+            Key::Left => "←".into(),
+            Key::Right => "→".into(),
+            Key::Home => "Home".into(),
+            Key::End => "End".into(),
+            Key::PageUp => "PgUp".into(),
+            Key::PageDown => "PgDn".into(),
+            Key::F(n) => format!("F{n}"),
+        };
+        let mut label = String::new();
+        if self.ctrl {
+            label.push_str("Ctrl-");
+        }
+        if self.alt {
+            label.push_str("Alt-");
+        }
+        label + &key
+    }
+}
+
+/// xterm's `\e[N~` function keys: N, the key xterm calls it, and the key
+/// crossterm reports when it arrives. From `\e[28~` on crossterm numbers
+/// them lower (`\e[29~` is xterm's F16 but arrives as F14).
+const CSI_FUNCTION_KEYS: [(u8, u8, u8); 20] = [
+    (11, 1, 1),
+    (12, 2, 2),
+    (13, 3, 3),
+    (14, 4, 4),
+    (15, 5, 5),
+    (17, 6, 6),
+    (18, 7, 7),
+    (19, 8, 8),
+    (20, 9, 9),
+    (21, 10, 10),
+    (23, 11, 11),
+    (24, 12, 12),
+    (25, 13, 13),
+    (26, 14, 14),
+    (28, 15, 13),
+    (29, 16, 14),
+    (31, 17, 14),
+    (32, 18, 15),
+    (33, 19, 16),
+    (34, 20, 17),
+];
+
+/// The function key a CSI suffix like `[29~` is sent for: its number as
+/// xterm names it (16) and as the dashboard receives it (14).
+pub fn csi_function_key(suffix: &str) -> Option<(u8, u8)> {
+    let n: u8 = suffix.strip_prefix('[')?.strip_suffix('~')?.parse().ok()?;
+    CSI_FUNCTION_KEYS
+        .iter()
+        .find(|(csi, ..)| *csi == n)
+        .map(|&(_, name, received)| (name, received))
+}
+
+/// Where in the TUI a key is pressed. Each action only applies in some.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    Dashboard,
+    Overview,
+    Diff,
+    /// A headless agent's output, full-screen
+    Output,
+}
+
+impl View {
+    pub const ALL: &[View] = &[Self::Dashboard, Self::Overview, Self::Diff, Self::Output];
+}
+
+/// Something a key does. Names are what `[keys]` in config rebinds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    Back,
+    Up,
+    Down,
+    Left,
+    Right,
+    Help,
+    SwitchFocus,
+    Open,
+    Kill,
+    NewSession,
+    Spawn,
+    Permission,
+    ApproveAll,
+    Message,
+    Search,
+    Palette,
+    Layout,
+    PopPane,
+    Copy,
+    Diff,
+    Sync,
+    Refresh,
+    NextHunk,
+    PrevHunk,
+    ToggleBase,
+    Stage,
+    Discard,
+}
+
+impl Action {
+    /// Every action, in the order the help overlay lists them.
+    pub const ALL: &[Action] = &[
+        Self::Quit,
+        Self::Back,
+        Self::Up,
+        Self::Down,
+        Self::Left,
+        Self::Right,
+        Self::Help,
+        Self::SwitchFocus,
+        Self::Open,
+        Self::Kill,
+        Self::NewSession,
+        Self::Spawn,
+        Self::Permission,
+        Self::ApproveAll,
+        Self::Message,
+        Self::Search,
+        Self::Palette,
+        Self::Layout,
+        Self::PopPane,
+        Self::Copy,
+        Self::Diff,
+        Self::Sync,
+        Self::Refresh,
+        Self::NextHunk,
+        Self::PrevHunk,
+        Self::ToggleBase,
+        Self::Stage,
+        Self::Discard,
+    ];
+
+    /// Name in `[keys]`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Quit => "quit",
+            Self::Back => "back",
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Help => "help",
+            Self::SwitchFocus => "switch_focus",
+            Self::Open => "open",
+            Self::Kill => "kill",
+            Self::NewSession => "new_session",
+            Self::Spawn => "spawn",
+            Self::Permission => "permission",
+            Self::ApproveAll => "approve_all",
+            Self::Message => "message",
+            Self::Search => "search",
+            Self::Palette => "palette",
+            Self::Layout => "layout",
+            Self::PopPane => "pop_pane",
+            Self::Copy => "copy",
+            Self::Diff => "diff",
+            Self::Sync => "sync",
+            Self::Refresh => "refresh",
+            Self::NextHunk => "next_hunk",
+            Self::PrevHunk => "prev_hunk",
+            Self::ToggleBase => "toggle_base",
+            Self::Stage => "stage",
+            Self::Discard => "discard",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Quit => "Quit vibe",
+            Self::Back => "Back",
+            Self::Up => "Move up",
+            Self::Down => "Move down",
+            Self::Left => "Move left",
+            Self::Right => "Move right",
+            Self::Help => "Show these keys",
+            Self::SwitchFocus => "Switch between sessions and agents",
+            Self::Open => "Open the session or agent",
+            Self::Kill => "Kill the session, or kill/remove the agent",
+            Self::NewSession => "New session",
+            Self::Spawn => "Spawn an agent in the session",
+            Self::Permission => "Answer the session's permission prompt",
+            Self::ApproveAll => "Approve every pending permission prompt",
+            Self::Message => "Message the session's Claude",
+            Self::Search => "Search transcripts",
+            Self::Palette => "Find a session, agent, template, plan or action",
+            Self::Layout => "Cycle the session window's layout",
+            Self::PopPane => "Pop the agent into its own window, or back",
+            Self::Copy => "Copy the agent's output",
+            Self::Diff => "Diff of the session's worktree",
+            Self::Sync => "Sync the session onto its base branch",
+            Self::Refresh => "Refresh",
+            Self::NextHunk => "Next hunk",
+            Self::PrevHunk => "Previous hunk",
+            Self::ToggleBase => "Compare with the base branch or HEAD",
+            Self::Stage => "Stage the hunk",
+            Self::Discard => "Discard the hunk",
+        }
+    }
+
+    /// Views the action applies in.
+    pub fn views(self) -> &'static [View] {
+        use View::*;
+        match self {
+            Self::Quit | Self::Back | Self::Up | Self::Down | Self::Help => View::ALL,
+            Self::Left | Self::Right => &[Overview],
+            Self::Open | Self::Permission | Self::ApproveAll => &[Dashboard, Overview],
+            Self::Copy => &[Dashboard, Output],
+            Self::Refresh => &[Dashboard, Diff],
+            Self::SwitchFocus
+            | Self::Kill
+            | Self::NewSession
+            | Self::Spawn
+            | Self::Message
+            | Self::Search
+            | Self::Palette
+            | Self::Layout
+            | Self::PopPane
+            | Self::Diff
+            | Self::Sync => &[Dashboard],
+            Self::NextHunk | Self::PrevHunk | Self::ToggleBase | Self::Stage | Self::Discard => &[Diff],
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Self::Quit => &["q"],
+            Self::Back => &["esc"],
+            Self::Up => &["k", "up"],
+            Self::Down => &["j", "down"],
+            Self::Left => &["h", "left"],
+            Self::Right => &["l", "right"],
+            Self::Help => &["?"],
+            Self::SwitchFocus => &["tab", "backtab"],
+            Self::Open => &["enter"],
+            Self::Kill => &["backspace"],
+            Self::NewSession => &["n"],
+            Self::Spawn => &["s"],
+            Self::Permission => &["p"],
+            Self::ApproveAll => &["A"],
+            Self::Message => &["m"],
+            Self::Search => &["/"],
+            Self::Palette => &[":", "ctrl-p"],
+            Self::Layout => &["L"],
+            Self::PopPane => &["z"],
+            Self::Copy => &["c"],
+            Self::Diff => &["d"],
+            // Reachable from the palette
+            Self::Sync => &[],
+            Self::Refresh => &["r"],
+            Self::NextHunk => &["n", "right"],
+            Self::PrevHunk => &["p", "left"],
+            Self::ToggleBase => &["tab"],
+            Self::Stage => &["a"],
+            Self::Discard => &["x"],
+        }
+    }
+}
+
+/// Keys for one action in `[keys]`: `"n"` or `["n", "ctrl-n"]`. An empty
+/// list unbinds it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    fn as_slice(&self) -> &[String] {
+        match self {
+            Self::One(key) => std::slice::from_ref(key),
+            Self::Many(keys) => keys,
+        }
+    }
+}
+
+/// The chords bound to each action: defaults, with `[keys]` on top.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: BTreeMap<Action, Vec<KeyChord>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .iter()
+            .map(|&action| {
+                let chords = action
+                    .default_keys()
+                    .iter()
+                    .map(|k| KeyChord::parse(k).expect("default key parses"))
+                    .collect();
+                (action, chords)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// The defaults with `overrides` replacing the keys of the actions they
+    /// name. Every key that doesn't parse and every chord two actions share
+    /// in some view is reported.
+    pub fn new(overrides: &BTreeMap<Action, KeyList>) -> Result<Self, Vec<String>> {
+        let mut keymap = Self::default();
+        let mut problems = Vec::new();
+        for (&action, keys) in overrides {
+            let mut chords = Vec::new();
+            for key in keys.as_slice() {
+                match KeyChord::parse(key) {
+                    Ok(chord) => chords.push(chord),
+                    Err(e) => problems.push(format!("{}: {e}", action.name())),
+                }
+            }
+            keymap.bindings.insert(action, chords);
+        }
+        problems.extend(keymap.conflicts());
+        if problems.is_empty() {
+            Ok(keymap)
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// The action `chord` triggers in `view`, if any.
+    pub fn action(&self, view: View, chord: KeyChord) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(action, chords)| action.views().contains(&view) && chords.contains(&chord))
+            .map(|(&action, _)| action)
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyChord] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Label of an action's first key, for hints. Empty when unbound.
+    pub fn label(&self, action: Action) -> String {
+        self.keys(action).first().map(KeyChord::label).unwrap_or_default()
+    }
+
+    /// (keys, description) of the bound actions that apply in `view`, in
+    /// [`Action::ALL`] order.
+    pub fn help(&self, view: View) -> Vec<(String, &'static str)> {
+        Action::ALL
+            .iter()
+            .filter(|action| action.views().contains(&view) && !self.keys(**action).is_empty())
+            .map(|&action| {
+                let keys: Vec<String> = self.keys(action).iter().map(KeyChord::label).collect();
+                (keys.join(" "), action.description())
+            })
+            .collect()
+    }
+
+    /// Chords bound to two actions that apply in the same view.
+    fn conflicts(&self) -> Vec<String> {
+        let mut conflicts = Vec::new();
+        let actions: Vec<_> = self.bindings.iter().collect();
+        for (i, (a, a_chords)) in actions.iter().enumerate() {
+            for (b, b_chords) in &actions[i + 1..] {
+                if !a.views().iter().any(|v| b.views().contains(v)) {
+                    continue;
+                }
+                for chord in a_chords.iter().filter(|c| b_chords.contains(c)) {
+                    conflicts.push(format!(
+                        "'{}' is bound to both {} and {}",
+                        chord.label(),
+                        a.name(),
+                        b.name()
+                    ));
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chord() {
+        assert_eq!(KeyChord::parse("n").unwrap(), KeyChord::new(Key::Char('n')));
+        assert_eq!(KeyChord::parse("A").unwrap().key, Key::Char('A'));
+        let ctrl_p = KeyChord::parse("Ctrl-p").unwrap();
+        assert!(ctrl_p.ctrl && !ctrl_p.alt);
+        assert_eq!(ctrl_p.key, Key::Char('p'));
+        assert_eq!(ctrl_p.label(), "Ctrl-p");
+        assert_eq!(KeyChord::parse("enter").unwrap().label(), "⏎");
+        assert_eq!(KeyChord::parse("f5").unwrap().key, Key::F(5));
+        // A lone "-" is a key, not an empty modifier
+        assert_eq!(KeyChord::parse("-").unwrap().key, Key::Char('-'));
+        assert!(KeyChord::parse("hyper-x").is_err());
+        assert!(KeyChord::parse("f42").is_err());
+    }
+
+    #[test]
+    fn test_csi_function_key() {
+        assert_eq!(csi_function_key("[15~"), Some((5, 5)));
+        // The default dashboard and overview keys
+        assert_eq!(csi_function_key("[29~"), Some((16, 14)));
+        assert_eq!(csi_function_key("[33~"), Some((19, 16)));
+        assert_eq!(csi_function_key("[16~"), None);
+        assert_eq!(csi_function_key("29~"), None);
+    }
+
+    #[test]
+    fn test_defaults_resolve_per_view() {
+        let keymap = Keymap::new(&BTreeMap::new()).unwrap();
+        let n = KeyChord::new(Key::Char('n'));
+        assert_eq!(keymap.action(View::Dashboard, n), Some(Action::NewSession));
+        assert_eq!(keymap.action(View::Diff, n), Some(Action::NextHunk));
+        assert_eq!(keymap.action(View::Output, n), None);
+        assert_eq!(keymap.label(Action::Kill), "⌫");
+        let ctrl_p = KeyChord::parse("ctrl-p").unwrap();
+        assert_eq!(keymap.action(View::Dashboard, ctrl_p), Some(Action::Palette));
+        // Unbound until configured
+        assert_eq!(keymap.label(Action::Sync), "");
+    }
+
+    #[test]
+    fn test_overrides_and_conflicts() {
+        let overrides: BTreeMap<Action, KeyList> = toml::from_str(
+            r#"
+            new_session = "N"
+            down = ["j", "ctrl-n"]
+            layout = []
+            "#,
+        )
+        .unwrap();
+        let keymap = Keymap::new(&overrides).unwrap();
+        assert_eq!(keymap.action(View::Dashboard, KeyChord::new(Key::Char('N'))), Some(Action::NewSession));
+        assert_eq!(keymap.action(View::Dashboard, KeyChord::new(Key::Char('n'))), None);
+        assert_eq!(keymap.action(View::Dashboard, KeyChord::new(Key::Down)), None);
+        assert_eq!(keymap.label(Action::Layout), "");
+        assert!(!keymap.help(View::Dashboard).iter().any(|(_, d)| *d == Action::Layout.description()));
+
+        // Shared only in views neither applies in together: fine
+        let ok: BTreeMap<Action, KeyList> = toml::from_str(r#"stage = "c""#).unwrap();
+        assert!(Keymap::new(&ok).is_ok());
+
+        let clash: BTreeMap<Action, KeyList> = toml::from_str(
+            r#"
+            spawn = "m"
+            quit = "ctrl-q"
+            copy = "nope"
+            "#,
+        )
+        .unwrap();
+        let problems = Keymap::new(&clash).unwrap_err();
+        assert_eq!(
+            problems,
+            vec![
+                "copy: unknown key 'nope'".to_string(),
+                "'m' is bound to both spawn and message".to_string(),
+            ]
+        );
+
+        assert!(toml::from_str::<BTreeMap<Action, KeyList>>(r#"teleport = "t""#).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+/// How interactive agents are arranged around a session's Claude.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PaneLayout {
+    /// Session's Claude on the left, agents stacked on the right.
+    #[default]
+    MainVertical,
+    /// All panes in an even grid.
+    Tiled,
+    /// All panes side by side.
+    EvenHorizontal,
+    /// Each agent in its own `{session}~{agent}` window.
+    Window,
+}
+
+impl PaneLayout {
+    /// Layouts `select-layout` can apply to a session window, in the order
+    /// the dashboard cycles through them.
+    pub const CYCLE: &[PaneLayout] = &[Self::MainVertical, Self::Tiled, Self::EvenHorizontal];
+
+    /// Name `tmux select-layout` takes. `None` for agents in their own window.
+    pub fn tmux_name(self) -> Option<&'static str> {
+        match self {
+            Self::MainVertical => Some("main-vertical"),
+            Self::Tiled => Some("tiled"),
+            Self::EvenHorizontal => Some("even-horizontal"),
+            Self::Window => None,
+        }
+    }
+
+    /// The layout after this one in [`Self::CYCLE`].
+    pub fn next(self) -> Self {
+        let idx = Self::CYCLE.iter().position(|&l| l == self);
+        match idx {
+            Some(i) => Self::CYCLE[(i + 1) % Self::CYCLE.len()],
+            None => Self::CYCLE[0],
+        }
+    }
+}
+
+impl std::fmt::Display for PaneLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.tmux_name().unwrap_or("window"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_cycle() {
+        #[derive(Deserialize)]
+        struct Config {
+            layout: PaneLayout,
+        }
+        let parse = |s: &str| toml::from_str::<Config>(&format!("layout = \"{s}\"")).unwrap().layout;
+        assert_eq!(parse("main-vertical"), PaneLayout::MainVertical);
+        assert_eq!(parse("even-horizontal"), PaneLayout::EvenHorizontal);
+        assert_eq!(parse("window"), PaneLayout::Window);
+        assert!(toml::from_str::<Config>("layout = \"sideways\"").is_err());
+
+        assert_eq!(PaneLayout::MainVertical.next(), PaneLayout::Tiled);
+        assert_eq!(PaneLayout::EvenHorizontal.next(), PaneLayout::MainVertical);
+        // Agents in windows leave the session window with one pane; cycling
+        // starts over
+        assert_eq!(PaneLayout::Window.next(), PaneLayout::MainVertical);
+        assert_eq!(PaneLayout::Window.to_string(), "window");
+    }
+}
@@ -1,5 +1,18 @@
+pub mod activity;
 pub mod agent;
+pub mod ci;
+pub mod diff;
+pub mod keymap;
+pub mod layout;
+pub mod overlap;
+pub mod palette;
+pub mod permission;
 pub mod plan;
+pub mod pr;
+pub mod review;
 pub mod session;
+pub mod sync;
+pub mod syntax;
 pub mod template;
+pub mod transcript;
 pub mod workspace;
@@ -0,0 +1,138 @@
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+/// Files one session has changed in one repo since forking from its base.
+#[derive(Debug, Clone)]
+pub struct ChangeSet {
+    pub session: String,
+    /// Repo name for multi-repo sessions, `None` for single-repo.
+    pub repo: Option<String>,
+    pub branch: String,
+    pub worktree: PathBuf,
+    pub files: BTreeSet<String>,
+}
+
+/// Two sessions changing the same files in the same repo.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionOverlap {
+    pub sessions: [String; 2],
+    pub repo: Option<String>,
+    pub files: Vec<String>,
+    /// Paths a trial merge of the two branches conflicts on. `None` if no
+    /// trial merge was run.
+    pub merge_conflicts: Option<Vec<String>>,
+}
+
+impl SessionOverlap {
+    pub fn involves(&self, session: &str) -> bool {
+        self.sessions.iter().any(|s| s == session)
+    }
+
+    /// The session on the other side of the overlap from `session`.
+    pub fn other(&self, session: &str) -> &str {
+        if self.sessions[0] == session {
+            &self.sessions[1]
+        } else {
+            &self.sessions[0]
+        }
+    }
+
+    /// True if a trial merge found real conflicts (not just shared files).
+    pub fn conflicts(&self) -> bool {
+        self.merge_conflicts.as_ref().is_some_and(|c| !c.is_empty())
+    }
+
+    /// Short file summary, e.g. "src/auth.rs" or "src/auth.rs (+2 more)".
+    pub fn files_summary(&self) -> String {
+        let repo_prefix = self
+            .repo
+            .as_deref()
+            .map(|r| format!("{r}/"))
+            .unwrap_or_default();
+        match self.files.as_slice() {
+            [] => String::new(),
+            [only] => format!("{repo_prefix}{only}"),
+            [first, rest @ ..] => format!("{repo_prefix}{first} (+{} more)", rest.len()),
+        }
+    }
+}
+
+/// Pair up change sets from different sessions in the same repo that share
+/// at least one changed file.
+pub fn find_overlaps(changes: &[ChangeSet]) -> Vec<SessionOverlap> {
+    let mut overlaps = Vec::new();
+    for (i, a) in changes.iter().enumerate() {
+        for b in &changes[i + 1..] {
+            if a.session == b.session || a.repo != b.repo {
+                continue;
+            }
+            let files: Vec<String> = a.files.intersection(&b.files).cloned().collect();
+            if files.is_empty() {
+                continue;
+            }
+            overlaps.push(SessionOverlap {
+                sessions: [a.session.clone(), b.session.clone()],
+                repo: a.repo.clone(),
+                files,
+                merge_conflicts: None,
+            });
+        }
+    }
+    overlaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes(session: &str, repo: Option<&str>, files: &[&str]) -> ChangeSet {
+        ChangeSet {
+            session: session.into(),
+            repo: repo.map(|r| r.into()),
+            branch: format!("feat/{session}"),
+            worktree: PathBuf::from(format!("/tmp/{session}")),
+            files: files.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_find_overlaps_shared_files() {
+        let sets = vec![
+            changes("a", None, &["src/auth.rs", "src/main.rs"]),
+            changes("b", None, &["src/auth.rs", "README.md"]),
+            changes("c", None, &["docs/guide.md"]),
+        ];
+        let overlaps = find_overlaps(&sets);
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(overlaps[0].sessions, ["a".to_string(), "b".to_string()]);
+        assert_eq!(overlaps[0].files, vec!["src/auth.rs".to_string()]);
+        assert!(overlaps[0].involves("b"));
+        assert!(!overlaps[0].involves("c"));
+        assert_eq!(overlaps[0].other("a"), "b");
+    }
+
+    #[test]
+    fn test_find_overlaps_ignores_other_repos() {
+        let sets = vec![
+            changes("a", Some("api"), &["src/lib.rs"]),
+            changes("b", Some("web"), &["src/lib.rs"]),
+        ];
+        assert!(find_overlaps(&sets).is_empty());
+    }
+
+    #[test]
+    fn test_files_summary() {
+        let mut overlap = SessionOverlap {
+            sessions: ["a".into(), "b".into()],
+            repo: Some("api".into()),
+            files: vec!["x.rs".into()],
+            merge_conflicts: Some(vec![]),
+        };
+        assert_eq!(overlap.files_summary(), "api/x.rs");
+        assert!(!overlap.conflicts());
+        overlap.files.push("y.rs".into());
+        overlap.files.push("z.rs".into());
+        assert_eq!(overlap.files_summary(), "api/x.rs (+2 more)");
+    }
+}
@@ -0,0 +1,99 @@
+//! Fuzzy matching for the dashboard's command palette.
+
+/// How well `candidate` matches `query`; higher is better. `None` unless
+/// every character of the query (spaces aside) appears in the candidate in
+/// order, ignoring case. Matches at the start of a word and runs of
+/// consecutive characters score more, so `fb` ranks `fix-bug` above `fooba`.
+pub fn score(query: &str, candidate: &str) -> Option<i64> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(lower)
+        .collect();
+    let Some(&first) = query.first() else {
+        return Some(0);
+    };
+    let original: Vec<char> = candidate.chars().collect();
+    let chars: Vec<char> = original.iter().copied().map(lower).collect();
+    let word_start: Vec<bool> = (0..original.len())
+        .map(|i| {
+            i == 0
+                || !original[i - 1].is_alphanumeric()
+                || (original[i - 1].is_lowercase() && original[i].is_uppercase())
+        })
+        .collect();
+
+    // Try each place the query could start and keep the best
+    (0..chars.len())
+        .filter(|&i| chars[i] == first)
+        .filter_map(|start| score_from(&query, &chars, &word_start, start))
+        .max()
+}
+
+/// Score of matching `query` greedily from `start`.
+fn score_from(query: &[char], chars: &[char], word_start: &[bool], start: usize) -> Option<i64> {
+    // A match further in counts a little less
+    let mut score = -(start.min(10) as i64);
+    let mut pos = start;
+    let mut prev: Option<usize> = None;
+    for &q in query {
+        let i = (pos..chars.len()).find(|&i| chars[i] == q)?;
+        score += 1;
+        if word_start[i] {
+            score += 8;
+        }
+        match prev {
+            Some(p) if p + 1 == i => score += 8,
+            // Skipping ahead breaks the run
+            Some(_) => score -= 3,
+            None => {}
+        }
+        prev = Some(i);
+        pos = i + 1;
+    }
+    Some(score)
+}
+
+fn lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Indices of the `candidates` that match `query`, best first. Equal scores
+/// keep their order, so an empty query lists everything as given.
+pub fn rank<'a>(query: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<usize> {
+    let mut scored: Vec<(i64, usize)> = candidates
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, candidate)| score(query, candidate).map(|s| (s, i)))
+        .collect();
+    scored.sort_by_key(|&(s, _)| std::cmp::Reverse(s));
+    scored.into_iter().map(|(_, i)| i).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_needs_every_char_in_order() {
+        assert!(score("fb", "fix-bug").is_some());
+        assert!(score("FB", "fix-bug").is_some());
+        assert!(score("f b", "fix-bug").is_some());
+        assert!(score("bf", "fix-bug").is_none());
+        assert!(score("fbx", "fix-bug").is_none());
+        assert_eq!(score("", "anything"), Some(0));
+        assert!(score("x", "").is_none());
+    }
+
+    #[test]
+    fn test_rank_prefers_word_starts_and_runs() {
+        let candidates = ["fooba", "fix-bug", "auth-flow"];
+        assert_eq!(rank("fb", candidates), vec![1, 0]);
+        assert_eq!(rank("fix", ["prefix", "fix-bug"]), vec![1, 0]);
+        // A run beats the same letters scattered across words
+        assert_eq!(rank("auth", ["a-unit-test-helper", "oauth"]), vec![1, 0]);
+        // Nothing typed: everything, in order
+        assert_eq!(rank("", candidates), vec![0, 1, 2]);
+        assert!(rank("zzz", candidates).is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+/// Lines from the bottom of a pane searched for a prompt.
+const TAIL_LINES: usize = 15;
+/// Lines above the question kept as context when there is no box around it.
+const CONTEXT_LINES: usize = 6;
+
+/// How a prompt takes its answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptStyle {
+    /// Claude Code's numbered menu ("❯ 1. Yes" ... "No (esc)").
+    Menu,
+    /// A `[Y/n]` / `[y/N]` question answered with a letter and Enter.
+    YesNo,
+}
+
+/// A permission or confirmation prompt waiting in a pane.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PermissionPrompt {
+    pub style: PromptStyle,
+    /// The prompt as shown, without box borders.
+    pub text: String,
+}
+
+impl PermissionPrompt {
+    /// Detect a prompt at the bottom of captured pane content.
+    pub fn detect(content: &str) -> Option<Self> {
+        let lines: Vec<&str> = content.lines().collect();
+        let tail = &lines[lines.len().saturating_sub(TAIL_LINES)..];
+        let question = tail.iter().rposition(|line| is_question(line))?;
+        let style = if tail[question + 1..].iter().any(|line| is_menu_option(line)) {
+            PromptStyle::Menu
+        } else {
+            PromptStyle::YesNo
+        };
+        // Output after the prompt means it has already been answered
+        let still_open = match style {
+            PromptStyle::Menu => tail
+                .iter()
+                .rev()
+                .find(|line| !line.trim().is_empty())
+                .is_some_and(|line| is_menu_end(line)),
+            PromptStyle::YesNo => question + 5 >= tail.len(),
+        };
+        if !still_open {
+            return None;
+        }
+
+        // Start at the top of the box the question is in, if any
+        let start = tail[..question]
+            .iter()
+            .rposition(|line| line.trim_start().starts_with('╭'))
+            .unwrap_or(question.saturating_sub(CONTEXT_LINES));
+        let text = tail[start..]
+            .iter()
+            .map(|line| strip_border(line))
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Some(Self { style, text })
+    }
+
+    /// A prompt known only from Claude's permission notification, for when
+    /// the pane can't be read. Claude Code asks with a menu.
+    pub fn from_notification(message: Option<&str>) -> Self {
+        Self {
+            style: PromptStyle::Menu,
+            text: message.unwrap_or("Claude needs your permission").to_string(),
+        }
+    }
+
+    /// Keys (tmux `send-keys` names) that accept the prompt once.
+    pub fn approve_keys(&self) -> &'static [&'static str] {
+        match self.style {
+            PromptStyle::Menu => &["1"],
+            PromptStyle::YesNo => &["y", "Enter"],
+        }
+    }
+
+    /// Keys that decline the prompt.
+    pub fn deny_keys(&self) -> &'static [&'static str] {
+        match self.style {
+            PromptStyle::Menu => &["Escape"],
+            PromptStyle::YesNo => &["n", "Enter"],
+        }
+    }
+
+    /// The question line, for one-line summaries.
+    pub fn summary(&self) -> &str {
+        self.text
+            .lines()
+            .find(|line| is_question(line))
+            .or_else(|| self.text.lines().next())
+            .unwrap_or_default()
+    }
+}
+
+fn is_question(line: &str) -> bool {
+    line.contains("[Y/n]")
+        || line.contains("[y/N]")
+        || line.contains("Do you want to")
+        || line.contains("allow this action")
+}
+
+fn is_menu_option(line: &str) -> bool {
+    let option = strip_border(line);
+    let option = option.trim_start_matches('❯').trim_start();
+    option.starts_with("1. Yes")
+}
+
+/// Last line of a menu prompt still waiting: its box's bottom border, an
+/// option, or the "Esc to cancel" hint.
+fn is_menu_end(line: &str) -> bool {
+    let trimmed = line.trim();
+    let option = strip_border(line).trim_start_matches('❯').trim_start();
+    trimmed.starts_with('╰')
+        || option.split_once(". ").is_some_and(|(n, _)| n.parse::<u8>().is_ok())
+        || trimmed.to_lowercase().contains("esc")
+}
+
+fn strip_border(line: &str) -> &str {
+    line.trim_matches(|c: char| c.is_whitespace() || "│╭╮╰╯─".contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_claude_menu() {
+        let content = "⏺ I'll remove the build dir.\n\
+                       \n\
+                       ╭──────────────────────────────────╮\n\
+                       │ Bash command                     │\n\
+                       │                                  │\n\
+                       │   rm -rf target                  │\n\
+                       │                                  │\n\
+                       │ Do you want to proceed?          │\n\
+                       │ ❯ 1. Yes                         │\n\
+                       │   2. Yes, and don't ask again    │\n\
+                       │   3. No, and tell Claude what to do differently (esc) │\n\
+                       ╰──────────────────────────────────╯\n";
+        let prompt = PermissionPrompt::detect(content).unwrap();
+        assert_eq!(prompt.style, PromptStyle::Menu);
+        assert!(prompt.text.starts_with("Bash command\nrm -rf target"));
+        assert!(!prompt.text.contains("I'll remove"));
+        assert_eq!(prompt.summary(), "Do you want to proceed?");
+        assert_eq!(prompt.approve_keys(), ["1"]);
+        assert_eq!(prompt.deny_keys(), ["Escape"]);
+
+        // Answered: Claude carried on below the box
+        let answered = format!("{content}⏺ Removed target.\n");
+        assert!(PermissionPrompt::detect(&answered).is_none());
+    }
+
+    #[test]
+    fn test_detect_yes_no() {
+        let content = "Installing deps\nOverwrite existing lockfile? [y/N] ";
+        let prompt = PermissionPrompt::detect(content).unwrap();
+        assert_eq!(prompt.style, PromptStyle::YesNo);
+        assert_eq!(prompt.approve_keys(), ["y", "Enter"]);
+
+        // A question scrolled up with no menu under it has been answered
+        let answered = format!("Overwrite? [y/N] y\n{}", "output\n".repeat(8));
+        assert!(PermissionPrompt::detect(&answered).is_none());
+        assert!(PermissionPrompt::detect("> ").is_none());
+    }
+}
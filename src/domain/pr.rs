@@ -0,0 +1,414 @@
+use serde::{Deserialize, Serialize};
+
+/// Markers around the cross-link section vibe maintains in PR bodies.
+const SET_START: &str = "<!-- vibe:pr-set -->";
+const SET_END: &str = "<!-- /vibe:pr-set -->";
+
+/// One PR of a session's PR set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrLink {
+    /// Repo name for multi-repo sessions, `None` for single-repo.
+    pub repo: Option<String>,
+    pub number: u64,
+    pub url: String,
+}
+
+impl PrLink {
+    pub fn label(&self) -> String {
+        match &self.repo {
+            Some(repo) => format!("{repo}#{}", self.number),
+            None => format!("#{}", self.number),
+        }
+    }
+}
+
+/// Markdown section listing every PR in the set, marking the one it is
+/// written into.
+pub fn pr_set_section(links: &[PrLink], this: &PrLink) -> String {
+    let mut section = format!("{SET_START}\n**Part of a multi-repo change:**\n\n");
+    for link in links {
+        let marker = if link == this { " (this PR)" } else { "" };
+        section.push_str(&format!("- [{}]({}){marker}\n", link.label(), link.url));
+    }
+    section.push_str(SET_END);
+    section
+}
+
+/// Insert `section` into a PR body, replacing the one from an earlier run.
+pub fn with_pr_set(body: &str, section: &str) -> String {
+    let existing = body
+        .find(SET_START)
+        .and_then(|start| body[start..].find(SET_END).map(|end| (start, start + end + SET_END.len())));
+    match existing {
+        Some((start, end)) => format!("{}{section}{}", &body[..start], &body[end..]),
+        None if body.trim().is_empty() => section.to_string(),
+        None => format!("{}\n\n{section}", body.trim_end()),
+    }
+}
+
+/// PR state as reported by `gh pr view --json`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrStatus {
+    pub number: u64,
+    #[serde(default)]
+    pub url: String,
+    /// OPEN, CLOSED or MERGED.
+    pub state: String,
+    #[serde(default)]
+    pub is_draft: bool,
+    /// APPROVED, CHANGES_REQUESTED, REVIEW_REQUIRED or empty.
+    #[serde(default)]
+    pub review_decision: Option<String>,
+    #[serde(default)]
+    pub status_check_rollup: Vec<CheckRun>,
+}
+
+/// A check run or commit status from `statusCheckRollup`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckRun {
+    /// Check run name (`context` for commit statuses).
+    #[serde(default, alias = "context")]
+    pub name: String,
+    /// Check runs: QUEUED, IN_PROGRESS, COMPLETED.
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Check runs: SUCCESS, FAILURE, CANCELLED, SKIPPED, ...
+    #[serde(default)]
+    pub conclusion: Option<String>,
+    /// Commit statuses: SUCCESS, FAILURE, ERROR, PENDING, EXPECTED.
+    #[serde(default)]
+    pub state: Option<String>,
+    /// Link to the run (`targetUrl` for commit statuses).
+    #[serde(default, alias = "targetUrl")]
+    pub details_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckOutcome {
+    Passed,
+    Failed,
+    Pending,
+}
+
+impl CheckRun {
+    pub fn outcome(&self) -> CheckOutcome {
+        let result = self
+            .conclusion
+            .as_deref()
+            .filter(|c| !c.is_empty())
+            .or(self.state.as_deref())
+            .unwrap_or_default();
+        match result {
+            "SUCCESS" | "NEUTRAL" | "SKIPPED" => CheckOutcome::Passed,
+            "FAILURE" | "ERROR" | "CANCELLED" | "TIMED_OUT" | "ACTION_REQUIRED"
+            | "STARTUP_FAILURE" | "STALE" => CheckOutcome::Failed,
+            _ => CheckOutcome::Pending,
+        }
+    }
+
+    /// GitHub Actions run id, from a `.../actions/runs/<id>/job/<id>` link.
+    /// `None` for checks from other CI systems.
+    pub fn run_id(&self) -> Option<u64> {
+        let url = self.details_url.as_deref()?;
+        let rest = &url[url.find("/actions/runs/")? + "/actions/runs/".len()..];
+        rest.split('/').next()?.parse().ok()
+    }
+}
+
+/// Counts of check outcomes for one PR.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CheckCounts {
+    pub passed: usize,
+    pub failed: usize,
+    pub pending: usize,
+}
+
+impl std::fmt::Display for CheckCounts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.passed + self.failed + self.pending == 0 {
+            return write!(f, "no checks");
+        }
+        write!(f, "{}✓ {}✗ {}…", self.passed, self.failed, self.pending)
+    }
+}
+
+impl PrStatus {
+    pub fn checks(&self) -> CheckCounts {
+        let mut counts = CheckCounts::default();
+        for check in &self.status_check_rollup {
+            match check.outcome() {
+                CheckOutcome::Passed => counts.passed += 1,
+                CheckOutcome::Failed => counts.failed += 1,
+                CheckOutcome::Pending => counts.pending += 1,
+            }
+        }
+        counts
+    }
+
+    /// State for display: "draft", "open", "merged" or "closed".
+    pub fn state_label(&self) -> String {
+        if self.is_draft && self.state == "OPEN" {
+            "draft".into()
+        } else {
+            self.state.to_lowercase()
+        }
+    }
+
+    /// Review decision for display, e.g. "approved" or "changes requested".
+    pub fn review_label(&self) -> String {
+        match self.review_decision.as_deref() {
+            None | Some("") => "no review".into(),
+            Some(decision) => decision.to_lowercase().replace('_', " "),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct Author {
+    #[serde(default)]
+    pub login: String,
+}
+
+/// Login of a comment author; GitHub reports deleted accounts as null.
+pub fn login(author: &Option<Author>) -> &str {
+    author.as_ref().map(|a| a.login.as_str()).unwrap_or("ghost")
+}
+
+/// Conversation comments and reviews from `gh pr view --json comments,reviews`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct PrComments {
+    #[serde(default)]
+    pub comments: Vec<PrComment>,
+    #[serde(default)]
+    pub reviews: Vec<PrReview>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PrComment {
+    #[serde(default)]
+    pub author: Option<Author>,
+    #[serde(default)]
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PrReview {
+    #[serde(default)]
+    pub author: Option<Author>,
+    /// APPROVED, CHANGES_REQUESTED, COMMENTED, ...
+    #[serde(default)]
+    pub state: String,
+    #[serde(default)]
+    pub body: String,
+}
+
+impl PrComments {
+    pub fn is_empty(&self) -> bool {
+        self.comments.is_empty() && self.reviews.iter().all(|r| r.body.trim().is_empty())
+    }
+
+    /// Markdown rendering for agent prompts.
+    pub fn to_markdown(&self) -> String {
+        let mut out = Vec::new();
+        for review in self.reviews.iter().filter(|r| !r.body.trim().is_empty()) {
+            out.push(format!(
+                "- @{} ({}): {}",
+                login(&review.author),
+                review.state.to_lowercase().replace('_', " "),
+                review.body.trim()
+            ));
+        }
+        for comment in &self.comments {
+            out.push(format!("- @{}: {}", login(&comment.author), comment.body.trim()));
+        }
+        out.join("\n")
+    }
+}
+
+/// An inline review thread, from the GraphQL `reviewThreads` connection.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewThread {
+    /// GraphQL node id, used to reply.
+    pub id: String,
+    #[serde(default)]
+    pub is_resolved: bool,
+    /// The code the thread is on has changed since.
+    #[serde(default)]
+    pub is_outdated: bool,
+    #[serde(default)]
+    pub path: String,
+    #[serde(default)]
+    pub line: Option<u32>,
+    #[serde(default)]
+    pub comments: ThreadComments,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ThreadComments {
+    #[serde(default)]
+    pub nodes: Vec<PrComment>,
+}
+
+impl ReviewThread {
+    pub fn location(&self) -> String {
+        match self.line {
+            Some(line) => format!("{}:{line}", self.path),
+            None => self.path.clone(),
+        }
+    }
+}
+
+/// Appended to the implementer prompt for `vibe pr address --reply`.
+pub const REPLY_INSTRUCTIONS: &str = r#"When you are done, finish your answer with a fenced JSON block listing a short reply for each thread you addressed, saying what you changed (or why you didn't):
+
+```json
+{"replies": [{"thread": 1, "reply": "Now returns an error instead of panicking."}]}
+```
+
+`thread` is the number from the checklist. Leave out threads you did not look at."#;
+
+/// The implementer's reply to one numbered thread.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ThreadReply {
+    pub thread: usize,
+    pub reply: String,
+}
+
+#[derive(Deserialize)]
+struct ThreadReplies {
+    #[serde(default)]
+    replies: Vec<ThreadReply>,
+}
+
+/// A thread reply as posted. The implementer commits without pushing, so
+/// the reply says the change isn't on the PR yet.
+pub fn pending_reply(reply: &str) -> String {
+    format!("{}\n\n_Committed locally, not pushed to this PR yet._", reply.trim())
+}
+
+/// Pull the thread replies out of the implementer's answer.
+pub fn parse_replies(text: &str) -> Result<Vec<ThreadReply>, String> {
+    let json = crate::domain::review::json_block(text).ok_or("no replies JSON in the agent's answer")?;
+    serde_json::from_str::<ThreadReplies>(json)
+        .map(|r| r.replies)
+        .map_err(|e| format!("invalid replies JSON: {e}"))
+}
+
+/// Numbered checklist of review threads for an implementer prompt. Thread
+/// numbers start at 1 and are what the agent refers to in its replies.
+pub fn threads_checklist(threads: &[ReviewThread]) -> String {
+    let mut out = String::new();
+    for (idx, thread) in threads.iter().enumerate() {
+        let outdated = if thread.is_outdated { " (outdated)" } else { "" };
+        out.push_str(&format!("- [ ] **{}.** `{}`{outdated}\n", idx + 1, thread.location()));
+        for comment in &thread.comments.nodes {
+            let body = comment.body.trim().replace('\n', "\n      ");
+            out.push_str(&format!("    - @{}: {body}\n", login(&comment.author)));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(repo: &str, number: u64) -> PrLink {
+        PrLink {
+            repo: Some(repo.into()),
+            number,
+            url: format!("https://github.com/org/{repo}/pull/{number}"),
+        }
+    }
+
+    #[test]
+    fn test_with_pr_set_appends_then_replaces() {
+        let links = vec![link("api", 12), link("web", 7)];
+        let section = pr_set_section(&links, &links[0]);
+        assert!(section.contains("[api#12](https://github.com/org/api/pull/12) (this PR)"));
+        assert!(section.contains("[web#7](https://github.com/org/web/pull/7)\n"));
+
+        let body = with_pr_set("Adds login.\n", &section);
+        assert!(body.starts_with("Adds login.\n\n<!-- vibe:pr-set -->"));
+
+        let links = vec![link("api", 12), link("web", 8)];
+        let updated = with_pr_set(&body, &pr_set_section(&links, &links[0]));
+        assert!(updated.contains("web#8"));
+        assert!(!updated.contains("web#7"));
+        assert_eq!(updated.matches(SET_START).count(), 1);
+        assert!(updated.starts_with("Adds login."));
+    }
+
+    #[test]
+    fn test_threads_checklist() {
+        let threads: Vec<ReviewThread> = serde_json::from_str(
+            r#"[
+                {"id": "T1", "isResolved": false, "isOutdated": false, "path": "src/a.rs", "line": 4,
+                 "comments": {"nodes": [
+                    {"author": {"login": "ana"}, "body": "Handle the error"},
+                    {"author": null, "body": "+1"}
+                 ]}},
+                {"id": "T2", "isResolved": false, "isOutdated": true, "path": "README.md", "line": null,
+                 "comments": {"nodes": [{"author": {"login": "ana"}, "body": "Typo"}]}}
+            ]"#,
+        )
+        .unwrap();
+        let checklist = threads_checklist(&threads);
+        assert!(checklist.starts_with("- [ ] **1.** `src/a.rs:4`\n    - @ana: Handle the error\n    - @ghost: +1\n"));
+        assert!(checklist.contains("- [ ] **2.** `README.md` (outdated)\n"));
+    }
+
+    #[test]
+    fn test_parse_replies() {
+        let text = "Done.\n```json\n{\"replies\": [{\"thread\": 2, \"reply\": \"Fixed\"}]}\n```";
+        assert_eq!(
+            parse_replies(text).unwrap(),
+            vec![ThreadReply {
+                thread: 2,
+                reply: "Fixed".into()
+            }]
+        );
+        assert!(parse_replies("nothing").is_err());
+        assert_eq!(
+            pending_reply("Fixed\n"),
+            "Fixed\n\n_Committed locally, not pushed to this PR yet._"
+        );
+    }
+
+    #[test]
+    fn test_pr_status_from_gh_json() {
+        let status: PrStatus = serde_json::from_str(
+            r#"{
+                "number": 12,
+                "state": "OPEN",
+                "isDraft": false,
+                "reviewDecision": "CHANGES_REQUESTED",
+                "statusCheckRollup": [
+                    {"__typename": "CheckRun", "name": "test", "status": "COMPLETED", "conclusion": "SUCCESS"},
+                    {"__typename": "CheckRun", "name": "lint", "status": "COMPLETED", "conclusion": "FAILURE",
+                     "detailsUrl": "https://github.com/o/r/actions/runs/987/job/654"},
+                    {"__typename": "CheckRun", "name": "e2e", "status": "IN_PROGRESS", "conclusion": ""},
+                    {"__typename": "StatusContext", "context": "ci/legacy", "state": "SUCCESS",
+                     "targetUrl": "https://ci.example.com/build/1"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            status.checks(),
+            CheckCounts {
+                passed: 2,
+                failed: 1,
+                pending: 1
+            }
+        );
+        assert_eq!(status.review_label(), "changes requested");
+        assert_eq!(status.state_label(), "open");
+        assert_eq!(status.status_check_rollup[3].name, "ci/legacy");
+        assert_eq!(status.status_check_rollup[1].run_id(), Some(987));
+        assert_eq!(status.status_check_rollup[3].run_id(), None);
+    }
+}
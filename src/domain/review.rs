@@ -0,0 +1,432 @@
+use crate::domain::pr::{self, Author};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+/// Appended to the review prompt for `vibe review --post` so the reviewer's
+/// answer can be turned into inline GitHub comments.
+pub const FINDINGS_INSTRUCTIONS: &str = r#"Finish your answer with your findings as a single fenced JSON block in exactly this shape:
+
+```json
+{
+  "summary": "One-paragraph overall assessment",
+  "findings": [
+    {"path": "src/lib.rs", "line": 42, "severity": "major", "comment": "What is wrong and how to fix it"}
+  ]
+}
+```
+
+`path` is relative to the repo root and `line` is a line number in the new version of the file that is part of the diff. `severity` is one of "critical", "major", "minor" or "nit". Only include findings worth a reviewer's comment."#;
+
+/// Structured review produced by the reviewer agent.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ReviewFindings {
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub findings: Vec<Finding>,
+}
+
+/// One inline comment.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Finding {
+    pub path: String,
+    pub line: u32,
+    #[serde(default = "default_severity")]
+    pub severity: String,
+    pub comment: String,
+}
+
+fn default_severity() -> String {
+    "minor".into()
+}
+
+impl Finding {
+    /// Comment text as posted, prefixed with the severity.
+    pub fn body(&self) -> String {
+        format!("**{}**: {}", self.severity, self.comment)
+    }
+}
+
+/// How the review is submitted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReviewEvent {
+    /// Left as a pending review for you to edit and submit on GitHub.
+    #[default]
+    Pending,
+    Comment,
+    RequestChanges,
+    Approve,
+}
+
+impl ReviewEvent {
+    /// Value of the `event` field in the GitHub API; `None` keeps the review pending.
+    pub fn api_value(self) -> Option<&'static str> {
+        match self {
+            Self::Pending => None,
+            Self::Comment => Some("COMMENT"),
+            Self::RequestChanges => Some("REQUEST_CHANGES"),
+            Self::Approve => Some("APPROVE"),
+        }
+    }
+}
+
+impl std::str::FromStr for ReviewEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "comment" => Ok(Self::Comment),
+            "request-changes" => Ok(Self::RequestChanges),
+            "approve" => Ok(Self::Approve),
+            other => Err(format!(
+                "unknown review event '{other}' (expected pending, comment, request-changes or approve)"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for ReviewEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pending => write!(f, "pending review"),
+            Self::Comment => write!(f, "comment"),
+            Self::RequestChanges => write!(f, "request changes"),
+            Self::Approve => write!(f, "approval"),
+        }
+    }
+}
+
+/// The JSON an agent was asked to end its answer with: the last ```json
+/// block, or failing that the outermost braces.
+pub fn json_block(text: &str) -> Option<&str> {
+    let fenced = text.rfind("```json").and_then(|start| {
+        let body = &text[start + "```json".len()..];
+        body.find("```").map(|end| &body[..end])
+    });
+    match fenced {
+        Some(json) => Some(json.trim()),
+        None => match (text.find('{'), text.rfind('}')) {
+            (Some(start), Some(end)) if start < end => Some(&text[start..=end]),
+            _ => None,
+        },
+    }
+}
+
+/// Pull the findings out of the reviewer's answer.
+pub fn parse_findings(text: &str) -> Result<ReviewFindings, String> {
+    let json = json_block(text).ok_or("no findings JSON in the reviewer's answer")?;
+    serde_json::from_str(json).map_err(|e| format!("invalid findings JSON: {e}"))
+}
+
+/// Cut a diff down to at most `max_bytes` (on a character boundary) and say
+/// it was cut, so the reviewer knows to read the files.
+pub fn truncate_diff(diff: String, max_bytes: usize) -> String {
+    if diff.len() <= max_bytes {
+        return diff;
+    }
+    let mut end = max_bytes;
+    while !diff.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!(
+        "{}...\n\n[Diff truncated at {}k chars. Read individual files for full context.]",
+        &diff[..end],
+        max_bytes / 1000
+    )
+}
+
+/// Lines of the new side of each file that GitHub accepts inline comments
+/// on: added and context lines inside the diff's hunks.
+pub fn commentable_lines(diff: &str) -> BTreeMap<String, BTreeSet<u32>> {
+    let mut lines: BTreeMap<String, BTreeSet<u32>> = BTreeMap::new();
+    let mut path: Option<String> = None;
+    let mut new_line = 0u32;
+
+    for line in diff.lines() {
+        if let Some(p) = line.strip_prefix("+++ ") {
+            path = p.strip_prefix("b/").map(|p| p.to_string());
+            continue;
+        }
+        if line.starts_with("--- ") || line.starts_with("diff --git") {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix("@@ ") {
+            // "@@ -a,b +c,d @@": new side starts at c
+            new_line = header
+                .split_whitespace()
+                .find_map(|part| part.strip_prefix('+'))
+                .and_then(|range| range.split(',').next())
+                .and_then(|start| start.parse().ok())
+                .unwrap_or(0);
+            continue;
+        }
+        let Some(path) = &path else {
+            continue;
+        };
+        match line.chars().next() {
+            Some('+') | Some(' ') => {
+                lines.entry(path.clone()).or_default().insert(new_line);
+                new_line += 1;
+            }
+            _ => {}
+        }
+    }
+    lines
+}
+
+/// An open PR as listed by `gh pr list`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenPr {
+    pub number: u64,
+    #[serde(default)]
+    pub title: String,
+    pub head_ref_oid: String,
+    #[serde(default)]
+    pub author: Option<Author>,
+}
+
+impl OpenPr {
+    pub fn author(&self) -> &str {
+        pr::login(&self.author)
+    }
+
+    pub fn short_sha(&self) -> &str {
+        &self.head_ref_oid[..self.head_ref_oid.len().min(7)]
+    }
+}
+
+/// Which PRs `vibe review watch` picks up.
+#[derive(Debug, Clone, Default)]
+pub struct WatchFilter {
+    pub label: Option<String>,
+    pub author: Option<String>,
+}
+
+impl WatchFilter {
+    /// Arguments for `gh pr list`.
+    pub fn gh_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = ["pr", "list", "--state", "open", "--json", "number,title,headRefOid,author"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        if let Some(label) = &self.label {
+            args.extend(["--label".into(), label.clone()]);
+        }
+        if let Some(author) = &self.author {
+            args.extend(["--author".into(), author.clone()]);
+        }
+        args
+    }
+}
+
+/// Outcome of one watched review.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct WatchedReview {
+    /// Head commit the review was run against.
+    pub head_sha: String,
+    #[serde(default)]
+    pub title: String,
+    pub reviewed_at: DateTime<Utc>,
+    /// Number of findings, or `None` if the review failed.
+    #[serde(default)]
+    pub findings: Option<usize>,
+    /// Reviewer's answer, or the error if it failed.
+    #[serde(default)]
+    pub output: Option<PathBuf>,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Reviews run against this head so far, counting failed ones.
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+impl WatchedReview {
+    /// One line for notifications and logs.
+    pub fn summary(&self, pr_number: u64) -> String {
+        let sha = &self.head_sha[..self.head_sha.len().min(7)];
+        match (&self.error, self.findings) {
+            (Some(e), _) => format!("Review of PR #{pr_number} ({sha}) failed: {e}"),
+            (None, Some(0)) => format!("PR #{pr_number} ({sha}) reviewed: no findings"),
+            (None, Some(n)) => format!("PR #{pr_number} ({sha}) reviewed: {n} finding(s)"),
+            (None, None) => format!("PR #{pr_number} ({sha}) reviewed"),
+        }
+    }
+}
+
+/// Last reviewed head per PR, persisted in `.vibe/review-watch.json` so a
+/// restarted watcher doesn't review the same push twice.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct WatchLedger {
+    #[serde(default)]
+    pub prs: BTreeMap<u64, WatchedReview>,
+}
+
+/// Reviews tried against one head before a failing PR is left alone until
+/// its next push.
+pub const MAX_REVIEW_ATTEMPTS: u32 = 3;
+
+impl WatchLedger {
+    /// PRs that are new, have been pushed to since their last review, or
+    /// whose last review failed and has attempts left.
+    pub fn due<'a>(&self, open: &'a [OpenPr]) -> Vec<&'a OpenPr> {
+        open.iter()
+            .filter(|pr| {
+                self.prs.get(&pr.number).is_none_or(|review| {
+                    review.head_sha != pr.head_ref_oid
+                        || (review.error.is_some() && review.attempts < MAX_REVIEW_ATTEMPTS)
+                })
+            })
+            .collect()
+    }
+
+    /// Record a review, counting it as another attempt if the head is the
+    /// same as last time.
+    pub fn record(&mut self, pr_number: u64, mut review: WatchedReview) {
+        review.attempts = match self.prs.get(&pr_number) {
+            Some(last) if last.head_sha == review.head_sha => last.attempts + 1,
+            _ => 1,
+        };
+        self.prs.insert(pr_number, review);
+    }
+
+    /// Reviews finished after `since`, oldest first.
+    pub fn reviewed_since(&self, since: DateTime<Utc>) -> Vec<(u64, &WatchedReview)> {
+        let mut reviews: Vec<_> = self
+            .prs
+            .iter()
+            .filter(|(_, review)| review.reviewed_at > since)
+            .map(|(number, review)| (*number, review))
+            .collect();
+        reviews.sort_by_key(|(_, review)| review.reviewed_at);
+        reviews
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_findings_fenced() {
+        let text = "Looks mostly fine.\n\n```json\n{\"summary\": \"ok\", \"findings\": [\
+            {\"path\": \"src/a.rs\", \"line\": 3, \"severity\": \"nit\", \"comment\": \"rename\"}]}\n```\n";
+        let findings = parse_findings(text).unwrap();
+        assert_eq!(findings.summary, "ok");
+        assert_eq!(findings.findings.len(), 1);
+        assert_eq!(findings.findings[0].body(), "**nit**: rename");
+        assert!(parse_findings("no json here").is_err());
+    }
+
+    #[test]
+    fn test_commentable_lines() {
+        let diff = "diff --git a/src/a.rs b/src/a.rs\n\
+                    --- a/src/a.rs\n\
+                    +++ b/src/a.rs\n\
+                    @@ -1,3 +1,4 @@\n\
+                    \x20fn a() {}\n\
+                    -fn b() {}\n\
+                    +fn b2() {}\n\
+                    +fn c() {}\n\
+                    \x20fn d() {}\n\
+                    diff --git a/gone.rs b/gone.rs\n\
+                    --- a/gone.rs\n\
+                    +++ /dev/null\n\
+                    @@ -1 +0,0 @@\n\
+                    -x\n";
+        let lines = commentable_lines(diff);
+        assert_eq!(
+            lines["src/a.rs"].iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+        assert!(!lines.contains_key("gone.rs"));
+    }
+
+    #[test]
+    fn test_truncate_diff_on_char_boundary() {
+        assert_eq!(truncate_diff("+short".into(), 10), "+short");
+        // "é" is two bytes; byte 5 falls inside the second one
+        let diff = "+abéé".to_string();
+        let cut = truncate_diff(diff, 5);
+        assert!(cut.starts_with("+abé..."), "{cut}");
+    }
+
+    fn review_of(pr: &OpenPr) -> WatchedReview {
+        WatchedReview {
+            head_sha: pr.head_ref_oid.clone(),
+            title: pr.title.clone(),
+            reviewed_at: Utc::now(),
+            findings: Some(0),
+            output: None,
+            error: None,
+            attempts: 0,
+        }
+    }
+
+    #[test]
+    fn test_watch_ledger_due() {
+        // Successive `gh pr list` outputs as a scripted gh would print them
+        let polls = [
+            r#"[{"number":1,"title":"a","headRefOid":"aaa111","author":{"login":"x"}},
+                {"number":2,"title":"b","headRefOid":"bbb111","author":null}]"#,
+            r#"[{"number":1,"title":"a","headRefOid":"aaa111"},
+                {"number":2,"title":"b","headRefOid":"bbb222"}]"#,
+            r#"[{"number":2,"title":"b","headRefOid":"bbb222"},
+                {"number":3,"title":"c","headRefOid":"ccc111"}]"#,
+        ];
+        let mut ledger = WatchLedger::default();
+        let mut reviewed = vec![];
+        for poll in polls {
+            let open: Vec<OpenPr> = serde_json::from_str(poll).unwrap();
+            let due: Vec<u64> = ledger.due(&open).iter().map(|pr| pr.number).collect();
+            for pr in ledger.due(&open) {
+                ledger.record(pr.number, review_of(pr));
+            }
+            reviewed.push(due);
+        }
+        assert_eq!(reviewed, vec![vec![1, 2], vec![2], vec![3]]);
+        assert_eq!(ledger.prs[&2].head_sha, "bbb222");
+
+        let open: Vec<OpenPr> = serde_json::from_str(polls[0]).unwrap();
+        assert_eq!(open[0].author(), "x");
+        assert_eq!(open[1].author(), "ghost");
+    }
+
+    #[test]
+    fn test_watch_ledger_retries_failed() {
+        let open: Vec<OpenPr> =
+            serde_json::from_str(r#"[{"number":1,"title":"a","headRefOid":"aaa111"}]"#).unwrap();
+        let mut ledger = WatchLedger::default();
+        for attempt in 1..=MAX_REVIEW_ATTEMPTS {
+            assert_eq!(ledger.due(&open).len(), 1, "attempt {attempt}");
+            let mut review = review_of(&open[0]);
+            review.error = Some("HTTP 502".into());
+            ledger.record(1, review);
+            assert_eq!(ledger.prs[&1].attempts, attempt);
+        }
+        assert!(ledger.due(&open).is_empty());
+
+        // A push starts over
+        let pushed: Vec<OpenPr> =
+            serde_json::from_str(r#"[{"number":1,"title":"a","headRefOid":"aaa222"}]"#).unwrap();
+        assert_eq!(ledger.due(&pushed).len(), 1);
+        ledger.record(1, review_of(&pushed[0]));
+        assert_eq!(ledger.prs[&1].attempts, 1);
+        assert!(ledger.due(&pushed).is_empty());
+    }
+
+    #[test]
+    fn test_watch_filter_gh_args() {
+        let filter = WatchFilter {
+            label: Some("needs-review".into()),
+            author: None,
+        };
+        let args = filter.gh_args();
+        assert_eq!(&args[..2], ["pr", "list"]);
+        assert!(args.ends_with(&["--label".to_string(), "needs-review".to_string()]));
+        assert!(!args.contains(&"--author".to_string()));
+    }
+}
@@ -1,7 +1,8 @@
+use crate::domain::sync::SyncConflict;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 /// A Session represents a single Claude Code working context:
@@ -27,6 +28,88 @@ pub struct Session {
     /// Multi-repo: worktree path per repo name. Empty for single-repo sessions.
     #[serde(default)]
     pub repo_worktrees: BTreeMap<String, PathBuf>,
+    /// Worktrees left mid-rebase/merge by `vibe sync`, cleared once resolved.
+    #[serde(default)]
+    pub sync_conflicts: Vec<SyncConflict>,
+    /// Multi-repo: branch and base per repo name. Repos missing here use the
+    /// session branch and the repo's default branch.
+    #[serde(default)]
+    pub repo_refs: BTreeMap<String, RepoRefs>,
+    /// Multi-repo: repos whose worktree could not be created, with the error.
+    /// Cleared by `vibe session repair` once the worktree exists.
+    #[serde(default)]
+    pub failed_repos: BTreeMap<String, String>,
+    /// Multi-repo: PR number per repo opened by `vibe pr open`. Single-repo
+    /// sessions use `metadata.pr_number`.
+    #[serde(default)]
+    pub repo_prs: BTreeMap<String, u64>,
+    /// Single-repo: ref the branch was created from, if `--base` was given.
+    /// `None` means the workspace's default branch.
+    #[serde(default)]
+    pub base: Option<String>,
+}
+
+/// What is kept of a killed session so `vibe log` and search can still find
+/// its transcripts by name. Saved next to the session's transcript.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedSession {
+    pub id: Uuid,
+    pub name: String,
+    pub branch: String,
+    pub killed_at: DateTime<Utc>,
+    /// The session's agents, whose transcripts are kept too.
+    #[serde(default)]
+    pub agents: Vec<ArchivedAgent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedAgent {
+    pub id: Uuid,
+    pub name: String,
+}
+
+/// Branch and base ref of one repo in a multi-repo session.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RepoRefs {
+    pub branch: String,
+    /// Ref the branch was created from and is synced onto. `None` means the
+    /// repo's default branch.
+    pub base: Option<String>,
+}
+
+/// `--base` / `--branch` values for a new session: a plain value applies to
+/// every repo, `repo=value` to a single repo of a multi-repo workspace.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RefOverrides {
+    pub all: Option<String>,
+    pub per_repo: BTreeMap<String, String>,
+}
+
+impl RefOverrides {
+    pub fn parse(values: &[String]) -> Result<Self, String> {
+        let mut overrides = Self::default();
+        for value in values {
+            match value.split_once('=') {
+                Some((repo, r)) if !repo.is_empty() && !r.is_empty() => {
+                    overrides.per_repo.insert(repo.to_string(), r.to_string());
+                }
+                Some(_) => return Err(format!("Invalid override '{value}', expected repo=ref")),
+                None if overrides.all.is_some() => {
+                    return Err(format!("Only one value without repo= is allowed, got '{value}'"));
+                }
+                None => overrides.all = Some(value.clone()),
+            }
+        }
+        Ok(overrides)
+    }
+
+    /// Value for a repo: its own override, else the value for all repos.
+    pub fn for_repo(&self, repo: &str) -> Option<&str> {
+        self.per_repo
+            .get(repo)
+            .or(self.all.as_ref())
+            .map(|s| s.as_str())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -79,10 +162,91 @@ impl Session {
             metadata: SessionMetadata::default(),
             is_main: false,
             repo_worktrees: BTreeMap::new(),
+            sync_conflicts: vec![],
+            repo_refs: BTreeMap::new(),
+            failed_repos: BTreeMap::new(),
+            repo_prs: BTreeMap::new(),
+            base: None,
         }
     }
 
     pub fn is_active(&self) -> bool {
         matches!(self.status, SessionStatus::Active | SessionStatus::Creating)
     }
+
+    /// Worktree path for a repo of a multi-repo session, or the session
+    /// worktree itself when `repo` is `None`.
+    pub fn repo_worktree(&self, repo: Option<&str>) -> Option<&Path> {
+        match repo {
+            Some(name) => self.repo_worktrees.get(name).map(|p| p.as_path()),
+            None => Some(self.worktree_path.as_path()),
+        }
+    }
+
+    /// Branch checked out in a repo's worktree (the session branch for
+    /// single-repo sessions or repos without an override).
+    pub fn branch_for(&self, repo: Option<&str>) -> &str {
+        repo.and_then(|r| self.repo_refs.get(r))
+            .map(|refs| refs.branch.as_str())
+            .unwrap_or(&self.branch)
+    }
+
+    /// PR opened for a repo's branch (the session's own PR when `repo` is `None`).
+    pub fn pr_for(&self, repo: Option<&str>) -> Option<u64> {
+        match repo {
+            Some(name) => self.repo_prs.get(name).copied(),
+            None => self.metadata.pr_number,
+        }
+    }
+
+    /// Base ref recorded for a repo (the session's own base when `repo` is
+    /// `None`), if it was overridden at creation.
+    pub fn base_for(&self, repo: Option<&str>) -> Option<&str> {
+        match repo {
+            Some(name) => self.repo_refs.get(name).and_then(|refs| refs.base.as_deref()),
+            None => self.base.as_deref(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ref_overrides_parse() {
+        let overrides = RefOverrides::parse(&[
+            "main".to_string(),
+            "api=release/2.1".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(overrides.for_repo("api"), Some("release/2.1"));
+        assert_eq!(overrides.for_repo("web"), Some("main"));
+
+        assert!(RefOverrides::parse(&["a".into(), "b".into()]).is_err());
+        assert!(RefOverrides::parse(&["api=".into()]).is_err());
+        assert_eq!(RefOverrides::parse(&[]).unwrap().for_repo("api"), None);
+    }
+
+    #[test]
+    fn test_branch_and_base_for_repo() {
+        let mut session = Session::new(
+            "auth".into(),
+            "feat/auth".into(),
+            PathBuf::from("/tmp/auth"),
+            "@1".into(),
+        );
+        session.repo_refs.insert(
+            "api".into(),
+            RepoRefs {
+                branch: "feature/auth-api".into(),
+                base: Some("release/2.1".into()),
+            },
+        );
+        assert_eq!(session.branch_for(Some("api")), "feature/auth-api");
+        assert_eq!(session.base_for(Some("api")), Some("release/2.1"));
+        assert_eq!(session.branch_for(Some("web")), "feat/auth");
+        assert_eq!(session.base_for(Some("web")), None);
+        assert_eq!(session.branch_for(None), "feat/auth");
+    }
 }
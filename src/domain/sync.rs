@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+/// How `vibe sync` brings a session branch up to date with its base.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncStrategy {
+    #[default]
+    Rebase,
+    Merge,
+}
+
+impl std::fmt::Display for SyncStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rebase => write!(f, "rebase"),
+            Self::Merge => write!(f, "merge"),
+        }
+    }
+}
+
+/// A worktree left mid-rebase/merge by `vibe sync`. Persisted on the session
+/// so the dashboard can flag it until the conflict is resolved or aborted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SyncConflict {
+    /// Repo name for multi-repo sessions, `None` for single-repo.
+    pub repo: Option<String>,
+    pub strategy: SyncStrategy,
+    /// Ref the branch was being synced onto (e.g. "origin/main").
+    pub onto: String,
+    pub files: Vec<String>,
+}
+
+/// Result of syncing one worktree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncOutcome {
+    /// Base was already contained in the branch.
+    UpToDate,
+    /// Branch was rebased/merged cleanly.
+    Synced,
+    /// Conflicts were hit. If `aborted`, the worktree was restored to its
+    /// pre-sync state; otherwise the rebase/merge is left in progress.
+    Conflicted { files: Vec<String>, aborted: bool },
+    /// A rebase or merge was already in progress before this sync.
+    InProgress,
+    /// Not attempted (e.g. uncommitted changes).
+    Skipped(String),
+    Failed(String),
+}
+
+impl SyncOutcome {
+    /// Outcomes that leave the worktree needing human attention.
+    pub fn needs_attention(&self) -> bool {
+        matches!(
+            self,
+            Self::Conflicted { aborted: false, .. } | Self::InProgress
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strategy_serde_lowercase() {
+        #[derive(Deserialize)]
+        struct Cfg {
+            sync_strategy: SyncStrategy,
+        }
+        let cfg: Cfg = toml::from_str(r#"sync_strategy = "merge""#).unwrap();
+        assert_eq!(cfg.sync_strategy, SyncStrategy::Merge);
+        assert_eq!(SyncStrategy::default(), SyncStrategy::Rebase);
+    }
+
+    #[test]
+    fn test_needs_attention() {
+        assert!(SyncOutcome::InProgress.needs_attention());
+        assert!(SyncOutcome::Conflicted {
+            files: vec!["a.rs".into()],
+            aborted: false,
+        }
+        .needs_attention());
+        assert!(!SyncOutcome::Conflicted {
+            files: vec!["a.rs".into()],
+            aborted: true,
+        }
+        .needs_attention());
+        assert!(!SyncOutcome::Synced.needs_attention());
+        assert!(!SyncOutcome::Skipped("dirty".into()).needs_attention());
+    }
+}
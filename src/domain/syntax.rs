@@ -0,0 +1,220 @@
+/// What a piece of a source line is, for colouring diffs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Plain,
+    Keyword,
+    String,
+    Comment,
+    Number,
+}
+
+/// Just enough of a language to colour it a line at a time: keywords,
+/// strings, line comments and numbers. Block comments and strings that
+/// span lines are not tracked.
+#[derive(Debug)]
+pub struct Syntax {
+    line_comment: &'static [&'static str],
+    quotes: &'static [char],
+    keywords: &'static [&'static str],
+}
+
+const RUST: Syntax = Syntax {
+    line_comment: &["//"],
+    quotes: &['"'],
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+        "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true",
+        "type", "unsafe", "use", "where", "while",
+    ],
+};
+
+const PYTHON: Syntax = Syntax {
+    line_comment: &["#"],
+    quotes: &['"', '\''],
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+        "elif", "else", "except", "False", "finally", "for", "from", "global", "if", "import",
+        "in", "is", "lambda", "None", "nonlocal", "not", "or", "pass", "raise", "return", "True",
+        "try", "while", "with", "yield",
+    ],
+};
+
+const JAVASCRIPT: Syntax = Syntax {
+    line_comment: &["//"],
+    quotes: &['"', '\'', '`'],
+    keywords: &[
+        "async", "await", "break", "case", "catch", "class", "const", "continue", "default",
+        "delete", "do", "else", "export", "extends", "false", "finally", "for", "from",
+        "function", "if", "import", "in", "instanceof", "interface", "let", "new", "null",
+        "return", "switch", "this", "throw", "true", "try", "type", "typeof", "undefined", "var",
+        "void", "while", "yield",
+    ],
+};
+
+const GO: Syntax = Syntax {
+    line_comment: &["//"],
+    quotes: &['"', '`'],
+    keywords: &[
+        "break", "case", "chan", "const", "continue", "default", "defer", "else", "false",
+        "for", "func", "go", "if", "import", "interface", "map", "nil", "package", "range",
+        "return", "select", "struct", "switch", "true", "type", "var",
+    ],
+};
+
+const C_LIKE: Syntax = Syntax {
+    line_comment: &["//"],
+    quotes: &['"'],
+    keywords: &[
+        "break", "case", "class", "const", "continue", "default", "do", "else", "enum",
+        "extern", "false", "final", "for", "if", "import", "new", "null", "nullptr", "package",
+        "private", "protected", "public", "return", "static", "struct", "switch", "this",
+        "throw", "true", "try", "typedef", "void", "while",
+    ],
+};
+
+const SHELL: Syntax = Syntax {
+    line_comment: &["#"],
+    quotes: &['"', '\''],
+    keywords: &[
+        "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if",
+        "in", "local", "return", "then", "until", "while",
+    ],
+};
+
+const CONFIG: Syntax = Syntax {
+    line_comment: &["#"],
+    quotes: &['"', '\''],
+    keywords: &["false", "true", "null"],
+};
+
+impl Syntax {
+    /// The syntax for a file, by extension. `None` for files it doesn't know.
+    pub fn for_path(path: &str) -> Option<&'static Syntax> {
+        let ext = path.rsplit_once('.').map(|(_, ext)| ext)?;
+        match ext {
+            "rs" => Some(&RUST),
+            "py" => Some(&PYTHON),
+            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" => Some(&JAVASCRIPT),
+            "go" => Some(&GO),
+            "c" | "h" | "cc" | "cpp" | "hpp" | "java" | "kt" | "cs" | "swift" => Some(&C_LIKE),
+            "sh" | "bash" | "zsh" => Some(&SHELL),
+            "toml" | "yaml" | "yml" => Some(&CONFIG),
+            _ => None,
+        }
+    }
+
+    /// Split a line into coloured pieces that concatenate back to it.
+    pub fn tokens<'a>(&self, line: &'a str) -> Vec<(TokenKind, &'a str)> {
+        let mut tokens = vec![];
+        // Start of the plain text not yet pushed, so a run of it is one piece
+        let mut plain_from = 0;
+        let mut i = 0;
+        while i < line.len() {
+            let rest = &line[i..];
+            let c = rest.chars().next().unwrap_or_default();
+            let (kind, len) = if self.line_comment.iter().any(|p| rest.starts_with(p)) {
+                (TokenKind::Comment, rest.len())
+            } else if self.quotes.contains(&c) {
+                (TokenKind::String, string_len(rest, c))
+            } else if c.is_ascii_digit() {
+                let len = rest
+                    .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '.'))
+                    .unwrap_or(rest.len());
+                (TokenKind::Number, len)
+            } else if c.is_alphanumeric() || c == '_' {
+                let len = rest
+                    .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+                    .unwrap_or(rest.len());
+                let kind = if self.keywords.contains(&&rest[..len]) {
+                    TokenKind::Keyword
+                } else {
+                    TokenKind::Plain
+                };
+                (kind, len)
+            } else {
+                (TokenKind::Plain, c.len_utf8())
+            };
+
+            if kind != TokenKind::Plain {
+                if plain_from < i {
+                    tokens.push((TokenKind::Plain, &line[plain_from..i]));
+                }
+                tokens.push((kind, &rest[..len]));
+                plain_from = i + len;
+            }
+            i += len;
+        }
+        if plain_from < line.len() {
+            tokens.push((TokenKind::Plain, &line[plain_from..]));
+        }
+        tokens
+    }
+}
+
+/// Length of a string literal at the start of `s`, through its closing quote
+/// or to the end of the line if it isn't closed.
+fn string_len(s: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (i, ch) in s.char_indices().skip(1) {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            _ if ch == quote => return i + ch.len_utf8(),
+            _ => {}
+        }
+    }
+    s.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(path: &str, line: &str) -> Vec<(TokenKind, String)> {
+        let tokens = Syntax::for_path(path).unwrap().tokens(line);
+        assert_eq!(tokens.iter().map(|(_, t)| *t).collect::<String>(), line);
+        tokens.into_iter().map(|(k, t)| (k, t.to_string())).collect()
+    }
+
+    #[test]
+    fn test_rust_line() {
+        use TokenKind::*;
+        assert_eq!(
+            kinds("src/lib.rs", r#"let x = "a \"b\""; // 42 fn"#),
+            [
+                (Keyword, "let".into()),
+                (Plain, " x = ".into()),
+                (String, r#""a \"b\"""#.into()),
+                (Plain, "; ".into()),
+                (Comment, "// 42 fn".into()),
+            ]
+        );
+        assert_eq!(
+            kinds("a.rs", "for_each(1.5, \"é\")"),
+            [
+                (Plain, "for_each(".into()),
+                (Number, "1.5".into()),
+                (Plain, ", ".into()),
+                (String, "\"é\"".into()),
+                (Plain, ")".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unclosed_string_and_hash_comments() {
+        use TokenKind::*;
+        assert_eq!(kinds("x.py", "s = 'open"), [(Plain, "s = ".into()), (String, "'open".into())]);
+        assert_eq!(
+            kinds("Cargo.toml", "on = true # yes"),
+            [(Plain, "on = ".into()), (Keyword, "true".into()), (Plain, " ".into()), (Comment, "# yes".into())]
+        );
+    }
+
+    #[test]
+    fn test_unknown_extension() {
+        assert!(Syntax::for_path("README").is_none());
+        assert!(Syntax::for_path("notes.txt").is_none());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::domain::agent::AgentMode;
+use crate::domain::layout::PaneLayout;
 use crate::error::VibeError;
 use serde::Deserialize;
 use std::collections::HashSet;
@@ -14,6 +15,9 @@ pub struct AgentTemplate {
     pub allowed_tools: Vec<String>,
     pub disallowed_tools: Vec<String>,
     pub permission_mode: Option<String>,
+    /// Where interactive agents from this template go, overriding the
+    /// workspace `layout`.
+    pub layout: Option<PaneLayout>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -27,6 +31,8 @@ struct TemplateFrontmatter {
     allowed_tools: Option<Vec<String>>,
     #[serde(default)]
     disallowed_tools: Option<Vec<String>>,
+    #[serde(default)]
+    layout: Option<PaneLayout>,
 }
 
 impl AgentTemplate {
@@ -55,6 +61,7 @@ impl AgentTemplate {
             allowed_tools: frontmatter.allowed_tools.unwrap_or_default(),
             disallowed_tools: frontmatter.disallowed_tools.unwrap_or_default(),
             permission_mode: frontmatter.permission_mode,
+            layout: frontmatter.layout,
         })
     }
 
@@ -166,12 +173,14 @@ You are a test agent. Do test things."#;
         let content = r#"+++
 description = "Interactive agent"
 mode = "interactive"
+layout = "tiled"
 +++
 
 Do things interactively."#;
 
         let t = AgentTemplate::parse("interactive", content).unwrap();
         assert_eq!(t.mode, AgentMode::Interactive);
+        assert_eq!(t.layout, Some(crate::domain::layout::PaneLayout::Tiled));
         assert!(t.allowed_tools.is_empty());
         assert!(t.disallowed_tools.is_empty());
         assert!(t.permission_mode.is_none());
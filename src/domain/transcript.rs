@@ -0,0 +1,121 @@
+/// Plain text of one line of raw terminal output: escape sequences and
+/// control characters removed, and only the last of any `\r`-overwritten
+/// segments kept.
+pub fn clean_line(raw: &str) -> String {
+    let mut text = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI: parameters up to a final byte in @..~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC (titles, hyperlinks): up to BEL or ESC \
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' {
+                            chars.next_if_eq(&'\\');
+                            break;
+                        }
+                    }
+                }
+                // Charset designation carries one more byte
+                Some('(' | ')') => {
+                    chars.next();
+                }
+                _ => {}
+            },
+            '\r' => {
+                // Text after a carriage return overwrites the line
+                if chars.peek().is_some_and(|&c| c != '\n') {
+                    text.clear();
+                }
+            }
+            '\t' => text.push(c),
+            c if c.is_control() => {}
+            c => text.push(c),
+        }
+    }
+    text.trim_end().to_string()
+}
+
+/// Drops the noise of full-screen redraws from a cleaned line stream by
+/// skipping repeats of the previous line (which also collapses blank runs).
+#[derive(Debug, Default)]
+pub struct LineFilter {
+    last: Option<String>,
+}
+
+impl LineFilter {
+    /// Whether `line` should be written.
+    pub fn accept(&mut self, line: &str) -> bool {
+        if self.last.as_deref() == Some(line) {
+            return false;
+        }
+        self.last = Some(line.to_string());
+        true
+    }
+}
+
+/// A transcript line matching a search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptMatch {
+    /// 1-based line number in the transcript.
+    pub line_number: usize,
+    pub line: String,
+}
+
+/// Lines of `text` containing `query`, ignoring case.
+pub fn search(text: &str, query: &str) -> Vec<TranscriptMatch> {
+    let query = query.to_lowercase();
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| line.to_lowercase().contains(&query))
+        .map(|(i, line)| TranscriptMatch {
+            line_number: i + 1,
+            line: line.trim().to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_line() {
+        assert_eq!(clean_line("\x1b[1;32mok\x1b[0m done\r\n"), "ok done");
+        assert_eq!(clean_line("\x1b]0;claude\x07⏺ Reading file"), "⏺ Reading file");
+        assert_eq!(clean_line("\x1b]8;;https://x\x1b\\link\x1b]8;;\x1b\\"), "link");
+        assert_eq!(clean_line("\x1b(Bplain\x1b[2K"), "plain");
+        assert_eq!(clean_line("50%\r100%   \n"), "100%");
+        assert_eq!(clean_line("a\x08b\x07c"), "abc");
+    }
+
+    #[test]
+    fn test_line_filter_and_search() {
+        let mut filter = LineFilter::default();
+        let kept: Vec<&str> = ["> prompt", "> prompt", "", "", "Error: boom", "> prompt"]
+            .into_iter()
+            .filter(|line| filter.accept(line))
+            .collect();
+        assert_eq!(kept, ["> prompt", "", "Error: boom", "> prompt"]);
+
+        let matches = search(&kept.join("\n"), "error");
+        assert_eq!(
+            matches,
+            [TranscriptMatch {
+                line_number: 3,
+                line: "Error: boom".into()
+            }]
+        );
+    }
+}
@@ -275,6 +275,7 @@ mod tests {
         assert!(state.workspace.repos.is_empty());
         assert!(!state.workspace.is_multi_repo());
         assert!(state.sessions[0].repo_worktrees.is_empty());
+        assert!(state.sessions[0].sync_conflicts.is_empty());
         assert!(!state.sessions[0].is_main); // default false
     }
 
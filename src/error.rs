@@ -11,12 +11,18 @@ pub enum VibeError {
     #[error("tmux error: {0}")]
     Tmux(String),
 
+    #[error("Terminal error: {0}")]
+    Pty(String),
+
     #[error("Claude CLI error: {0}")]
     Claude(String),
 
     #[error("Template error: {0}")]
     Template(String),
 
+    #[error("Hook failed: {0}")]
+    Hook(String),
+
     #[error("Config error: {0}")]
     Config(String),
 
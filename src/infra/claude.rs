@@ -1,3 +1,4 @@
+use crate::domain::activity::HOOK_EVENTS;
 use crate::domain::agent::AgentResult;
 use crate::error::VibeError;
 use serde::{Deserialize, Serialize};
@@ -69,7 +70,74 @@ pub fn interactive_command(
     parts.join(" ")
 }
 
+/// Point Claude Code's activity hooks in `dir` at `events_file`, so the
+/// dashboard learns what Claude is doing without scraping its pane.
+///
+/// Merges into `.claude/settings.local.json`, replacing hooks from an earlier
+/// install and keeping everything else.
+pub fn install_activity_hooks(dir: &Path, events_file: &Path) -> Result<(), VibeError> {
+    let exe = std::env::current_exe()?;
+    let quote = |p: &Path| format!("'{}'", p.display().to_string().replace('\'', "'\\''"));
+    let command = format!("{} hook-event {}", quote(&exe), quote(events_file));
+
+    let settings_file = dir.join(".claude").join("settings.local.json");
+    let mut settings: serde_json::Value = match std::fs::read_to_string(&settings_file) {
+        Ok(content) => serde_json::from_str(&content)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => serde_json::json!({}),
+        Err(e) => return Err(e.into()),
+    };
+    let Some(settings_obj) = settings.as_object_mut() else {
+        return Err(VibeError::Claude(format!(
+            "{} is not a JSON object",
+            settings_file.display()
+        )));
+    };
+    let hooks = settings_obj
+        .entry("hooks")
+        .or_insert_with(|| serde_json::json!({}));
+    let Some(hooks) = hooks.as_object_mut() else {
+        return Err(VibeError::Claude(format!(
+            "\"hooks\" in {} is not a JSON object",
+            settings_file.display()
+        )));
+    };
+
+    for event in HOOK_EVENTS {
+        let entries = hooks
+            .entry(event.to_string())
+            .or_insert_with(|| serde_json::json!([]));
+        let Some(entries) = entries.as_array_mut() else {
+            continue;
+        };
+        entries.retain(|entry| !is_vibe_hook(entry));
+        let mut entry = serde_json::json!({
+            "hooks": [{ "type": "command", "command": command }],
+        });
+        if event.ends_with("ToolUse") {
+            entry["matcher"] = "*".into();
+        }
+        entries.push(entry);
+    }
+
+    std::fs::create_dir_all(dir.join(".claude"))?;
+    std::fs::write(&settings_file, serde_json::to_string_pretty(&settings)?)?;
+    debug!(file = %settings_file.display(), "installed activity hooks");
+    Ok(())
+}
+
+/// A hook entry installed by [`install_activity_hooks`].
+fn is_vibe_hook(entry: &serde_json::Value) -> bool {
+    entry["hooks"].as_array().is_some_and(|hooks| {
+        hooks.iter().any(|hook| {
+            hook["command"]
+                .as_str()
+                .is_some_and(|c| c.contains(" hook-event "))
+        })
+    })
+}
+
 /// Run a headless claude agent, capturing JSON output.
+#[allow(clippy::too_many_arguments)]
 pub async fn run_headless(
     claude_command: &str,
     prompt: &str,
@@ -80,11 +148,37 @@ pub async fn run_headless(
     permission_mode: Option<&str>,
     extra_args: &[String],
 ) -> Result<ClaudeJsonOutput, VibeError> {
+    let cmd = headless_command(
+        claude_command,
+        prompt,
+        working_dir,
+        system_prompt,
+        allowed_tools,
+        disallowed_tools,
+        permission_mode,
+        extra_args,
+    );
+    run_headless_command(cmd).await
+}
+
+/// The command [`run_headless`] runs, for callers that need to adjust it
+/// (e.g. `kill_on_drop`) before running it with [`run_headless_command`].
+#[allow(clippy::too_many_arguments)]
+pub fn headless_command(
+    claude_command: &str,
+    prompt: &str,
+    working_dir: &Path,
+    system_prompt: Option<&str>,
+    allowed_tools: &[String],
+    disallowed_tools: &[String],
+    permission_mode: Option<&str>,
+    extra_args: &[String],
+) -> Command {
     let is_simple = !claude_command.contains(' ');
 
     debug!(working_dir = %working_dir.display(), claude_command, "running headless claude agent");
 
-    let output = if is_simple {
+    if is_simple {
         let mut cmd = Command::new(claude_command);
         cmd.current_dir(working_dir);
         cmd.arg("-p");
@@ -106,7 +200,7 @@ pub async fn run_headless(
             cmd.arg(arg);
         }
         cmd.arg(prompt);
-        cmd.output().await?
+        cmd
     } else {
         // Compound command (env vars, wrapper script, etc.) — use sh -c
         let mut shell_parts = vec![claude_command.to_string()];
@@ -137,8 +231,13 @@ pub async fn run_headless(
         let mut cmd = Command::new("sh");
         cmd.current_dir(working_dir);
         cmd.arg("-c").arg(shell_parts.join(" "));
-        cmd.output().await?
-    };
+        cmd
+    }
+}
+
+/// Run a command from [`headless_command`] and parse its JSON output.
+pub async fn run_headless_command(mut cmd: Command) -> Result<ClaudeJsonOutput, VibeError> {
+    let output = cmd.output().await?;
 
     if !output.status.success() && output.stdout.is_empty() {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
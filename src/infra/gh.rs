@@ -1,6 +1,9 @@
+use crate::domain::pr::{PrComments, PrStatus, ReviewThread};
+use crate::domain::review::{OpenPr, WatchFilter};
 use crate::error::VibeError;
 use serde::Deserialize;
 use std::path::Path;
+use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 
 #[derive(Debug, Deserialize)]
@@ -31,9 +34,31 @@ pub struct PrFile {
     pub deletions: u64,
 }
 
+#[cfg(test)]
+thread_local! {
+    /// Binary [`command`] runs on this thread, set by [`stub_for_test`].
+    static TEST_GH: std::cell::Cell<Option<&'static Path>> = const { std::cell::Cell::new(None) };
+}
+
+/// A `gh` command. Tests swap the binary for a stub on their own thread.
+fn command() -> Command {
+    #[cfg(test)]
+    if let Some(gh) = TEST_GH.get() {
+        return Command::new(gh);
+    }
+    Command::new("gh")
+}
+
+/// Run `gh` as `stub` for the rest of this thread. `#[tokio::test]` runs the
+/// test and what it spawns on one thread, so tests don't see each other's stubs.
+#[cfg(test)]
+pub fn stub_for_test(stub: &'static Path) {
+    TEST_GH.set(Some(stub));
+}
+
 /// Fetch PR metadata via gh CLI
 pub async fn get_pr_info(pr_number: u64, repo_root: &Path) -> Result<PrInfo, VibeError> {
-    let output = Command::new("gh")
+    let output = command()
         .current_dir(repo_root)
         .args([
             "pr",
@@ -58,9 +83,28 @@ pub async fn get_pr_info(pr_number: u64, repo_root: &Path) -> Result<PrInfo, Vib
     Ok(info)
 }
 
+/// List open PRs matching a filter
+pub async fn list_open_prs(repo_root: &Path, filter: &WatchFilter) -> Result<Vec<OpenPr>, VibeError> {
+    let output = command()
+        .current_dir(repo_root)
+        .args(filter.gh_args())
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(VibeError::Git(format!(
+            "gh pr list failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| VibeError::Git(format!("Failed to parse PR list: {e}")))
+}
+
 /// Get the diff for a PR
 pub async fn get_pr_diff(pr_number: u64, repo_root: &Path) -> Result<String, VibeError> {
-    let output = Command::new("gh")
+    let output = command()
         .current_dir(repo_root)
         .args(["pr", "diff", &pr_number.to_string()])
         .output()
@@ -76,10 +120,9 @@ pub async fn get_pr_diff(pr_number: u64, repo_root: &Path) -> Result<String, Vib
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-/// Get comments on a PR
-pub async fn get_pr_comments(pr_number: u64, repo_root: &Path) -> Result<String, VibeError> {
-    // Get review comments (inline code comments)
-    let output = Command::new("gh")
+/// Get conversation comments and reviews on a PR
+pub async fn get_pr_comments(pr_number: u64, repo_root: &Path) -> Result<PrComments, VibeError> {
+    let output = command()
         .current_dir(repo_root)
         .args([
             "pr",
@@ -93,12 +136,247 @@ pub async fn get_pr_comments(pr_number: u64, repo_root: &Path) -> Result<String,
 
     if !output.status.success() {
         // Non-fatal — PR might have no comments
-        return Ok(String::new());
+        return Ok(PrComments::default());
+    }
+
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| VibeError::Git(format!("Failed to parse PR comments: {e}")))
+}
+
+const REVIEW_THREADS_QUERY: &str = "query($owner: String!, $repo: String!, $number: Int!) {
+  repository(owner: $owner, name: $repo) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100) {
+        nodes {
+          id isResolved isOutdated path line
+          comments(first: 50) { nodes { author { login } body } }
+        }
+      }
+    }
+  }
+}";
+
+const REPLY_MUTATION: &str = "mutation($thread: ID!, $body: String!) {
+  addPullRequestReviewThreadReply(input: {pullRequestReviewThreadId: $thread, body: $body}) {
+    comment { id }
+  }
+}";
+
+/// Get the inline review threads on a PR (GraphQL; `gh pr view` has no
+/// thread or resolution data)
+pub async fn get_review_threads(
+    pr_number: u64,
+    repo_root: &Path,
+) -> Result<Vec<ReviewThread>, VibeError> {
+    let output = command()
+        .current_dir(repo_root)
+        .args([
+            "api",
+            "graphql",
+            "-F",
+            "owner={owner}",
+            "-F",
+            "repo={repo}",
+            "-F",
+            &format!("number={pr_number}"),
+            "-f",
+            &format!("query={REVIEW_THREADS_QUERY}"),
+        ])
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(VibeError::Git(format!(
+            "gh api graphql (review threads) failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| VibeError::Git(format!("Failed to parse review threads: {e}")))?;
+    let nodes = response
+        .pointer("/data/repository/pullRequest/reviewThreads/nodes")
+        .cloned()
+        .unwrap_or_default();
+    serde_json::from_value(nodes)
+        .map_err(|e| VibeError::Git(format!("Failed to parse review threads: {e}")))
+}
+
+/// Logs of the failed steps of a workflow run
+pub async fn get_failed_run_log(run_id: u64, repo_root: &Path) -> Result<String, VibeError> {
+    let output = command()
+        .current_dir(repo_root)
+        .args(["run", "view", &run_id.to_string(), "--log-failed"])
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(VibeError::Git(format!(
+            "gh run view {run_id} --log-failed failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
     }
 
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
+/// Reply to an inline review thread
+pub async fn reply_to_thread(thread_id: &str, repo_root: &Path, body: &str) -> Result<(), VibeError> {
+    let output = command()
+        .current_dir(repo_root)
+        .args([
+            "api",
+            "graphql",
+            "-f",
+            &format!("thread={thread_id}"),
+            "-f",
+            &format!("body={body}"),
+            "-f",
+            &format!("query={REPLY_MUTATION}"),
+        ])
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(VibeError::Git(format!(
+            "gh api graphql (thread reply) failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Open a PR for `head` against `base`. Returns the PR URL.
+pub async fn create_pr(
+    worktree_path: &Path,
+    head: &str,
+    base: &str,
+    title: &str,
+    body: &str,
+    draft: bool,
+) -> Result<String, VibeError> {
+    let mut args = vec![
+        "pr", "create", "--head", head, "--base", base, "--title", title, "--body", body,
+    ];
+    if draft {
+        args.push("--draft");
+    }
+    let output = command()
+        .current_dir(worktree_path)
+        .args(&args)
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(VibeError::Git(format!(
+            "gh pr create failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    // gh prints the new PR's URL as the last line
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().last().unwrap_or_default().trim().to_string())
+}
+
+/// Replace a PR's description
+pub async fn edit_pr_body(pr_number: u64, repo_root: &Path, body: &str) -> Result<(), VibeError> {
+    let output = command()
+        .current_dir(repo_root)
+        .args(["pr", "edit", &pr_number.to_string(), "--body", body])
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(VibeError::Git(format!(
+            "gh pr edit failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Fetch review and check state for a PR
+pub async fn get_pr_status(pr_number: u64, repo_root: &Path) -> Result<PrStatus, VibeError> {
+    let output = command()
+        .current_dir(repo_root)
+        .args([
+            "pr",
+            "view",
+            &pr_number.to_string(),
+            "--json",
+            "number,url,state,isDraft,reviewDecision,statusCheckRollup",
+        ])
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(VibeError::Git(format!(
+            "gh pr view failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| VibeError::Git(format!("Failed to parse PR status: {e}")))
+}
+
+/// Submit a review with inline comments (`(path, line, body)` on the new side
+/// of the diff). `event` is None for a pending review. Returns the review URL.
+pub async fn submit_review(
+    pr_number: u64,
+    repo_root: &Path,
+    body: &str,
+    event: Option<&str>,
+    comments: &[(String, u32, String)],
+) -> Result<String, VibeError> {
+    let mut request = serde_json::json!({
+        "body": body,
+        "comments": comments
+            .iter()
+            .map(|(path, line, body)| serde_json::json!({
+                "path": path,
+                "line": line,
+                "side": "RIGHT",
+                "body": body,
+            }))
+            .collect::<Vec<_>>(),
+    });
+    if let Some(event) = event {
+        request["event"] = event.into();
+    }
+
+    let mut child = command()
+        .current_dir(repo_root)
+        .args([
+            "api",
+            "--method",
+            "POST",
+            &format!("repos/{{owner}}/{{repo}}/pulls/{pr_number}/reviews"),
+            "--input",
+            "-",
+        ])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(request.to_string().as_bytes()).await?;
+    }
+    let output = child.wait_with_output().await?;
+
+    if !output.status.success() {
+        return Err(VibeError::Git(format!(
+            "gh api (submit review) failed: {}{}",
+            String::from_utf8_lossy(&output.stderr).trim(),
+            String::from_utf8_lossy(&output.stdout).trim()
+        )));
+    }
+
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap_or_default();
+    Ok(response["html_url"].as_str().unwrap_or_default().to_string())
+}
+
 /// Parse a PR identifier — could be a number or a URL
 pub fn parse_pr_identifier(pr: &str) -> Result<u64, VibeError> {
     // Try direct number
@@ -120,5 +398,9 @@ pub fn parse_pr_identifier(pr: &str) -> Result<u64, VibeError> {
 
 /// Check if gh CLI is available
 pub fn is_available() -> bool {
+    #[cfg(test)]
+    if TEST_GH.get().is_some() {
+        return true;
+    }
     which::which("gh").is_ok()
 }
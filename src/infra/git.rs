@@ -1,6 +1,12 @@
+use crate::domain::diff::{
+    DiffHunk, DiffLine, DiffLineKind, DiffScope, FileChange, FileDiff, HunkApply,
+};
+use crate::domain::sync::{SyncOutcome, SyncStrategy};
 use crate::domain::workspace::RepoInfo;
 use crate::error::VibeError;
-use git2::Repository;
+use git2::{ApplyLocation, ApplyOptions, Delta, DiffOptions, Patch, Repository, RepositoryState};
+use std::cell::Cell;
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 use tokio::process::Command;
 use tracing;
@@ -9,6 +15,39 @@ use tracing;
 pub struct WorktreeInfo {
     pub path: PathBuf,
     pub branch: String,
+    /// True if the branch was created for this worktree rather than an
+    /// existing one checked out.
+    pub created_branch: bool,
+}
+
+/// Untracked files and directories brought over from the main checkout into
+/// every new worktree. Paths are relative to the repo root.
+#[derive(Debug, Clone, Default)]
+pub struct WorktreeSetup {
+    /// Copied into the worktree (e.g. ".env").
+    pub copy_files: Vec<String>,
+    /// Symlinked to the main checkout's copy (e.g. "node_modules").
+    pub symlink_dirs: Vec<String>,
+}
+
+/// Where `discover_repos` looks for repos under a multi-repo workspace.
+#[derive(Debug, Clone)]
+pub struct RepoDiscovery {
+    /// How many directory levels below the workspace root to search (1 =
+    /// immediate children). The search never descends into a repo.
+    pub depth: usize,
+    /// Directory names or workspace-relative paths to skip, with `*` and `?`
+    /// wildcards (e.g. "archive", "vendor/old-api", "legacy-*").
+    pub ignore: Vec<String>,
+}
+
+impl Default for RepoDiscovery {
+    fn default() -> Self {
+        Self {
+            depth: 1,
+            ignore: vec![],
+        }
+    }
 }
 
 /// Detect the repository root from any path within it
@@ -47,6 +86,7 @@ pub async fn create_worktree(
     branch_name: &str,
     base_ref: Option<&str>,
     worktree_base_dir: &Path,
+    setup: &WorktreeSetup,
 ) -> Result<WorktreeInfo, VibeError> {
     // Fetch origin so we have the latest refs
     let fetch_output = Command::new("git")
@@ -88,7 +128,8 @@ pub async fn create_worktree(
         .output()
         .await?;
 
-    if !output.status.success() {
+    let created_branch = output.status.success();
+    if !created_branch {
         // Branch might already exist — try without -b
         let output2 = Command::new("git")
             .current_dir(repo_root)
@@ -105,9 +146,12 @@ pub async fn create_worktree(
         }
     }
 
+    apply_worktree_setup(repo_root, &worktree_path, setup);
+
     Ok(WorktreeInfo {
         path: worktree_path,
         branch: branch_name.to_string(),
+        created_branch,
     })
 }
 
@@ -151,6 +195,29 @@ pub async fn remove_worktree(
 
 /// List all worktrees managed by vibe (identified by naming convention)
 pub async fn list_vibe_worktrees(repo_root: &Path) -> Result<Vec<WorktreeInfo>, VibeError> {
+    Ok(list_worktrees(repo_root)
+        .await?
+        .into_iter()
+        .filter(|wt| {
+            wt.path
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy().contains("-vibe-"))
+        })
+        .collect())
+}
+
+/// Where `branch` is checked out, if anywhere: the main checkout or any
+/// worktree of the repo.
+pub async fn branch_checkout(repo_root: &Path, branch: &str) -> Result<Option<PathBuf>, VibeError> {
+    Ok(list_worktrees(repo_root)
+        .await?
+        .into_iter()
+        .find(|wt| wt.branch == branch)
+        .map(|wt| wt.path))
+}
+
+/// Every checkout of the repo with a branch, the main one included.
+async fn list_worktrees(repo_root: &Path) -> Result<Vec<WorktreeInfo>, VibeError> {
     let output = Command::new("git")
         .current_dir(repo_root)
         .args(["worktree", "list", "--porcelain"])
@@ -172,12 +239,11 @@ pub async fn list_vibe_worktrees(repo_root: &Path) -> Result<Vec<WorktreeInfo>,
             }
         }
         if let (Some(path), Some(branch)) = (path, branch) {
-            if path
-                .file_name()
-                .is_some_and(|n| n.to_string_lossy().contains("-vibe-"))
-            {
-                worktrees.push(WorktreeInfo { path, branch });
-            }
+            worktrees.push(WorktreeInfo {
+                path,
+                branch,
+                created_branch: false,
+            });
         }
     }
 
@@ -194,11 +260,27 @@ pub async fn prune(repo_root: &Path) -> Result<(), VibeError> {
     Ok(())
 }
 
-/// Scan immediate subdirectories of `parent_dir` for git repositories.
-/// Returns a `RepoInfo` for each subdirectory that contains a `.git` directory or file.
-pub fn discover_repos(parent_dir: &Path) -> Result<Vec<RepoInfo>, VibeError> {
+/// Scan subdirectories of `parent_dir`, down to `discovery.depth` levels, for
+/// git repositories. Returns a `RepoInfo` for each subdirectory that contains a
+/// `.git` directory or file.
+pub fn discover_repos(
+    parent_dir: &Path,
+    discovery: &RepoDiscovery,
+) -> Result<Vec<RepoInfo>, VibeError> {
     let mut repos = Vec::new();
-    let entries = std::fs::read_dir(parent_dir)
+    scan_for_repos(parent_dir, parent_dir, discovery, 1, &mut repos)?;
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(repos)
+}
+
+fn scan_for_repos(
+    parent_dir: &Path,
+    dir: &Path,
+    discovery: &RepoDiscovery,
+    level: usize,
+    repos: &mut Vec<RepoInfo>,
+) -> Result<(), VibeError> {
+    let entries = std::fs::read_dir(dir)
         .map_err(|e| VibeError::Git(format!("Cannot read directory: {e}")))?;
 
     for entry in entries.flatten() {
@@ -206,20 +288,26 @@ pub fn discover_repos(parent_dir: &Path) -> Result<Vec<RepoInfo>, VibeError> {
         if !path.is_dir() {
             continue;
         }
+        let dir_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
         // Skip hidden directories (e.g. .vibe, .git)
-        if path
-            .file_name()
-            .is_some_and(|n| n.to_string_lossy().starts_with('.'))
+        if dir_name.starts_with('.') {
+            continue;
+        }
+        // Nested repos are named by their path relative to the workspace root
+        let name = path
+            .strip_prefix(parent_dir)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+        if discovery
+            .ignore
+            .iter()
+            .any(|pattern| glob_match(pattern, &dir_name) || glob_match(pattern, &name))
         {
             continue;
         }
         // Check if this subdir is a git repo
         if Repository::open(&path).is_ok() {
-            let name = path
-                .file_name()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .to_string();
             let db = default_branch(&path).unwrap_or_else(|_| "main".into());
             let url = remote_url(&path);
             repos.push(RepoInfo {
@@ -228,11 +316,42 @@ pub fn discover_repos(parent_dir: &Path) -> Result<Vec<RepoInfo>, VibeError> {
                 default_branch: db,
                 remote_url: url,
             });
+        } else if level < discovery.depth {
+            // Unreadable subdirectories are skipped rather than failing the scan
+            let _ = scan_for_repos(parent_dir, &path, discovery, level + 1, repos);
         }
     }
+    Ok(())
+}
 
-    repos.sort_by(|a, b| a.name.cmp(&b.name));
-    Ok(repos)
+/// Match `text` against a pattern where `*` is any run of characters (`/`
+/// included) and `?` any one character.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let (pattern, text): (Vec<char>, Vec<char>) = (pattern.chars().collect(), text.chars().collect());
+    // Position after the last `*` seen, and where in `text` it matched up to
+    let mut star: Option<(usize, usize)> = None;
+    let (mut p, mut t) = (0, 0);
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                star = Some((p, t));
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
 }
 
 /// Create a worktree at an exact path (used for multi-repo sessions).
@@ -244,6 +363,7 @@ pub async fn create_worktree_at(
     branch_name: &str,
     base_ref: Option<&str>,
     exact_path: &Path,
+    setup: &WorktreeSetup,
 ) -> Result<WorktreeInfo, VibeError> {
     // Fetch origin
     let fetch_output = Command::new("git")
@@ -275,7 +395,8 @@ pub async fn create_worktree_at(
         .output()
         .await?;
 
-    if !output.status.success() {
+    let created_branch = output.status.success();
+    if !created_branch {
         // Branch might already exist — try without -b
         let output2 = Command::new("git")
             .current_dir(repo_root)
@@ -292,12 +413,512 @@ pub async fn create_worktree_at(
         }
     }
 
+    apply_worktree_setup(repo_root, exact_path, setup);
+
     Ok(WorktreeInfo {
         path: exact_path.to_path_buf(),
         branch: branch_name.to_string(),
+        created_branch,
     })
 }
 
+/// Copy files and symlink directories from the main checkout into a fresh
+/// worktree. Best effort: missing sources are skipped and anything already
+/// present in the worktree (e.g. tracked files) is left alone.
+fn apply_worktree_setup(repo_root: &Path, worktree_path: &Path, setup: &WorktreeSetup) {
+    for rel in &setup.copy_files {
+        let src = repo_root.join(rel);
+        let dst = worktree_path.join(rel);
+        if !src.is_file() || dst.exists() {
+            tracing::debug!(file = %rel, "skipping worktree copy");
+            continue;
+        }
+        if let Some(parent) = dst.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match std::fs::copy(&src, &dst) {
+            Ok(_) => tracing::info!(file = %rel, "copied into worktree"),
+            Err(e) => tracing::warn!(file = %rel, error = %e, "failed to copy into worktree"),
+        }
+    }
+
+    for rel in &setup.symlink_dirs {
+        let src = repo_root.join(rel);
+        let dst = worktree_path.join(rel);
+        if !src.is_dir() || dst.symlink_metadata().is_ok() {
+            tracing::debug!(dir = %rel, "skipping worktree symlink");
+            continue;
+        }
+        if let Some(parent) = dst.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match std::os::unix::fs::symlink(&src, &dst) {
+            Ok(()) => tracing::info!(dir = %rel, "symlinked into worktree"),
+            Err(e) => tracing::warn!(dir = %rel, error = %e, "failed to symlink into worktree"),
+        }
+    }
+}
+
+/// Resolve the ref a session branch should be compared against.
+/// Prefers `origin/{default_branch}` and falls back to the local branch for
+/// repos without a remote.
+pub fn resolve_base_ref(worktree_path: &Path, default_branch: &str) -> String {
+    let remote = format!("origin/{default_branch}");
+    match Repository::open(worktree_path) {
+        Ok(repo) if repo.revparse_single(&remote).is_ok() => remote,
+        _ => default_branch.to_string(),
+    }
+}
+
+/// Diff a worktree's working tree (including the index and untracked files)
+/// against either the merge-base with `base_ref` or HEAD.
+pub fn diff_worktree(
+    worktree_path: &Path,
+    base_ref: &str,
+    scope: DiffScope,
+    repo_name: Option<&str>,
+) -> Result<Vec<FileDiff>, VibeError> {
+    let repo = Repository::open(worktree_path)?;
+    let head = repo.head()?.peel_to_commit()?;
+    let old_tree = match scope {
+        DiffScope::Head => head.tree()?,
+        DiffScope::Base => {
+            let base = repo.revparse_single(base_ref)?.peel_to_commit()?;
+            let merge_base = repo.merge_base(base.id(), head.id())?;
+            repo.find_commit(merge_base)?.tree()?
+        }
+    };
+
+    let mut opts = DiffOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    let diff = repo.diff_tree_to_workdir_with_index(Some(&old_tree), Some(&mut opts))?;
+
+    let mut files = Vec::new();
+    for idx in 0..diff.deltas().len() {
+        let delta = diff.get_delta(idx).expect("delta index in range");
+        let path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let change = match delta.status() {
+            Delta::Added => FileChange::Added,
+            Delta::Deleted => FileChange::Deleted,
+            Delta::Modified => FileChange::Modified,
+            Delta::Renamed => FileChange::Renamed,
+            Delta::Untracked => FileChange::Untracked,
+            _ => FileChange::Other,
+        };
+
+        let mut hunks = Vec::new();
+        let mut binary = delta.flags().is_binary();
+        match Patch::from_diff(&diff, idx)? {
+            Some(patch) => {
+                for h in 0..patch.num_hunks() {
+                    let (hunk, line_count) = patch.hunk(h)?;
+                    let mut lines = Vec::with_capacity(line_count);
+                    for l in 0..line_count {
+                        let line = patch.line_in_hunk(h, l)?;
+                        let kind = match line.origin() {
+                            '+' => DiffLineKind::Added,
+                            '-' => DiffLineKind::Removed,
+                            ' ' => DiffLineKind::Context,
+                            // "\ No newline at end of file" and friends
+                            _ => continue,
+                        };
+                        lines.push(DiffLine {
+                            kind,
+                            content: String::from_utf8_lossy(line.content())
+                                .trim_end_matches(['\n', '\r'])
+                                .to_string(),
+                        });
+                    }
+                    hunks.push(DiffHunk {
+                        header: String::from_utf8_lossy(hunk.header()).trim_end().to_string(),
+                        new_start: hunk.new_start(),
+                        new_lines: hunk.new_lines(),
+                        lines,
+                    });
+                }
+            }
+            None => binary = true,
+        }
+
+        files.push(FileDiff {
+            worktree: worktree_path.to_path_buf(),
+            repo: repo_name.map(|s| s.to_string()),
+            path,
+            change,
+            binary,
+            hunks,
+        });
+    }
+
+    Ok(files)
+}
+
+/// Stage a single hunk of `file` into the index.
+///
+/// The hunk is matched against the unstaged (index → workdir) diff by its
+/// working-tree line range. Untracked files are staged whole.
+pub fn stage_hunk(file: &FileDiff, hunk: &DiffHunk) -> Result<HunkApply, VibeError> {
+    let repo = Repository::open(&file.worktree)?;
+
+    if file.change == FileChange::Untracked {
+        let mut index = repo.index()?;
+        index.add_path(Path::new(&file.path))?;
+        index.write()?;
+        return Ok(HunkApply::Applied);
+    }
+
+    let mut diff_opts = DiffOptions::new();
+    diff_opts.pathspec(&file.path).disable_pathspec_match(true);
+    let diff = repo.diff_index_to_workdir(None, Some(&mut diff_opts))?;
+
+    let matcher = HunkMatcher::new(hunk);
+    let mut apply_opts = ApplyOptions::new();
+    apply_opts.hunk_callback(|h| h.is_some_and(|h| matcher.check(h.new_start(), h.new_lines())));
+    repo.apply(&diff, ApplyLocation::Index, Some(&mut apply_opts))?;
+    Ok(matcher.outcome())
+}
+
+/// Discard a single unstaged hunk of `file` from the working tree.
+///
+/// Applies the reverse of the matching index → workdir hunk, so staged
+/// content is left alone.
+pub fn discard_hunk(file: &FileDiff, hunk: &DiffHunk) -> Result<HunkApply, VibeError> {
+    if file.change == FileChange::Untracked {
+        return Err(VibeError::User(format!(
+            "'{}' is untracked — delete the file to discard it",
+            file.path
+        )));
+    }
+
+    let repo = Repository::open(&file.worktree)?;
+    let mut diff_opts = DiffOptions::new();
+    diff_opts
+        .pathspec(&file.path)
+        .disable_pathspec_match(true)
+        .reverse(true);
+    let diff = repo.diff_index_to_workdir(None, Some(&mut diff_opts))?;
+
+    // Reversed: the "old" side of each hunk is now the working tree.
+    let matcher = HunkMatcher::new(hunk);
+    let mut apply_opts = ApplyOptions::new();
+    apply_opts.hunk_callback(|h| h.is_some_and(|h| matcher.check(h.old_start(), h.old_lines())));
+    repo.apply(&diff, ApplyLocation::WorkDir, Some(&mut apply_opts))?;
+    Ok(matcher.outcome())
+}
+
+/// Picks the unstaged hunk with the same working-tree range as a displayed
+/// hunk, noting unstaged hunks that only overlap it.
+struct HunkMatcher {
+    start: u32,
+    lines: u32,
+    matched: Cell<bool>,
+    overlapped: Cell<bool>,
+}
+
+impl HunkMatcher {
+    fn new(hunk: &DiffHunk) -> Self {
+        Self {
+            start: hunk.new_start,
+            lines: hunk.new_lines,
+            matched: Cell::new(false),
+            overlapped: Cell::new(false),
+        }
+    }
+
+    /// Whether to apply the unstaged hunk at `start` with `lines` lines.
+    fn check(&self, start: u32, lines: u32) -> bool {
+        if start == self.start && lines == self.lines {
+            self.matched.set(true);
+            return true;
+        }
+        if start <= self.start + self.lines && self.start <= start + lines {
+            self.overlapped.set(true);
+        }
+        false
+    }
+
+    fn outcome(&self) -> HunkApply {
+        if self.matched.get() {
+            HunkApply::Applied
+        } else if self.overlapped.get() {
+            HunkApply::Changed
+        } else {
+            HunkApply::NothingUnstaged
+        }
+    }
+}
+
+/// Paths a worktree has changed since it forked from `base_ref`: committed,
+/// staged, unstaged and untracked.
+pub fn changed_files(worktree_path: &Path, base_ref: &str) -> Result<BTreeSet<String>, VibeError> {
+    let repo = Repository::open(worktree_path)?;
+    let head = repo.head()?.peel_to_commit()?;
+    let base = repo.revparse_single(base_ref)?.peel_to_commit()?;
+    let merge_base = repo.merge_base(base.id(), head.id())?;
+    let old_tree = repo.find_commit(merge_base)?.tree()?;
+
+    let mut opts = DiffOptions::new();
+    opts.include_untracked(true).recurse_untracked_dirs(true);
+    let diff = repo.diff_tree_to_workdir_with_index(Some(&old_tree), Some(&mut opts))?;
+
+    Ok(diff
+        .deltas()
+        .filter_map(|d| {
+            d.new_file()
+                .path()
+                .or_else(|| d.old_file().path())
+                .map(|p| p.to_string_lossy().to_string())
+        })
+        .collect())
+}
+
+/// Merge two branches in memory and return the paths that would conflict.
+/// Only committed work is considered; nothing is written to disk.
+pub fn merge_conflicts(
+    repo_path: &Path,
+    branch_a: &str,
+    branch_b: &str,
+) -> Result<Vec<String>, VibeError> {
+    let repo = Repository::open(repo_path)?;
+    let a = repo.revparse_single(branch_a)?.peel_to_commit()?;
+    let b = repo.revparse_single(branch_b)?.peel_to_commit()?;
+    let ancestor = repo.find_commit(repo.merge_base(a.id(), b.id())?)?.tree()?;
+    let index = repo.merge_trees(&ancestor, &a.tree()?, &b.tree()?, None)?;
+
+    let mut paths = BTreeSet::new();
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        let entry = conflict.our.or(conflict.their).or(conflict.ancestor);
+        if let Some(entry) = entry {
+            paths.insert(String::from_utf8_lossy(&entry.path).to_string());
+        }
+    }
+    Ok(paths.into_iter().collect())
+}
+
+/// Fetch origin for a repo. Worktrees share refs with their main repo, so one
+/// fetch per repo is enough for every session worktree created from it.
+pub async fn fetch_origin(repo_root: &Path) -> Result<(), VibeError> {
+    let output = Command::new("git")
+        .current_dir(repo_root)
+        .args(["fetch", "origin"])
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(VibeError::Git(format!(
+            "git fetch origin failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// True if a worktree has a rebase/merge in progress or unresolved conflicts.
+pub fn has_unresolved_sync(worktree_path: &Path) -> bool {
+    let Ok(repo) = Repository::open(worktree_path) else {
+        return false;
+    };
+    repo.state() != RepositoryState::Clean
+        || repo.index().is_ok_and(|idx| idx.has_conflicts())
+}
+
+/// Add `pattern` to the repo's `info/exclude` (shared by all its worktrees),
+/// for files vibe writes into worktrees that shouldn't show up as changes.
+pub fn exclude_locally(worktree_path: &Path, pattern: &str) -> Result<(), VibeError> {
+    let repo = Repository::open(worktree_path)?;
+    let exclude = repo.commondir().join("info").join("exclude");
+    let content = std::fs::read_to_string(&exclude).unwrap_or_default();
+    if content.lines().any(|line| line.trim() == pattern) {
+        return Ok(());
+    }
+    if let Some(dir) = exclude.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let separator = if content.is_empty() || content.ends_with('\n') { "" } else { "\n" };
+    std::fs::write(&exclude, format!("{content}{separator}{pattern}\n"))?;
+    Ok(())
+}
+
+/// Rebase or merge a worktree's branch onto `onto`.
+///
+/// Worktrees with uncommitted changes or an operation already in progress
+/// are left untouched. On conflict the rebase/merge is left in progress for
+/// the user (or an agent) to resolve, unless `abort_on_conflict` is set.
+pub async fn sync_worktree(
+    worktree_path: &Path,
+    onto: &str,
+    strategy: SyncStrategy,
+    abort_on_conflict: bool,
+) -> SyncOutcome {
+    if has_unresolved_sync(worktree_path) {
+        return SyncOutcome::InProgress;
+    }
+
+    match git_stdout(worktree_path, &["status", "--porcelain", "--untracked-files=no"]).await {
+        Ok(status) if !status.is_empty() => {
+            return SyncOutcome::Skipped("uncommitted changes".into());
+        }
+        Ok(_) => {}
+        Err(e) => return SyncOutcome::Failed(e.to_string()),
+    }
+
+    // Already contains the base?
+    let up_to_date = Command::new("git")
+        .current_dir(worktree_path)
+        .args(["merge-base", "--is-ancestor", onto, "HEAD"])
+        .output()
+        .await;
+    if up_to_date.is_ok_and(|o| o.status.success()) {
+        return SyncOutcome::UpToDate;
+    }
+
+    let args: &[&str] = match strategy {
+        SyncStrategy::Rebase => &["rebase", onto],
+        SyncStrategy::Merge => &["merge", "--no-edit", onto],
+    };
+    let output = match Command::new("git")
+        .current_dir(worktree_path)
+        .args(args)
+        .output()
+        .await
+    {
+        Ok(o) => o,
+        Err(e) => return SyncOutcome::Failed(e.to_string()),
+    };
+    if output.status.success() {
+        return SyncOutcome::Synced;
+    }
+
+    let files: Vec<String> = git_stdout(worktree_path, &["diff", "--name-only", "--diff-filter=U"])
+        .await
+        .unwrap_or_default()
+        .lines()
+        .map(|l| l.to_string())
+        .collect();
+
+    if files.is_empty() || abort_on_conflict {
+        let abort: &[&str] = match strategy {
+            SyncStrategy::Rebase => &["rebase", "--abort"],
+            SyncStrategy::Merge => &["merge", "--abort"],
+        };
+        let _ = Command::new("git")
+            .current_dir(worktree_path)
+            .args(abort)
+            .output()
+            .await;
+        if files.is_empty() {
+            return SyncOutcome::Failed(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        return SyncOutcome::Conflicted {
+            files,
+            aborted: true,
+        };
+    }
+
+    SyncOutcome::Conflicted {
+        files,
+        aborted: false,
+    }
+}
+
+/// True if a worktree has staged, unstaged or untracked changes.
+pub async fn has_uncommitted_changes(worktree_path: &Path) -> Result<bool, VibeError> {
+    Ok(!git_stdout(worktree_path, &["status", "--porcelain"]).await?.is_empty())
+}
+
+/// Merge `branch` into the worktree's current branch with a merge commit.
+///
+/// Returns the conflicted paths; empty means the merge succeeded. Conflicted
+/// merges are left in progress so they can be resolved in place.
+pub async fn merge_branch(worktree_path: &Path, branch: &str) -> Result<Vec<String>, VibeError> {
+    let output = Command::new("git")
+        .current_dir(worktree_path)
+        .args(["merge", "--no-ff", "--no-edit", branch])
+        .output()
+        .await?;
+    if output.status.success() {
+        return Ok(vec![]);
+    }
+
+    let files: Vec<String> = git_stdout(worktree_path, &["diff", "--name-only", "--diff-filter=U"])
+        .await
+        .unwrap_or_default()
+        .lines()
+        .map(|l| l.to_string())
+        .collect();
+    if files.is_empty() {
+        // Failed for some other reason (unknown branch, dirty tree, ...)
+        let _ = Command::new("git")
+            .current_dir(worktree_path)
+            .args(["merge", "--abort"])
+            .output()
+            .await;
+        return Err(VibeError::Git(format!(
+            "git merge {branch} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(files)
+}
+
+/// Fetch a GitHub PR's head commit (`pull/N/head`) into `branch`, a branch
+/// vibe names and owns for the PR. The PR's own head branch name is never
+/// used: a fork can call its branch anything, including `main`.
+///
+/// An existing `branch` is only updated if vibe created it for this PR, and
+/// only by fast-forward, so no local work is overwritten.
+pub async fn fetch_pr_head(repo_root: &Path, pr_number: u64, branch: &str) -> Result<(), VibeError> {
+    let owner_key = format!("branch.{branch}.vibe-pr");
+    let local_ref = format!("refs/heads/{branch}");
+    if git_stdout(repo_root, &["rev-parse", "--verify", "--quiet", &local_ref]).await.is_ok() {
+        let owner = git_stdout(repo_root, &["config", "--get", &owner_key]).await.ok();
+        if owner != Some(pr_number.to_string()) {
+            return Err(VibeError::Git(format!(
+                "branch {branch} already exists and vibe didn't create it for PR #{pr_number}; rename or delete it first"
+            )));
+        }
+    }
+
+    let refspec = format!("pull/{pr_number}/head:{branch}");
+    git_stdout(repo_root, &["fetch", "origin", &refspec])
+        .await
+        .map_err(|e| VibeError::Git(format!("fetching PR #{pr_number} into {branch}: {e}")))?;
+    git_stdout(repo_root, &["config", &owner_key, &pr_number.to_string()]).await?;
+    Ok(())
+}
+
+/// Number of commits on the worktree's HEAD that are not on `base_ref`.
+pub async fn commits_ahead(worktree_path: &Path, base_ref: &str) -> Result<usize, VibeError> {
+    let range = format!("{base_ref}..HEAD");
+    let count = git_stdout(worktree_path, &["rev-list", "--count", &range]).await?;
+    count
+        .parse()
+        .map_err(|_| VibeError::Git(format!("unexpected rev-list output: {count}")))
+}
+
+/// Push the worktree's branch to origin and set it as upstream.
+pub async fn push_branch(worktree_path: &Path, branch: &str) -> Result<(), VibeError> {
+    git_stdout(worktree_path, &["push", "-u", "origin", branch]).await?;
+    Ok(())
+}
+
+async fn git_stdout(dir: &Path, args: &[&str]) -> Result<String, VibeError> {
+    let output = Command::new("git").current_dir(dir).args(args).output().await?;
+    if !output.status.success() {
+        return Err(VibeError::Git(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
 async fn worktree_branch(worktree_path: &Path) -> Result<String, VibeError> {
     let output = Command::new("git")
         .current_dir(worktree_path)
@@ -306,3 +927,243 @@ async fn worktree_branch(worktree_path: &Path) -> Result<String, VibeError> {
         .await?;
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, git};
+
+    #[test]
+    fn test_apply_worktree_setup() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (root, wt) = (tmp.path().join("root"), tmp.path().join("wt"));
+        std::fs::create_dir_all(root.join("config")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        std::fs::create_dir_all(&wt).unwrap();
+        std::fs::write(root.join(".env"), "SECRET=1").unwrap();
+        std::fs::write(root.join("config/local.toml"), "x = 1").unwrap();
+        std::fs::write(root.join("tracked.txt"), "main checkout").unwrap();
+        std::fs::write(wt.join("tracked.txt"), "worktree").unwrap();
+
+        let setup = WorktreeSetup {
+            copy_files: vec![".env".into(), "config/local.toml".into(), "tracked.txt".into(), "missing.env".into()],
+            symlink_dirs: vec!["node_modules".into(), "vendor/missing".into()],
+        };
+        apply_worktree_setup(&root, &wt, &setup);
+
+        assert_eq!(std::fs::read_to_string(wt.join(".env")).unwrap(), "SECRET=1");
+        assert_eq!(std::fs::read_to_string(wt.join("config/local.toml")).unwrap(), "x = 1");
+        // Files already in the worktree are left alone
+        assert_eq!(std::fs::read_to_string(wt.join("tracked.txt")).unwrap(), "worktree");
+        assert_eq!(std::fs::read_link(wt.join("node_modules")).unwrap(), root.join("node_modules"));
+        assert!(wt.join("node_modules/pkg").is_dir());
+        // Missing sources are skipped without creating anything
+        assert!(!wt.join("missing.env").exists());
+        assert!(!wt.join("vendor").exists());
+    }
+
+    #[test]
+    fn test_discover_repos_depth_and_ignore() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = tmp.path();
+        for repo in ["api", "services/auth", "services/billing", "libs/ui/core", "legacy-web", "archive/old"] {
+            std::fs::create_dir_all(ws.join(repo)).unwrap();
+            git(&ws.join(repo), &["init", "-q", "-b", "main"]);
+        }
+        // Not a repo, and nothing below it to find
+        std::fs::create_dir_all(ws.join("docs/guides")).unwrap();
+        // The search doesn't descend into a repo
+        std::fs::create_dir_all(ws.join("api/vendor/dep")).unwrap();
+        git(&ws.join("api/vendor/dep"), &["init", "-q"]);
+
+        let names = |depth: usize, ignore: &[&str]| -> Vec<String> {
+            let discovery = RepoDiscovery {
+                depth,
+                ignore: ignore.iter().map(|s| s.to_string()).collect(),
+            };
+            discover_repos(ws, &discovery).unwrap().into_iter().map(|r| r.name).collect()
+        };
+
+        assert_eq!(names(1, &[]), ["api", "legacy-web"]);
+        assert_eq!(
+            names(2, &[]),
+            ["api", "archive/old", "legacy-web", "services/auth", "services/billing"]
+        );
+        assert_eq!(
+            names(3, &[]),
+            ["api", "archive/old", "legacy-web", "libs/ui/core", "services/auth", "services/billing"]
+        );
+        // By directory name, relative path or glob
+        assert_eq!(
+            names(3, &["archive", "services/billing", "legacy-*"]),
+            ["api", "libs/ui/core", "services/auth"]
+        );
+        assert_eq!(names(3, &["*/ui"]), ["api", "archive/old", "legacy-web", "services/auth", "services/billing"]);
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("legacy-*", "legacy-web"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("services/*", "services/auth"));
+        assert!(glob_match("a?c*x", "abcdx"));
+        assert!(glob_match("*a*b", "xaab"));
+        assert!(!glob_match("legacy-*", "web-legacy"));
+        assert!(!glob_match("a?c", "ac"));
+        assert!(!glob_match("archive", "archive2"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pr_head_never_touches_other_branches() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = tmp.path().join("origin");
+        std::fs::create_dir(&origin).unwrap();
+        git(&origin, &["init", "-q", "-b", "main"]);
+        git(&origin, &["commit", "-q", "--allow-empty", "-m", "base"]);
+        let base = git(&origin, &["rev-parse", "HEAD"]);
+        // A fork PR whose head branch is also called main
+        git(&origin, &["commit", "-q", "--allow-empty", "-m", "fork"]);
+        let fork = git(&origin, &["rev-parse", "HEAD"]);
+        git(&origin, &["update-ref", "refs/pull/7/head", &fork]);
+        git(&origin, &["reset", "-q", "--hard", &base]);
+
+        let clone = tmp.path().join("clone");
+        git(tmp.path(), &["clone", "-q", origin.to_str().unwrap(), "clone"]);
+        git(&clone, &["branch", "mine"]);
+
+        // Branches vibe didn't create for the PR are refused
+        assert!(fetch_pr_head(&clone, 7, "main").await.is_err());
+        assert!(fetch_pr_head(&clone, 7, "mine").await.is_err());
+        assert_eq!(git(&clone, &["rev-parse", "main"]), base);
+        assert_eq!(git(&clone, &["rev-parse", "mine"]), base);
+
+        fetch_pr_head(&clone, 7, "vibe/pr-7").await.unwrap();
+        assert_eq!(git(&clone, &["rev-parse", "vibe/pr-7"]), fork);
+        // Its own branch is reused, but not by another PR
+        fetch_pr_head(&clone, 7, "vibe/pr-7").await.unwrap();
+        assert!(fetch_pr_head(&clone, 8, "vibe/pr-7").await.is_err());
+    }
+
+    /// Repo with `main` and a worktree on `feat`, each with a commit of its
+    /// own. `feat_file` is the file feat's commit changes.
+    fn diverged(tmp: &Path, feat_file: &str) -> (std::path::PathBuf, std::path::PathBuf) {
+        let (root, wt) = (tmp.join("root"), tmp.join("wt"));
+        crate::test_support::init_repo(&root);
+        commit_file(&root, "shared.txt", "base\n");
+        git(&root, &["worktree", "add", "-q", "-b", "feat", wt.to_str().unwrap()]);
+        commit_file(&wt, feat_file, "feat\n");
+        commit_file(&root, "shared.txt", "main\n");
+        (root, wt)
+    }
+
+    #[tokio::test]
+    async fn test_sync_worktree_rebase_and_merge() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (root, wt) = diverged(tmp.path(), "feat.txt");
+        let main = git(&root, &["rev-parse", "main"]);
+
+        std::fs::write(wt.join("feat.txt"), "dirty\n").unwrap();
+        assert_eq!(
+            sync_worktree(&wt, "main", SyncStrategy::Rebase, false).await,
+            SyncOutcome::Skipped("uncommitted changes".into())
+        );
+        git(&wt, &["checkout", "-q", "--", "feat.txt"]);
+
+        assert_eq!(sync_worktree(&wt, "main", SyncStrategy::Rebase, false).await, SyncOutcome::Synced);
+        // Rebased: main is the parent, no merge commit
+        assert_eq!(git(&wt, &["rev-parse", "HEAD^"]), main);
+        assert_eq!(sync_worktree(&wt, "main", SyncStrategy::Rebase, false).await, SyncOutcome::UpToDate);
+
+        commit_file(&root, "other.txt", "more\n");
+        assert_eq!(sync_worktree(&wt, "main", SyncStrategy::Merge, false).await, SyncOutcome::Synced);
+        assert_eq!(git(&wt, &["rev-parse", "HEAD^2"]), git(&root, &["rev-parse", "main"]));
+    }
+
+    #[tokio::test]
+    async fn test_sync_worktree_conflict() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (_, wt) = diverged(tmp.path(), "shared.txt");
+        let head = git(&wt, &["rev-parse", "HEAD"]);
+
+        // --abort-on-conflict puts the branch back
+        assert_eq!(
+            sync_worktree(&wt, "main", SyncStrategy::Rebase, true).await,
+            SyncOutcome::Conflicted {
+                files: vec!["shared.txt".into()],
+                aborted: true,
+            }
+        );
+        assert!(!has_unresolved_sync(&wt));
+        assert_eq!(git(&wt, &["rev-parse", "HEAD"]), head);
+
+        // Otherwise the conflict is left in place, and later syncs leave it be
+        assert_eq!(
+            sync_worktree(&wt, "main", SyncStrategy::Merge, false).await,
+            SyncOutcome::Conflicted {
+                files: vec!["shared.txt".into()],
+                aborted: false,
+            }
+        );
+        assert!(has_unresolved_sync(&wt));
+        assert_eq!(sync_worktree(&wt, "main", SyncStrategy::Merge, false).await, SyncOutcome::InProgress);
+    }
+
+    /// `lines.txt` with lines "1" to "20", committed.
+    fn numbered_file(dir: &Path) -> Vec<String> {
+        crate::test_support::init_repo(dir);
+        let lines: Vec<String> = (1..=20).map(|n| n.to_string()).collect();
+        commit_file(dir, "lines.txt", &(lines.join("\n") + "\n"));
+        lines
+    }
+
+    fn write_lines(dir: &Path, lines: &[String]) {
+        std::fs::write(dir.join("lines.txt"), lines.join("\n") + "\n").unwrap();
+    }
+
+    fn head_diff(dir: &Path) -> FileDiff {
+        diff_worktree(dir, "HEAD", DiffScope::Head, None).unwrap().remove(0)
+    }
+
+    #[test]
+    fn test_stage_and_discard_one_of_two_hunks() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut lines = numbered_file(tmp.path());
+        lines[1] = "two".into();
+        lines[17] = "eighteen".into();
+        write_lines(tmp.path(), &lines);
+        let file = head_diff(tmp.path());
+        assert_eq!(file.hunks.len(), 2);
+
+        assert_eq!(stage_hunk(&file, &file.hunks[0]).unwrap(), HunkApply::Applied);
+        let staged = git(tmp.path(), &["diff", "--cached"]);
+        assert!(staged.contains("+two") && !staged.contains("+eighteen"), "{staged}");
+        // The diff it came from is stale now
+        assert_eq!(stage_hunk(&file, &file.hunks[0]).unwrap(), HunkApply::NothingUnstaged);
+
+        assert_eq!(discard_hunk(&file, &file.hunks[1]).unwrap(), HunkApply::Applied);
+        let content = std::fs::read_to_string(tmp.path().join("lines.txt")).unwrap();
+        assert!(content.contains("two\n") && content.contains("\n18\n"), "{content}");
+        assert!(git(tmp.path(), &["diff"]).is_empty());
+        assert!(git(tmp.path(), &["diff", "--cached"]).contains("+two"));
+    }
+
+    #[test]
+    fn test_partly_staged_hunk_is_left_alone() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut lines = numbered_file(tmp.path());
+        lines[4] = "five".into();
+        write_lines(tmp.path(), &lines);
+        git(tmp.path(), &["add", "lines.txt"]);
+        lines[5] = "six".into();
+        write_lines(tmp.path(), &lines);
+
+        // One hunk against HEAD, half of it staged
+        let file = head_diff(tmp.path());
+        assert_eq!(file.hunks.len(), 1);
+        let before = (git(tmp.path(), &["diff"]), git(tmp.path(), &["diff", "--cached"]));
+
+        assert_eq!(stage_hunk(&file, &file.hunks[0]).unwrap(), HunkApply::Changed);
+        assert_eq!(discard_hunk(&file, &file.hunks[0]).unwrap(), HunkApply::Changed);
+        assert_eq!((git(tmp.path(), &["diff"]), git(tmp.path(), &["diff", "--cached"])), before);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::domain::session::Session;
+use crate::error::VibeError;
+use std::path::Path;
+use tokio::process::Command;
+
+/// Session details exposed to hooks as `VIBE_*` environment variables.
+pub struct HookContext<'a> {
+    pub session: &'a str,
+    pub branch: &'a str,
+    /// Worktree the hook runs in (a repo worktree for multi-repo sessions).
+    pub worktree: &'a Path,
+    /// Repo name for multi-repo sessions, `None` for single-repo.
+    pub repo: Option<&'a str>,
+    pub workspace_root: &'a Path,
+}
+
+/// Run a session hook with `sh -c` in the context's worktree.
+///
+/// Environment: `VIBE_SESSION`, `VIBE_BRANCH`, `VIBE_WORKTREE`,
+/// `VIBE_WORKSPACE_ROOT` and, for multi-repo sessions, `VIBE_REPO`.
+pub async fn run(name: &str, command: &str, ctx: &HookContext<'_>) -> Result<(), VibeError> {
+    let mut cmd = Command::new("sh");
+    cmd.current_dir(ctx.worktree)
+        .args(["-c", command])
+        .env("VIBE_SESSION", ctx.session)
+        .env("VIBE_BRANCH", ctx.branch)
+        .env("VIBE_WORKTREE", ctx.worktree)
+        .env("VIBE_WORKSPACE_ROOT", ctx.workspace_root);
+    if let Some(repo) = ctx.repo {
+        cmd.env("VIBE_REPO", repo);
+    }
+
+    tracing::info!(hook = name, session = ctx.session, repo = ?ctx.repo, "running hook");
+    let output = cmd.output().await?;
+    if !output.status.success() {
+        // Build tools report errors on either stream; keep the end of both
+        let combined = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let tail: Vec<&str> = combined.lines().rev().take(5).collect();
+        let tail: Vec<&str> = tail.into_iter().rev().collect();
+        let location = ctx.repo.map(|r| format!(" in {r}")).unwrap_or_default();
+        return Err(VibeError::Hook(format!(
+            "{name}{location} exited with {}: {}",
+            output.status,
+            tail.join("\n")
+        )));
+    }
+    Ok(())
+}
+
+/// Run `post_session_hook` in each of a session's worktrees that still exists.
+/// Failures are logged rather than returned so teardown always proceeds.
+pub async fn run_post_session(command: &str, session: &Session, workspace_root: &Path) {
+    let worktrees: Vec<(Option<&str>, &Path)> = if session.repo_worktrees.is_empty() {
+        vec![(None, session.worktree_path.as_path())]
+    } else {
+        session
+            .repo_worktrees
+            .iter()
+            .map(|(name, path)| (Some(name.as_str()), path.as_path()))
+            .collect()
+    };
+
+    for (repo, worktree) in worktrees {
+        if !worktree.exists() {
+            continue;
+        }
+        let ctx = HookContext {
+            session: &session.name,
+            branch: session.branch_for(repo),
+            worktree,
+            repo,
+            workspace_root,
+        };
+        if let Err(e) = run("post_session_hook", command, &ctx).await {
+            tracing::warn!(session = %session.name, error = %e, "post_session_hook failed");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context<'a>(worktree: &'a Path, repo: Option<&'a str>) -> HookContext<'a> {
+        HookContext {
+            session: "auth",
+            branch: "feat/auth",
+            worktree,
+            repo,
+            workspace_root: Path::new("/ws"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hook_env() {
+        let tmp = tempfile::tempdir().unwrap();
+        let command = r#"printf '%s|%s|%s|%s|%s|%s' "$VIBE_SESSION" "$VIBE_BRANCH" "$VIBE_WORKTREE" \
+            "$VIBE_WORKSPACE_ROOT" "${VIBE_REPO-unset}" "$PWD" > env.txt"#;
+
+        run("pre_session_hook", command, &context(tmp.path(), None)).await.unwrap();
+        let wt = tmp.path().display();
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join("env.txt")).unwrap(),
+            format!("auth|feat/auth|{wt}|/ws|unset|{wt}")
+        );
+
+        run("pre_session_hook", command, &context(tmp.path(), Some("api"))).await.unwrap();
+        let env = std::fs::read_to_string(tmp.path().join("env.txt")).unwrap();
+        assert_eq!(env.split('|').nth(4), Some("api"));
+    }
+
+    #[tokio::test]
+    async fn test_failing_hook_reports_output_tail() {
+        let tmp = tempfile::tempdir().unwrap();
+        let command = "for i in 1 2 3 4 5; do echo out$i; done; echo err >&2; exit 3";
+
+        let err = run("pre_session_hook", command, &context(tmp.path(), Some("api")))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("pre_session_hook in api exited with"), "{err}");
+        assert!(err.ends_with("out2\nout3\nout4\nout5\nerr"), "{err}");
+        assert!(!err.contains("out1"), "{err}");
+    }
+}
@@ -2,6 +2,9 @@ pub mod claude;
 pub mod clipboard;
 pub mod gh;
 pub mod git;
+pub mod hooks;
+pub mod mux;
 pub mod state;
 pub mod tmux;
+pub mod transcript;
 pub mod watcher;
@@ -0,0 +1,335 @@
+//! Terminal multiplexer backends.
+//!
+//! Sessions and agents run in windows and panes of a multiplexer. tmux is
+//! the default; the built-in PTY backend keeps the terminals inside the
+//! dashboard process and renders them there, for machines without tmux.
+//! Commands and the TUI go through [`current`] rather than a backend
+//! directly.
+
+use crate::error::VibeError;
+use crate::infra::tmux::{PaneInfo, TmuxController};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::OnceLock;
+
+#[cfg(test)]
+pub mod fake;
+pub mod pty;
+
+pub use pty::PtyMux;
+
+/// Which multiplexer runs sessions (`multiplexer` in config).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MuxKind {
+    #[default]
+    Tmux,
+    /// Terminals owned by the dashboard; they close when it exits.
+    Pty,
+}
+
+/// Window and pane operations sessions and agents need.
+///
+/// Windows are addressed by ID or `{session}:{name}`; operations on a pane
+/// also take a window, meaning its first pane.
+pub trait Multiplexer {
+    /// Create the session windows live in, if it doesn't exist.
+    fn ensure_session(&self, session: &str) -> impl Future<Output = Result<(), VibeError>> + Send;
+
+    /// Open a window running a shell in `working_dir`, in the background.
+    /// Returns its ID.
+    fn create_window(
+        &self,
+        session: &str,
+        name: &str,
+        working_dir: &str,
+    ) -> impl Future<Output = Result<String, VibeError>> + Send;
+
+    /// Open another shell pane in a window. Returns its ID.
+    fn split_pane(
+        &self,
+        window: &str,
+        working_dir: &str,
+        horizontal: bool,
+    ) -> impl Future<Output = Result<String, VibeError>> + Send;
+
+    /// Arrange a window's panes with a tmux preset layout.
+    fn select_layout(&self, window: &str, layout: &str) -> impl Future<Output = Result<(), VibeError>> + Send;
+
+    /// Bring a window (or the window holding a pane) to the front.
+    fn select_window(&self, target: &str) -> impl Future<Output = Result<(), VibeError>> + Send;
+
+    /// Make a pane the active one in its window.
+    fn select_pane(&self, pane_id: &str) -> impl Future<Output = Result<(), VibeError>> + Send;
+
+    /// Type a command into a pane and press Enter.
+    fn send_keys(&self, target: &str, command: &str) -> impl Future<Output = Result<(), VibeError>> + Send;
+
+    /// Press keys by tmux name ("y", "Enter", "Escape", "C-c"), without a
+    /// trailing Enter.
+    fn send_key_names(&self, pane_id: &str, keys: &[&str]) -> impl Future<Output = Result<(), VibeError>> + Send;
+
+    /// Paste text into a pane and submit it with Enter.
+    fn paste_text(&self, pane_id: &str, text: &str) -> impl Future<Output = Result<(), VibeError>> + Send;
+
+    /// The last `lines` lines a pane shows.
+    fn capture_pane(&self, pane_id: &str, lines: u32) -> impl Future<Output = Result<String, VibeError>> + Send;
+
+    /// Feed everything a pane prints to a shell command's stdin. A pane that
+    /// is already piped is left alone.
+    fn pipe_pane(&self, target: &str, command: &str) -> impl Future<Output = Result<(), VibeError>> + Send;
+
+    fn kill_window(&self, target: &str) -> impl Future<Output = Result<(), VibeError>> + Send;
+
+    fn kill_pane(&self, pane_id: &str) -> impl Future<Output = Result<(), VibeError>> + Send;
+
+    fn first_pane_id(&self, window: &str) -> impl Future<Output = Result<String, VibeError>> + Send;
+
+    /// A window's first pane ID and the command running in it.
+    fn first_pane_info(&self, window: &str) -> impl Future<Output = Result<(String, String), VibeError>> + Send;
+
+    /// Every pane in a session.
+    fn list_panes(&self, session: &str) -> impl Future<Output = Result<Vec<PaneInfo>, VibeError>> + Send;
+
+    fn window_exists(&self, target: &str) -> impl Future<Output = bool> + Send;
+
+    fn pane_exists(&self, pane_id: &str) -> impl Future<Output = bool> + Send;
+
+    fn window_id_for_pane(&self, pane_id: &str) -> impl Future<Output = Result<String, VibeError>> + Send;
+}
+
+/// The tmux backend: [`TmuxController`] behind the trait.
+pub struct TmuxMux;
+
+impl Multiplexer for TmuxMux {
+    async fn ensure_session(&self, session: &str) -> Result<(), VibeError> {
+        TmuxController::ensure_session(session).await
+    }
+
+    async fn create_window(&self, session: &str, name: &str, working_dir: &str) -> Result<String, VibeError> {
+        let window_id = TmuxController::create_window(session, name, working_dir).await?;
+        // Keep the name vibe looks windows up by when Claude starts
+        let _ = TmuxController::disable_auto_rename_for(&window_id).await;
+        Ok(window_id)
+    }
+
+    async fn split_pane(&self, window: &str, working_dir: &str, horizontal: bool) -> Result<String, VibeError> {
+        TmuxController::split_pane(window, working_dir, horizontal).await
+    }
+
+    async fn select_layout(&self, window: &str, layout: &str) -> Result<(), VibeError> {
+        TmuxController::select_layout(window, layout).await
+    }
+
+    async fn select_window(&self, target: &str) -> Result<(), VibeError> {
+        TmuxController::select_window(target).await
+    }
+
+    async fn select_pane(&self, pane_id: &str) -> Result<(), VibeError> {
+        TmuxController::select_pane(pane_id).await
+    }
+
+    async fn send_keys(&self, target: &str, command: &str) -> Result<(), VibeError> {
+        TmuxController::send_keys(target, command).await
+    }
+
+    async fn send_key_names(&self, pane_id: &str, keys: &[&str]) -> Result<(), VibeError> {
+        TmuxController::send_key_names(pane_id, keys).await
+    }
+
+    async fn paste_text(&self, pane_id: &str, text: &str) -> Result<(), VibeError> {
+        TmuxController::paste_text(pane_id, text).await
+    }
+
+    async fn capture_pane(&self, pane_id: &str, lines: u32) -> Result<String, VibeError> {
+        TmuxController::capture_pane(pane_id, lines).await
+    }
+
+    async fn pipe_pane(&self, target: &str, command: &str) -> Result<(), VibeError> {
+        TmuxController::pipe_pane(target, command).await
+    }
+
+    async fn kill_window(&self, target: &str) -> Result<(), VibeError> {
+        TmuxController::kill_window(target).await
+    }
+
+    async fn kill_pane(&self, pane_id: &str) -> Result<(), VibeError> {
+        TmuxController::kill_pane(pane_id).await
+    }
+
+    async fn first_pane_id(&self, window: &str) -> Result<String, VibeError> {
+        TmuxController::first_pane_id(window).await
+    }
+
+    async fn first_pane_info(&self, window: &str) -> Result<(String, String), VibeError> {
+        TmuxController::first_pane_info(window).await
+    }
+
+    async fn list_panes(&self, session: &str) -> Result<Vec<PaneInfo>, VibeError> {
+        TmuxController::list_panes(session).await
+    }
+
+    async fn window_exists(&self, target: &str) -> bool {
+        TmuxController::window_exists(target).await
+    }
+
+    async fn pane_exists(&self, pane_id: &str) -> bool {
+        TmuxController::pane_exists(pane_id).await
+    }
+
+    async fn window_id_for_pane(&self, pane_id: &str) -> Result<String, VibeError> {
+        TmuxController::window_id_for_pane(pane_id).await
+    }
+}
+
+/// The backend this process uses.
+pub enum Mux {
+    Tmux(TmuxMux),
+    Pty(PtyMux),
+    #[cfg(test)]
+    Fake(fake::FakeMux),
+}
+
+static CURRENT: OnceLock<Mux> = OnceLock::new();
+
+#[cfg(test)]
+thread_local! {
+    /// Backend `current` returns on this thread, set by [`fake_for_test`].
+    static TEST_MUX: std::cell::Cell<Option<&'static Mux>> = const { std::cell::Cell::new(None) };
+}
+
+/// Use `mux` for the rest of the process. Only the first call counts.
+pub fn install(mux: Mux) {
+    let _ = CURRENT.set(mux);
+}
+
+/// The installed backend; tmux unless the dashboard installed another.
+pub fn current() -> &'static Mux {
+    #[cfg(test)]
+    if let Some(mux) = TEST_MUX.get() {
+        return mux;
+    }
+    CURRENT.get_or_init(|| Mux::Tmux(TmuxMux))
+}
+
+/// Make [`current`] a fresh [`fake::FakeMux`] on this thread and return it.
+/// `#[tokio::test]` runs the test and what it spawns on one thread, so tests
+/// don't see each other's fakes.
+#[cfg(test)]
+pub fn fake_for_test() -> &'static fake::FakeMux {
+    let mux: &'static Mux = Box::leak(Box::new(Mux::Fake(fake::FakeMux::default())));
+    TEST_MUX.set(Some(mux));
+    match mux {
+        Mux::Fake(fake) => fake,
+        _ => unreachable!(),
+    }
+}
+
+impl Mux {
+    pub fn kind(&self) -> MuxKind {
+        match self {
+            Self::Tmux(_) => MuxKind::Tmux,
+            Self::Pty(_) => MuxKind::Pty,
+            #[cfg(test)]
+            Self::Fake(_) => MuxKind::Tmux,
+        }
+    }
+
+    /// The PTY backend, for the dashboard to draw and type into its panes.
+    pub fn pty(&self) -> Option<&PtyMux> {
+        match self {
+            Self::Pty(pty) => Some(pty),
+            _ => None,
+        }
+    }
+}
+
+/// Forward a call to whichever backend `$self` is.
+macro_rules! dispatch {
+    ($self:ident, $mux:ident => $call:expr) => {
+        match $self {
+            Mux::Tmux($mux) => $call,
+            Mux::Pty($mux) => $call,
+            #[cfg(test)]
+            Mux::Fake($mux) => $call,
+        }
+    };
+}
+
+impl Multiplexer for Mux {
+    async fn ensure_session(&self, session: &str) -> Result<(), VibeError> {
+        dispatch!(self, m => m.ensure_session(session).await)
+    }
+
+    async fn create_window(&self, session: &str, name: &str, working_dir: &str) -> Result<String, VibeError> {
+        dispatch!(self, m => m.create_window(session, name, working_dir).await)
+    }
+
+    async fn split_pane(&self, window: &str, working_dir: &str, horizontal: bool) -> Result<String, VibeError> {
+        dispatch!(self, m => m.split_pane(window, working_dir, horizontal).await)
+    }
+
+    async fn select_layout(&self, window: &str, layout: &str) -> Result<(), VibeError> {
+        dispatch!(self, m => m.select_layout(window, layout).await)
+    }
+
+    async fn select_window(&self, target: &str) -> Result<(), VibeError> {
+        dispatch!(self, m => m.select_window(target).await)
+    }
+
+    async fn select_pane(&self, pane_id: &str) -> Result<(), VibeError> {
+        dispatch!(self, m => m.select_pane(pane_id).await)
+    }
+
+    async fn send_keys(&self, target: &str, command: &str) -> Result<(), VibeError> {
+        dispatch!(self, m => m.send_keys(target, command).await)
+    }
+
+    async fn send_key_names(&self, pane_id: &str, keys: &[&str]) -> Result<(), VibeError> {
+        dispatch!(self, m => m.send_key_names(pane_id, keys).await)
+    }
+
+    async fn paste_text(&self, pane_id: &str, text: &str) -> Result<(), VibeError> {
+        dispatch!(self, m => m.paste_text(pane_id, text).await)
+    }
+
+    async fn capture_pane(&self, pane_id: &str, lines: u32) -> Result<String, VibeError> {
+        dispatch!(self, m => m.capture_pane(pane_id, lines).await)
+    }
+
+    async fn pipe_pane(&self, target: &str, command: &str) -> Result<(), VibeError> {
+        dispatch!(self, m => m.pipe_pane(target, command).await)
+    }
+
+    async fn kill_window(&self, target: &str) -> Result<(), VibeError> {
+        dispatch!(self, m => m.kill_window(target).await)
+    }
+
+    async fn kill_pane(&self, pane_id: &str) -> Result<(), VibeError> {
+        dispatch!(self, m => m.kill_pane(pane_id).await)
+    }
+
+    async fn first_pane_id(&self, window: &str) -> Result<String, VibeError> {
+        dispatch!(self, m => m.first_pane_id(window).await)
+    }
+
+    async fn first_pane_info(&self, window: &str) -> Result<(String, String), VibeError> {
+        dispatch!(self, m => m.first_pane_info(window).await)
+    }
+
+    async fn list_panes(&self, session: &str) -> Result<Vec<PaneInfo>, VibeError> {
+        dispatch!(self, m => m.list_panes(session).await)
+    }
+
+    async fn window_exists(&self, target: &str) -> bool {
+        dispatch!(self, m => m.window_exists(target).await)
+    }
+
+    async fn pane_exists(&self, pane_id: &str) -> bool {
+        dispatch!(self, m => m.pane_exists(pane_id).await)
+    }
+
+    async fn window_id_for_pane(&self, pane_id: &str) -> Result<String, VibeError> {
+        dispatch!(self, m => m.window_id_for_pane(pane_id).await)
+    }
+}
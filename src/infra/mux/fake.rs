@@ -0,0 +1,187 @@
+//! An in-memory multiplexer for tests: windows and panes are just records,
+//! and every call is logged. Only what spawning agents needs is modelled;
+//! input and output are logged and dropped. [`super::fake_for_test`] makes
+//! one the backend commands see.
+
+use super::Multiplexer;
+use crate::error::VibeError;
+use crate::infra::tmux::PaneInfo;
+use std::sync::Mutex;
+
+#[derive(Default)]
+pub struct FakeMux {
+    state: Mutex<FakeState>,
+}
+
+#[derive(Default)]
+struct FakeState {
+    next_id: u32,
+    windows: Vec<FakeWindow>,
+    calls: Vec<String>,
+}
+
+struct FakeWindow {
+    id: String,
+    session: String,
+    name: String,
+    /// Pane IDs, oldest first.
+    panes: Vec<String>,
+}
+
+impl FakeState {
+    fn next_id(&mut self, sigil: char) -> String {
+        self.next_id += 1;
+        format!("{sigil}{}", self.next_id)
+    }
+
+    /// A window by ID, `{session}:{name}`, or one of its pane IDs.
+    fn window(&mut self, target: &str) -> Option<&mut FakeWindow> {
+        self.windows.iter_mut().find(|w| {
+            w.id == target
+                || w.panes.iter().any(|p| p == target)
+                || target.split_once(':') == Some((w.session.as_str(), w.name.as_str()))
+        })
+    }
+}
+
+impl FakeMux {
+    /// Calls made so far, like `split-pane vibe:s1`.
+    pub fn calls(&self) -> Vec<String> {
+        self.state.lock().unwrap().calls.clone()
+    }
+
+    /// Names of the windows open, in creation order.
+    pub fn window_names(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        state.windows.iter().map(|w| w.name.clone()).collect()
+    }
+
+    fn log(&self, call: String) {
+        self.state.lock().unwrap().calls.push(call);
+    }
+
+    /// Log a call and hold the state for it.
+    fn record(&self, call: String) -> std::sync::MutexGuard<'_, FakeState> {
+        self.log(call);
+        self.state.lock().unwrap()
+    }
+}
+
+impl Multiplexer for FakeMux {
+    async fn ensure_session(&self, session: &str) -> Result<(), VibeError> {
+        self.log(format!("ensure-session {session}"));
+        Ok(())
+    }
+
+    async fn create_window(&self, session: &str, name: &str, _working_dir: &str) -> Result<String, VibeError> {
+        let mut state = self.record(format!("create-window {session}:{name}"));
+        let id = state.next_id('@');
+        let pane = state.next_id('%');
+        state.windows.push(FakeWindow {
+            id: id.clone(),
+            session: session.to_string(),
+            name: name.to_string(),
+            panes: vec![pane],
+        });
+        Ok(id)
+    }
+
+    async fn split_pane(&self, window: &str, _working_dir: &str, _horizontal: bool) -> Result<String, VibeError> {
+        let mut state = self.record(format!("split-pane {window}"));
+        let pane = state.next_id('%');
+        let target = state
+            .window(window)
+            .ok_or_else(|| VibeError::Pty(format!("no window {window}")))?;
+        target.panes.push(pane.clone());
+        Ok(pane)
+    }
+
+    async fn select_layout(&self, window: &str, layout: &str) -> Result<(), VibeError> {
+        self.log(format!("select-layout {window} {layout}"));
+        Ok(())
+    }
+
+    async fn select_window(&self, target: &str) -> Result<(), VibeError> {
+        self.log(format!("select-window {target}"));
+        Ok(())
+    }
+
+    async fn select_pane(&self, pane_id: &str) -> Result<(), VibeError> {
+        self.log(format!("select-pane {pane_id}"));
+        Ok(())
+    }
+
+    async fn send_keys(&self, target: &str, command: &str) -> Result<(), VibeError> {
+        self.log(format!("send-keys {target} {command}"));
+        Ok(())
+    }
+
+    async fn send_key_names(&self, pane_id: &str, keys: &[&str]) -> Result<(), VibeError> {
+        self.log(format!("send-key-names {pane_id} {}", keys.join(" ")));
+        Ok(())
+    }
+
+    async fn paste_text(&self, pane_id: &str, text: &str) -> Result<(), VibeError> {
+        self.log(format!("paste-text {pane_id} {text}"));
+        Ok(())
+    }
+
+    async fn capture_pane(&self, _pane_id: &str, _lines: u32) -> Result<String, VibeError> {
+        Ok(String::new())
+    }
+
+    async fn pipe_pane(&self, target: &str, _command: &str) -> Result<(), VibeError> {
+        self.log(format!("pipe-pane {target}"));
+        Ok(())
+    }
+
+    async fn kill_window(&self, target: &str) -> Result<(), VibeError> {
+        let mut state = self.record(format!("kill-window {target}"));
+        let id = state.window(target).map(|w| w.id.clone());
+        state.windows.retain(|w| Some(&w.id) != id.as_ref());
+        Ok(())
+    }
+
+    async fn kill_pane(&self, pane_id: &str) -> Result<(), VibeError> {
+        let mut state = self.record(format!("kill-pane {pane_id}"));
+        for window in &mut state.windows {
+            window.panes.retain(|p| p != pane_id);
+        }
+        state.windows.retain(|w| !w.panes.is_empty());
+        Ok(())
+    }
+
+    async fn first_pane_id(&self, window: &str) -> Result<String, VibeError> {
+        self.first_pane_info(window).await.map(|(id, _)| id)
+    }
+
+    async fn first_pane_info(&self, window: &str) -> Result<(String, String), VibeError> {
+        let mut state = self.state.lock().unwrap();
+        state
+            .window(window)
+            .and_then(|w| w.panes.first())
+            .map(|id| (id.clone(), "bash".to_string()))
+            .ok_or_else(|| VibeError::Pty("no pane found".into()))
+    }
+
+    async fn list_panes(&self, _session: &str) -> Result<Vec<PaneInfo>, VibeError> {
+        Ok(vec![])
+    }
+
+    async fn window_exists(&self, target: &str) -> bool {
+        self.state.lock().unwrap().window(target).is_some()
+    }
+
+    async fn pane_exists(&self, pane_id: &str) -> bool {
+        let state = self.state.lock().unwrap();
+        state.windows.iter().any(|w| w.panes.iter().any(|p| p == pane_id))
+    }
+
+    async fn window_id_for_pane(&self, pane_id: &str) -> Result<String, VibeError> {
+        let mut state = self.state.lock().unwrap();
+        state
+            .window(pane_id)
+            .map(|w| w.id.clone())
+            .ok_or_else(|| VibeError::Pty(format!("no pane {pane_id}")))
+    }
+}
@@ -0,0 +1,578 @@
+//! The built-in multiplexer: pseudo-terminals owned by the dashboard.
+//!
+//! Each pane is a shell on its own PTY. A reader thread per pane feeds its
+//! output to a `vt100` parser, which holds the screen the dashboard draws
+//! and attention detection captures. Windows only group panes; the
+//! dashboard shows one pane at a time, so layouts don't apply. Everything
+//! closes with the dashboard.
+
+use super::Multiplexer;
+use crate::error::VibeError;
+use crate::infra::tmux::PaneInfo;
+use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, MasterPty, PtySize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::debug;
+
+/// Size of new panes, as for a new tmux session. The dashboard resizes a
+/// pane to its view when it shows it.
+const DEFAULT_ROWS: u16 = 50;
+const DEFAULT_COLS: u16 = 200;
+
+/// Scrollback lines kept per pane.
+const SCROLLBACK: usize = 1000;
+
+/// Panes and windows, shared with the reader threads.
+#[derive(Clone, Default)]
+pub struct PtyMux {
+    inner: Arc<Mutex<Panes>>,
+}
+
+#[derive(Default)]
+struct Panes {
+    next_id: u32,
+    windows: Vec<Window>,
+    panes: HashMap<String, Pane>,
+    /// Pane the dashboard was asked to show, not picked up yet.
+    focus: Option<String>,
+}
+
+struct Window {
+    id: String,
+    session: String,
+    name: String,
+    /// Pane IDs, oldest first.
+    panes: Vec<String>,
+}
+
+struct Pane {
+    window: String,
+    screen: Arc<Mutex<vt100::Parser>>,
+    writer: Box<dyn Write + Send>,
+    master: Box<dyn MasterPty + Send>,
+    killer: Box<dyn ChildKiller + Send + Sync>,
+    pid: u32,
+    /// `pipe_pane` command the reader thread copies output to.
+    pipe: Arc<Mutex<Option<Child>>>,
+}
+
+impl Panes {
+    /// A new pane (`%`) or window (`@`) ID. Events and transcripts are kept
+    /// by pane ID across launches, so the counter is prefixed with the pid
+    /// to keep one run's panes from picking up the last run's files.
+    fn next_id(&mut self, sigil: char) -> String {
+        self.next_id += 1;
+        format!("{sigil}{}-{}", std::process::id(), self.next_id)
+    }
+
+    /// A window by ID, `{session}:{name}`, or one of its pane IDs.
+    fn window(&self, target: &str) -> Option<&Window> {
+        if target.starts_with('%') {
+            let pane = self.panes.get(target)?;
+            return self.windows.iter().find(|w| w.id == pane.window);
+        }
+        if target.starts_with('@') {
+            return self.windows.iter().find(|w| w.id == target);
+        }
+        let (session, name) = target.split_once(':')?;
+        self.windows.iter().find(|w| w.session == session && w.name == name)
+    }
+
+    /// A pane by ID, or the first pane of a window.
+    fn pane_id(&self, target: &str) -> Option<String> {
+        if self.panes.contains_key(target) {
+            return Some(target.to_string());
+        }
+        self.window(target)?.panes.first().cloned()
+    }
+
+    fn pane(&self, target: &str) -> Result<(String, &Pane), VibeError> {
+        let missing = || VibeError::Pty(format!("no pane {target}"));
+        let id = self.pane_id(target).ok_or_else(missing)?;
+        let pane = self.panes.get(&id).ok_or_else(missing)?;
+        Ok((id, pane))
+    }
+
+    /// Forget a pane, and its window once empty.
+    fn remove(&mut self, pane_id: &str) -> Option<Pane> {
+        let pane = self.panes.remove(pane_id)?;
+        if let Some(window) = self.windows.iter_mut().find(|w| w.id == pane.window) {
+            window.panes.retain(|p| p != pane_id);
+        }
+        self.windows.retain(|w| !w.panes.is_empty());
+        Some(pane)
+    }
+}
+
+impl Pane {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), VibeError> {
+        self.writer.write_all(bytes)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Name of the foreground process, as tmux's `pane_current_command`.
+    /// Agent exit detection compares it against the shell's name, so it has
+    /// to work without `/proc` too.
+    fn current_command(&self) -> String {
+        let pid = self
+            .master
+            .process_group_leader()
+            .map(|pid| pid as u32)
+            .unwrap_or(self.pid);
+        if let Ok(comm) = std::fs::read_to_string(format!("/proc/{pid}/comm")) {
+            return comm.trim().to_string();
+        }
+        // No /proc (macOS): ask ps, which gives the full path there, and a
+        // leading `-` for login shells
+        let output = Command::new("ps")
+            .args(["-o", "comm=", "-p", &pid.to_string()])
+            .stderr(Stdio::null())
+            .output();
+        match output {
+            Ok(output) if output.status.success() => {
+                let comm = String::from_utf8_lossy(&output.stdout);
+                let comm = comm.trim();
+                let name = comm.rsplit('/').next().unwrap_or(comm);
+                name.trim_start_matches('-').to_string()
+            }
+            _ => {
+                debug!(pid, "couldn't read the pane's foreground command");
+                String::new()
+            }
+        }
+    }
+}
+
+impl PtyMux {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start `cmd` (the user's shell, outside tests) on a new PTY in
+    /// `window`. Returns the pane ID.
+    fn open_pane(&self, window: &str, working_dir: &str, mut cmd: CommandBuilder) -> Result<String, VibeError> {
+        let pair = native_pty_system()
+            .openpty(PtySize {
+                rows: DEFAULT_ROWS,
+                cols: DEFAULT_COLS,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(pty_error)?;
+
+        let id = self.inner.lock().unwrap().next_id('%');
+        cmd.cwd(working_dir);
+        cmd.env("TERM", "xterm-256color");
+        // Hooks report the pane they ran in from this
+        cmd.env("VIBE_PANE", &id);
+        // Not from the terminal the dashboard was started in
+        cmd.env_remove("TMUX");
+        cmd.env_remove("TMUX_PANE");
+        let child = pair.slave.spawn_command(cmd).map_err(pty_error)?;
+        // The reader sees EOF once the shell exits only if nothing else
+        // holds the slave open
+        drop(pair.slave);
+
+        let reader = pair.master.try_clone_reader().map_err(pty_error)?;
+        let writer = pair.master.take_writer().map_err(pty_error)?;
+        let screen = Arc::new(Mutex::new(vt100::Parser::new(DEFAULT_ROWS, DEFAULT_COLS, SCROLLBACK)));
+        let pipe = Arc::new(Mutex::new(None));
+        let pane = Pane {
+            window: window.to_string(),
+            screen: screen.clone(),
+            writer,
+            killer: child.clone_killer(),
+            pid: child.process_id().unwrap_or(0),
+            master: pair.master,
+            pipe: pipe.clone(),
+        };
+        {
+            let mut inner = self.inner.lock().unwrap();
+            if let Some(window) = inner.windows.iter_mut().find(|w| w.id == window) {
+                window.panes.push(id.clone());
+            }
+            inner.panes.insert(id.clone(), pane);
+        }
+
+        let inner = self.inner.clone();
+        let pane_id = id.clone();
+        std::thread::Builder::new()
+            .name(format!("pty {id}"))
+            .spawn(move || {
+                read_loop(reader, &screen, &pipe);
+                let mut child = child;
+                let _ = child.wait();
+                debug!(pane = %pane_id, "pty pane closed");
+                inner.lock().unwrap().remove(&pane_id);
+            })?;
+        Ok(id)
+    }
+
+    /// Type raw bytes into a pane, as from the dashboard's pane view.
+    pub fn write_input(&self, target: &str, bytes: &[u8]) -> Result<(), VibeError> {
+        let mut inner = self.inner.lock().unwrap();
+        let id = inner
+            .pane_id(target)
+            .ok_or_else(|| VibeError::Pty(format!("no pane {target}")))?;
+        match inner.panes.get_mut(&id) {
+            Some(pane) => pane.write(bytes),
+            None => Err(VibeError::Pty(format!("no pane {target}"))),
+        }
+    }
+
+    /// A pane's terminal state, to draw it.
+    pub fn screen(&self, pane_id: &str) -> Option<Arc<Mutex<vt100::Parser>>> {
+        let inner = self.inner.lock().unwrap();
+        inner.panes.get(pane_id).map(|pane| pane.screen.clone())
+    }
+
+    /// Name of the window holding a pane.
+    pub fn window_name(&self, pane_id: &str) -> Option<String> {
+        let inner = self.inner.lock().unwrap();
+        inner.window(pane_id).map(|w| w.name.clone())
+    }
+
+    /// Fit a pane to the area it's drawn in.
+    pub fn resize(&self, pane_id: &str, rows: u16, cols: u16) {
+        let inner = self.inner.lock().unwrap();
+        let Some(pane) = inner.panes.get(pane_id) else {
+            return;
+        };
+        let mut screen = pane.screen.lock().unwrap();
+        if screen.screen().size() == (rows, cols) {
+            return;
+        }
+        screen.screen_mut().set_size(rows, cols);
+        let size = PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        };
+        if let Err(e) = pane.master.resize(size) {
+            debug!(pane = pane_id, error = %e, "failed to resize pty");
+        }
+    }
+
+    /// The pane last selected, if the dashboard hasn't shown it yet.
+    pub fn take_focus(&self) -> Option<String> {
+        self.inner.lock().unwrap().focus.take()
+    }
+
+    /// Kill every pane's process.
+    pub fn shutdown(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        for pane in inner.panes.values_mut() {
+            let _ = pane.killer.kill();
+        }
+        inner.panes.clear();
+        inner.windows.clear();
+    }
+
+    fn focus(&self, target: &str) -> Result<(), VibeError> {
+        let mut inner = self.inner.lock().unwrap();
+        let id = inner
+            .pane_id(target)
+            .ok_or_else(|| VibeError::Pty(format!("no window {target}")))?;
+        inner.focus = Some(id);
+        Ok(())
+    }
+
+    /// Add a window to `session` with `cmd` in its first pane.
+    fn new_window(
+        &self,
+        session: &str,
+        name: &str,
+        working_dir: &str,
+        cmd: CommandBuilder,
+    ) -> Result<String, VibeError> {
+        let id = {
+            let mut inner = self.inner.lock().unwrap();
+            let id = inner.next_id('@');
+            inner.windows.push(Window {
+                id: id.clone(),
+                session: session.to_string(),
+                name: name.to_string(),
+                panes: vec![],
+            });
+            id
+        };
+        if let Err(e) = self.open_pane(&id, working_dir, cmd) {
+            self.inner.lock().unwrap().windows.retain(|w| w.id != id);
+            return Err(e);
+        }
+        Ok(id)
+    }
+
+    fn kill(&self, pane_ids: &[String]) {
+        let mut inner = self.inner.lock().unwrap();
+        for id in pane_ids {
+            if let Some(mut pane) = inner.remove(id) {
+                let _ = pane.killer.kill();
+            }
+        }
+    }
+}
+
+impl Multiplexer for PtyMux {
+    async fn ensure_session(&self, _session: &str) -> Result<(), VibeError> {
+        Ok(())
+    }
+
+    async fn create_window(&self, session: &str, name: &str, working_dir: &str) -> Result<String, VibeError> {
+        self.new_window(session, name, working_dir, CommandBuilder::new_default_prog())
+    }
+
+    async fn split_pane(&self, window: &str, working_dir: &str, _horizontal: bool) -> Result<String, VibeError> {
+        let window_id = self
+            .inner
+            .lock()
+            .unwrap()
+            .window(window)
+            .map(|w| w.id.clone())
+            .ok_or_else(|| VibeError::Pty(format!("no window {window}")))?;
+        self.open_pane(&window_id, working_dir, CommandBuilder::new_default_prog())
+    }
+
+    async fn select_layout(&self, _window: &str, _layout: &str) -> Result<(), VibeError> {
+        // Panes are shown one at a time
+        Ok(())
+    }
+
+    async fn select_window(&self, target: &str) -> Result<(), VibeError> {
+        self.focus(target)
+    }
+
+    async fn select_pane(&self, pane_id: &str) -> Result<(), VibeError> {
+        self.focus(pane_id)
+    }
+
+    async fn send_keys(&self, target: &str, command: &str) -> Result<(), VibeError> {
+        self.write_input(target, format!("{command}\r").as_bytes())
+    }
+
+    async fn send_key_names(&self, pane_id: &str, keys: &[&str]) -> Result<(), VibeError> {
+        let bytes: Vec<u8> = keys.iter().flat_map(|key| key_bytes(key)).collect();
+        self.write_input(pane_id, &bytes)
+    }
+
+    async fn paste_text(&self, pane_id: &str, text: &str) -> Result<(), VibeError> {
+        let bracketed = {
+            let inner = self.inner.lock().unwrap();
+            let (_, pane) = inner.pane(pane_id)?;
+            pane.screen.lock().unwrap().screen().bracketed_paste()
+        };
+        let paste = if bracketed {
+            format!("\x1b[200~{text}\x1b[201~")
+        } else {
+            text.to_string()
+        };
+        self.write_input(pane_id, paste.as_bytes())?;
+        // Let the app finish taking the paste, or Enter lands inside it
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        self.write_input(pane_id, b"\r")
+    }
+
+    async fn capture_pane(&self, pane_id: &str, lines: u32) -> Result<String, VibeError> {
+        let inner = self.inner.lock().unwrap();
+        let (_, pane) = inner.pane(pane_id)?;
+        // The visible screen only; unlike tmux, no history above it
+        let contents = pane.screen.lock().unwrap().screen().contents();
+        let rows: Vec<&str> = contents.lines().collect();
+        let start = rows.len().saturating_sub(lines as usize);
+        Ok(rows[start..].join("\n"))
+    }
+
+    async fn pipe_pane(&self, target: &str, command: &str) -> Result<(), VibeError> {
+        let pipe = {
+            let inner = self.inner.lock().unwrap();
+            inner.pane(target)?.1.pipe.clone()
+        };
+        let mut pipe = pipe.lock().unwrap();
+        if pipe.is_some() {
+            return Ok(());
+        }
+        let child = Command::new("sh")
+            .args(["-c", command])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        *pipe = Some(child);
+        Ok(())
+    }
+
+    async fn kill_window(&self, target: &str) -> Result<(), VibeError> {
+        let panes = self
+            .inner
+            .lock()
+            .unwrap()
+            .window(target)
+            .map(|w| w.panes.clone())
+            .ok_or_else(|| VibeError::Pty(format!("no window {target}")))?;
+        self.kill(&panes);
+        Ok(())
+    }
+
+    async fn kill_pane(&self, pane_id: &str) -> Result<(), VibeError> {
+        if !self.inner.lock().unwrap().panes.contains_key(pane_id) {
+            return Err(VibeError::Pty(format!("no pane {pane_id}")));
+        }
+        self.kill(&[pane_id.to_string()]);
+        Ok(())
+    }
+
+    async fn first_pane_id(&self, window: &str) -> Result<String, VibeError> {
+        self.inner
+            .lock()
+            .unwrap()
+            .window(window)
+            .and_then(|w| w.panes.first().cloned())
+            .ok_or_else(|| VibeError::Pty(format!("no window {window}")))
+    }
+
+    async fn first_pane_info(&self, window: &str) -> Result<(String, String), VibeError> {
+        let inner = self.inner.lock().unwrap();
+        let (id, pane) = inner
+            .window(window)
+            .and_then(|w| w.panes.first())
+            .and_then(|id| Some((id.clone(), inner.panes.get(id)?)))
+            .ok_or_else(|| VibeError::Pty("no pane found".into()))?;
+        Ok((id, pane.current_command()))
+    }
+
+    async fn list_panes(&self, session: &str) -> Result<Vec<PaneInfo>, VibeError> {
+        let inner = self.inner.lock().unwrap();
+        let panes = inner
+            .windows
+            .iter()
+            .filter(|w| w.session == session)
+            .flat_map(|w| w.panes.iter().map(move |id| (w, id)))
+            .filter(|(_, id)| inner.panes.contains_key(*id))
+            .map(|(window, id)| PaneInfo {
+                pane_id: id.clone(),
+                window_name: window.name.clone(),
+            })
+            .collect();
+        Ok(panes)
+    }
+
+    async fn window_exists(&self, target: &str) -> bool {
+        self.inner.lock().unwrap().window(target).is_some()
+    }
+
+    async fn pane_exists(&self, pane_id: &str) -> bool {
+        self.inner.lock().unwrap().panes.contains_key(pane_id)
+    }
+
+    async fn window_id_for_pane(&self, pane_id: &str) -> Result<String, VibeError> {
+        self.inner
+            .lock()
+            .unwrap()
+            .window(pane_id)
+            .map(|w| w.id.clone())
+            .ok_or_else(|| VibeError::Pty(format!("no pane {pane_id}")))
+    }
+}
+
+/// Copy a pane's output to its screen, and to its pipe if it has one,
+/// until the PTY closes.
+fn read_loop(mut reader: Box<dyn Read + Send>, screen: &Mutex<vt100::Parser>, pipe: &Mutex<Option<Child>>) {
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        screen.lock().unwrap().process(&buf[..n]);
+
+        let mut pipe = pipe.lock().unwrap();
+        let written = pipe
+            .as_mut()
+            .and_then(|child| child.stdin.as_mut())
+            .map(|stdin| stdin.write_all(&buf[..n]));
+        if let Some(Err(e)) = written {
+            debug!(error = %e, "pane pipe closed");
+            pipe.take();
+        }
+    }
+
+    // Close the pipe's stdin so it finishes, then reap it
+    if let Some(mut child) = pipe.lock().unwrap().take() {
+        drop(child.stdin.take());
+        let _ = child.wait();
+    }
+}
+
+/// Bytes a terminal sends for a tmux key name. Anything that isn't a known
+/// name is typed as-is.
+fn key_bytes(key: &str) -> Vec<u8> {
+    let named: &[u8] = match key {
+        "Enter" => b"\r",
+        "Escape" => b"\x1b",
+        "Tab" => b"\t",
+        "BSpace" => b"\x7f",
+        "Space" => b" ",
+        "Up" => b"\x1b[A",
+        "Down" => b"\x1b[B",
+        "Right" => b"\x1b[C",
+        "Left" => b"\x1b[D",
+        _ => {
+            if let Some(c) = key.strip_prefix("C-").filter(|c| c.len() == 1) {
+                let c = c.as_bytes()[0].to_ascii_lowercase();
+                if c.is_ascii_lowercase() {
+                    return vec![c - b'a' + 1];
+                }
+            }
+            return key.as_bytes().to_vec();
+        }
+    };
+    named.to_vec()
+}
+
+fn pty_error(e: anyhow::Error) -> VibeError {
+    VibeError::Pty(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shell_round_trip() {
+        let mux = PtyMux::new();
+        // A plain sh, not the user's shell and its rc files
+        let mut sh = CommandBuilder::new("/bin/sh");
+        sh.env_remove("ENV");
+        let window = mux.new_window("vibe-test", "s1", "/tmp", sh).unwrap();
+        let pane = mux.first_pane_id("vibe-test:s1").await.unwrap();
+        assert_eq!(mux.window_id_for_pane(&pane).await.unwrap(), window);
+
+        // The shell expands it, so the echoed command line doesn't match
+        mux.send_keys(&window, "echo vibe-$((40 + 2))").await.unwrap();
+        let mut screen = String::new();
+        for _ in 0..50 {
+            screen = mux.capture_pane(&pane, 50).await.unwrap();
+            if screen.contains("vibe-42") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert!(screen.contains("vibe-42"), "screen:\n{screen}");
+
+        mux.kill_window("vibe-test:s1").await.unwrap();
+        assert!(!mux.window_exists(&window).await);
+        assert!(!mux.pane_exists(&pane).await);
+    }
+
+    #[test]
+    fn test_key_bytes() {
+        assert_eq!(key_bytes("Enter"), b"\r");
+        assert_eq!(key_bytes("C-c"), [3]);
+        assert_eq!(key_bytes("y"), b"y");
+    }
+}
@@ -1,13 +1,17 @@
+use crate::domain::review::WatchLedger;
+use crate::domain::session::ArchivedSession;
 use crate::domain::workspace::WorkspaceState;
 use crate::error::VibeError;
 use std::path::{Path, PathBuf};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 use tracing::{debug, info};
+use uuid::Uuid;
 
 pub struct StateManager {
     vibe_dir: PathBuf,
     state_file: PathBuf,
+    read_only: bool,
 }
 
 impl StateManager {
@@ -17,6 +21,16 @@ impl StateManager {
         Self {
             vibe_dir,
             state_file,
+            read_only: false,
+        }
+    }
+
+    /// A manager whose [`save`](Self::save) keeps changes in memory, for a
+    /// dashboard watching sessions another one runs.
+    pub fn read_only(self) -> Self {
+        Self {
+            read_only: true,
+            ..self
         }
     }
 
@@ -32,12 +46,40 @@ impl StateManager {
         self.vibe_dir.join("plans")
     }
 
+    fn sessions_dir(&self) -> PathBuf {
+        self.vibe_dir.join("sessions")
+    }
+
+    /// Transcript of a session's first pane.
+    pub fn session_transcript(&self, session_id: Uuid) -> PathBuf {
+        self.sessions_dir()
+            .join(session_id.to_string())
+            .join("transcript.log")
+    }
+
+    /// Transcript of an interactive agent's or shell's pane.
+    pub fn agent_transcript(&self, agent_id: Uuid) -> PathBuf {
+        self.agents_dir()
+            .join(agent_id.to_string())
+            .join("transcript.log")
+    }
+
+    pub fn events_dir(&self) -> PathBuf {
+        self.vibe_dir.join("events")
+    }
+
+    /// Activity log written by a session's Claude Code hooks.
+    pub fn events_file(&self, session_name: &str) -> PathBuf {
+        self.events_dir().join(format!("{session_name}.jsonl"))
+    }
+
     /// Initialize .vibe directory structure
     pub async fn init(&self) -> Result<(), VibeError> {
         info!(dir = %self.vibe_dir.display(), "initializing .vibe directory");
         fs::create_dir_all(&self.vibe_dir).await?;
         fs::create_dir_all(self.vibe_dir.join("agents")).await?;
         fs::create_dir_all(self.vibe_dir.join("plans")).await?;
+        fs::create_dir_all(self.vibe_dir.join("events")).await?;
         fs::create_dir_all(self.vibe_dir.join("templates")).await?;
         self.ensure_gitignore().await?;
         Ok(())
@@ -61,6 +103,9 @@ impl StateManager {
 
     /// Persist state to disk (atomic write via temp file + rename)
     pub async fn save(&self, state: &WorkspaceState) -> Result<(), VibeError> {
+        if self.read_only {
+            return Ok(());
+        }
         debug!(
             sessions = state.sessions.len(),
             agents = state.agents.len(),
@@ -74,6 +119,60 @@ impl StateManager {
         Ok(())
     }
 
+    fn review_watch_file(&self) -> PathBuf {
+        self.vibe_dir.join("review-watch.json")
+    }
+
+    /// Load the `vibe review watch` ledger (empty if it has never run)
+    pub async fn load_review_watch(&self) -> Result<WatchLedger, VibeError> {
+        let file = self.review_watch_file();
+        if !file.exists() {
+            return Ok(WatchLedger::default());
+        }
+        let content = fs::read_to_string(&file).await?;
+        serde_json::from_str(&content).map_err(|e| VibeError::State(e.to_string()))
+    }
+
+    /// Persist the review watch ledger (atomic write via temp file + rename)
+    pub async fn save_review_watch(&self, ledger: &WatchLedger) -> Result<(), VibeError> {
+        let json = serde_json::to_string_pretty(ledger)
+            .map_err(|e| VibeError::State(e.to_string()))?;
+        let file = self.review_watch_file();
+        let tmp = file.with_extension("json.tmp");
+        fs::write(&tmp, &json).await?;
+        fs::rename(&tmp, &file).await?;
+        Ok(())
+    }
+
+    /// Record a killed session next to its transcript
+    pub async fn save_archived_session(&self, archived: &ArchivedSession) -> Result<(), VibeError> {
+        let json = serde_json::to_string_pretty(archived)
+            .map_err(|e| VibeError::State(e.to_string()))?;
+        let dir = self.sessions_dir().join(archived.id.to_string());
+        fs::create_dir_all(&dir).await?;
+        fs::write(dir.join("session.json"), json).await?;
+        Ok(())
+    }
+
+    /// Killed sessions whose transcripts were kept, oldest first
+    pub fn archived_sessions(&self) -> Vec<ArchivedSession> {
+        let Ok(entries) = std::fs::read_dir(self.sessions_dir()) else {
+            return vec![];
+        };
+        let mut archived: Vec<ArchivedSession> = entries
+            .flatten()
+            .filter_map(|entry| std::fs::read_to_string(entry.path().join("session.json")).ok())
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+        archived.sort_by_key(|a| a.killed_at);
+        archived
+    }
+
+    /// The most recently killed session named `name`, if kept
+    pub fn find_archived_session(&self, name: &str) -> Option<ArchivedSession> {
+        self.archived_sessions().into_iter().rev().find(|a| a.name == name)
+    }
+
     /// Save agent output to .vibe/agents/{id}/
     pub async fn save_agent_output(
         &self,
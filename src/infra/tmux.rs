@@ -1,18 +1,20 @@
 use crate::error::VibeError;
 use std::path::Path;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 use tracing::{debug, warn};
 
-/// All tmux operations. Shells out to `tmux` CLI.
+pub mod control;
+
+/// All tmux operations. Shells out to `tmux` CLI, or sends targeted commands
+/// over the control-mode connection when the TUI has one open.
 pub struct TmuxController;
 
 #[derive(Debug, Clone)]
 pub struct PaneInfo {
     pub pane_id: String,
-    pub pid: u32,
-    pub command: String,
     pub window_name: String,
-    pub current_path: String,
 }
 
 impl TmuxController {
@@ -46,7 +48,7 @@ impl TmuxController {
         window_name: &str,
         working_dir: &str,
     ) -> Result<String, VibeError> {
-        run_tmux_output(&[
+        query_tmux(&[
             "new-window",
             "-t",
             session_name,
@@ -68,7 +70,7 @@ impl TmuxController {
         horizontal: bool,
     ) -> Result<String, VibeError> {
         let split_flag = if horizontal { "-h" } else { "-v" };
-        run_tmux_output(&[
+        query_tmux(&[
             "split-window",
             "-t",
             target_window,
@@ -82,40 +84,100 @@ impl TmuxController {
         .await
     }
 
+    /// Arrange a window's panes with one of tmux's preset layouts
+    /// (`main-vertical`, `tiled`, ...)
+    pub async fn select_layout(target_window: &str, layout: &str) -> Result<(), VibeError> {
+        command_tmux(&["select-layout", "-t", target_window, layout]).await
+    }
+
+    /// Move a pane out into a new window of its own, in the background.
+    /// The pane keeps its ID.
+    pub async fn break_pane(pane_id: &str, window_name: &str) -> Result<(), VibeError> {
+        run_tmux(&["break-pane", "-d", "-s", pane_id, "-n", window_name]).await
+    }
+
+    /// Move a pane into another window, beside its panes. The pane keeps its
+    /// ID; a window left empty closes.
+    pub async fn join_pane(pane_id: &str, target_window: &str) -> Result<(), VibeError> {
+        command_tmux(&["join-pane", "-d", "-h", "-s", pane_id, "-t", target_window]).await
+    }
+
     /// Send a command string to a tmux pane
     pub async fn send_keys(pane_id: &str, command: &str) -> Result<(), VibeError> {
-        run_tmux(&["send-keys", "-t", pane_id, command, "Enter"]).await
+        command_tmux(&["send-keys", "-t", pane_id, command, "Enter"]).await
+    }
+
+    /// Send key names (e.g. "y", "Enter", "Escape") to a pane as typed,
+    /// without a trailing Enter
+    pub async fn send_key_names(pane_id: &str, keys: &[&str]) -> Result<(), VibeError> {
+        let mut args = vec!["send-keys", "-t", pane_id];
+        args.extend_from_slice(keys);
+        command_tmux(&args).await
+    }
+
+    /// Deliver text to a pane as a bracketed paste and submit it with Enter.
+    /// Unlike `send_keys`, newlines and quotes arrive as typed text instead of
+    /// being interpreted as keystrokes or by the shell.
+    pub async fn paste_text(pane_id: &str, text: &str) -> Result<(), VibeError> {
+        // Buffer per call so concurrent sends don't paste each other's text
+        let buffer = format!("vibe-send-{}", uuid::Uuid::new_v4());
+        let mut child = Command::new("tmux")
+            .args(["load-buffer", "-b", &buffer, "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes()).await?;
+        }
+        let output = child.wait_with_output().await?;
+        if !output.status.success() {
+            return Err(VibeError::Tmux(
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            ));
+        }
+
+        command_tmux(&["paste-buffer", "-p", "-d", "-b", &buffer, "-t", pane_id]).await?;
+        // Let the app finish taking the paste, or Enter lands inside it
+        tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+        command_tmux(&["send-keys", "-t", pane_id, "Enter"]).await
     }
 
     /// Capture the current contents of a pane
     pub async fn capture_pane(pane_id: &str, lines: u32) -> Result<String, VibeError> {
         let start = format!("-{lines}");
-        run_tmux_output(&["capture-pane", "-t", pane_id, "-p", "-S", &start]).await
+        query_tmux(&["capture-pane", "-t", pane_id, "-p", "-S", &start]).await
+    }
+
+    /// Pipe a pane's output to a shell command (`tmux pipe-pane`). A pane
+    /// that is already piped is left alone.
+    pub async fn pipe_pane(target: &str, command: &str) -> Result<(), VibeError> {
+        // Not `pipe-pane -o`: that closes an existing pipe instead
+        let piped = query_tmux(&["display-message", "-p", "-t", target, "#{pane_pipe}"]).await?;
+        if piped.trim() == "1" {
+            return Ok(());
+        }
+        command_tmux(&["pipe-pane", "-t", target, command]).await
     }
 
     /// Kill a tmux window
     pub async fn kill_window(target: &str) -> Result<(), VibeError> {
-        run_tmux(&["kill-window", "-t", target]).await
+        command_tmux(&["kill-window", "-t", target]).await
     }
 
     /// Select a specific pane within a window
     pub async fn select_pane(pane_id: &str) -> Result<(), VibeError> {
-        run_tmux(&["select-pane", "-t", pane_id]).await
+        command_tmux(&["select-pane", "-t", pane_id]).await
     }
 
     /// Kill a tmux pane
     pub async fn kill_pane(pane_id: &str) -> Result<(), VibeError> {
-        run_tmux(&["kill-pane", "-t", pane_id]).await
-    }
-
-    /// Get the name of the currently active window
-    pub async fn current_window_name() -> Result<String, VibeError> {
-        run_tmux_output(&["display-message", "-p", "#{window_name}"]).await
+        command_tmux(&["kill-pane", "-t", pane_id]).await
     }
 
     /// Get the first pane ID for a window
     pub async fn first_pane_id(target_window: &str) -> Result<String, VibeError> {
-        run_tmux_output(&[
+        query_tmux(&[
             "list-panes",
             "-t",
             target_window,
@@ -128,49 +190,32 @@ impl TmuxController {
         })
     }
 
-    /// Kill an entire tmux session
-    pub async fn kill_session(session_name: &str) -> Result<(), VibeError> {
-        run_tmux(&["kill-session", "-t", session_name]).await
-    }
-
     /// List all panes in a session
     pub async fn list_panes(session_name: &str) -> Result<Vec<PaneInfo>, VibeError> {
-        let output = run_tmux_output(&[
+        let output = query_tmux(&[
             "list-panes",
             "-s",
             "-t",
             session_name,
             "-F",
-            "#{pane_id}\t#{pane_pid}\t#{pane_current_command}\t#{window_name}\t#{pane_current_path}",
+            "#{pane_id}\t#{window_name}",
         ])
         .await?;
 
         let panes = output
             .lines()
             .filter_map(|line| {
-                let parts: Vec<&str> = line.split('\t').collect();
-                if parts.len() >= 5 {
-                    Some(PaneInfo {
-                        pane_id: parts[0].to_string(),
-                        pid: parts[1].parse().unwrap_or(0),
-                        command: parts[2].to_string(),
-                        window_name: parts[3].to_string(),
-                        current_path: parts[4].to_string(),
-                    })
-                } else {
-                    None
-                }
+                let (pane_id, window_name) = line.split_once('\t')?;
+                Some(PaneInfo {
+                    pane_id: pane_id.to_string(),
+                    window_name: window_name.to_string(),
+                })
             })
             .collect();
 
         Ok(panes)
     }
 
-    /// Set a pane title
-    pub async fn set_pane_title(pane_id: &str, title: &str) -> Result<(), VibeError> {
-        run_tmux(&["select-pane", "-t", pane_id, "-T", title]).await
-    }
-
     /// Attach to a tmux session (replaces current terminal).
     /// Uses spawn_blocking to avoid blocking the tokio runtime — the
     /// attach-session command takes over the terminal until the user detaches.
@@ -193,7 +238,7 @@ impl TmuxController {
 
     /// Select a specific window within the session
     pub async fn select_window(target: &str) -> Result<(), VibeError> {
-        run_tmux(&["select-window", "-t", target]).await
+        command_tmux(&["select-window", "-t", target]).await
     }
 
     /// Rename the current tmux window
@@ -426,40 +471,30 @@ unbind-key o
         run_tmux(&["detach-client"]).await
     }
 
-    /// Get the current command running in a pane (first pane of target window)
-    pub async fn pane_current_command(target: &str) -> Result<String, VibeError> {
-        run_tmux_output(&["display-message", "-t", target, "-p", "#{pane_current_command}"])
-            .await
-    }
-
-    /// Get the current pane ID
-    pub async fn current_pane_id() -> Result<String, VibeError> {
-        run_tmux_output(&["display-message", "-p", "#{pane_id}"]).await
-    }
-
     /// Check if a window exists (without switching to it)
     pub async fn window_exists(target: &str) -> bool {
-        run_tmux_output(&["display-message", "-t", target, "-p", "#{window_id}"])
+        query_tmux(&["display-message", "-t", target, "-p", "#{window_id}"])
             .await
             .is_ok()
     }
 
     /// Check if a pane exists
     pub async fn pane_exists(pane_id: &str) -> bool {
-        run_tmux(&["display-message", "-t", pane_id, "-p", "#{pane_id}"])
+        // A pane that has just closed can still be targeted, with empty formats
+        query_tmux(&["display-message", "-t", pane_id, "-p", "#{pane_id}"])
             .await
-            .is_ok()
+            .is_ok_and(|id| id == pane_id)
     }
 
     /// Get the window ID containing a pane
     pub async fn window_id_for_pane(pane_id: &str) -> Result<String, VibeError> {
-        run_tmux_output(&["display-message", "-t", pane_id, "-p", "#{window_id}"]).await
+        query_tmux(&["display-message", "-t", pane_id, "-p", "#{window_id}"]).await
     }
 
     /// Get the first pane's ID and current command in a single tmux call.
     /// Returns (pane_id, current_command) or error if window doesn't exist.
     pub async fn first_pane_info(target_window: &str) -> Result<(String, String), VibeError> {
-        let output = run_tmux_output(&[
+        let output = query_tmux(&[
             "list-panes",
             "-t",
             target_window,
@@ -498,6 +533,12 @@ fn is_transient_tmux_error(stderr: &str) -> bool {
         || stderr.contains("no server running")
 }
 
+/// Check if a tmux stderr message can be ignored: the thing acted on is
+/// already gone.
+fn is_benign_tmux_error(stderr: &str) -> bool {
+    stderr.contains("no server running") || stderr.contains("session not found")
+}
+
 async fn run_tmux(args: &[&str]) -> Result<(), VibeError> {
     let mut last_err = None;
 
@@ -512,8 +553,7 @@ async fn run_tmux(args: &[&str]) -> Result<(), VibeError> {
                     return Ok(());
                 }
                 let stderr = String::from_utf8_lossy(&output.stderr);
-                // Ignore benign errors
-                if stderr.contains("no server running") || stderr.contains("session not found") {
+                if is_benign_tmux_error(&stderr) {
                     return Ok(());
                 }
                 // Retry on transient tmux errors
@@ -542,6 +582,43 @@ async fn run_tmux(args: &[&str]) -> Result<(), VibeError> {
     )))
 }
 
+/// `run_tmux_output`, sent over the control-mode connection when one is
+/// open to save spawning a process. Only for commands with an explicit
+/// target: over the connection, the "current" client is the control client.
+async fn query_tmux(args: &[&str]) -> Result<String, VibeError> {
+    let routed = match control::client() {
+        Some(client) => client.command(args).await,
+        None => None,
+    };
+    match routed {
+        Some(result) => result,
+        None => run_tmux_output(args).await,
+    }
+}
+
+/// `run_tmux`, sent over the control-mode connection when one is open, so
+/// changes reach tmux in order with the queries sent there. Same rule as
+/// `query_tmux`: only for commands with an explicit target. Commands that
+/// act on the current client or session (`switch-client`, `detach-client`,
+/// `break-pane`, an untargeted `rename-window`) would hit the control client
+/// instead, and server options and key bindings are set once at startup;
+/// those stay subprocesses.
+async fn command_tmux(args: &[&str]) -> Result<(), VibeError> {
+    let routed = match control::client() {
+        Some(client) => client.command(args).await,
+        None => None,
+    };
+    match routed {
+        Some(Ok(_)) => Ok(()),
+        Some(Err(VibeError::Tmux(stderr))) if is_benign_tmux_error(&stderr) => Ok(()),
+        Some(Err(e)) => {
+            warn!(args = ?args, error = %e, "tmux command failed");
+            Err(e)
+        }
+        None => run_tmux(args).await,
+    }
+}
+
 async fn run_tmux_output(args: &[&str]) -> Result<String, VibeError> {
     let mut last_err = None;
 
@@ -0,0 +1,401 @@
+//! A `tmux -C` control-mode connection.
+//!
+//! One long-lived client attached to the vibe session. Commands are written
+//! to its stdin one per line and tmux answers them in order, each answer
+//! wrapped in `%begin`/`%end` (or `%error`) guard lines. Everything outside
+//! a guarded block is a notification; `%output` and window closes are
+//! forwarded as [`ControlEvent`]s so the TUI can react to panes changing
+//! instead of polling them. `TmuxController` sends its targeted queries and
+//! changes here while the connection is up.
+
+use crate::error::VibeError;
+use std::collections::VecDeque;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, warn};
+
+/// How long a command may wait for its answer before it fails.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Events buffered for the TUI. Notifications past this are dropped rather
+/// than stalling command answers; a busy pane is marked again by its next
+/// output.
+const EVENT_BUFFER: usize = 1024;
+
+/// The registered connection used by `TmuxController`, if any.
+static CLIENT: Mutex<Option<Arc<ControlClient>>> = Mutex::new(None);
+
+/// Notifications from the control connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlEvent {
+    /// A pane printed something.
+    Output { pane: String },
+    /// A window was closed.
+    WindowClosed { window: String },
+}
+
+type Reply = oneshot::Sender<Result<String, String>>;
+
+pub struct ControlClient {
+    stdin: tokio::sync::Mutex<Box<dyn AsyncWrite + Send + Unpin>>,
+    /// Senders for commands written but not yet answered, oldest first.
+    pending: Arc<Mutex<VecDeque<Reply>>>,
+    connected: Arc<AtomicBool>,
+    /// How long to wait for an answer.
+    timeout: Duration,
+    _child: Option<Child>,
+}
+
+impl ControlClient {
+    /// A client writing commands to `stdin` and reading answers and
+    /// notifications from `stdout`, which arrive on the returned channel.
+    fn start(
+        stdin: impl AsyncWrite + Send + Unpin + 'static,
+        stdout: impl AsyncRead + Send + Unpin + 'static,
+        child: Option<Child>,
+        timeout: Duration,
+    ) -> (Self, mpsc::Receiver<ControlEvent>) {
+        let pending = Arc::new(Mutex::new(VecDeque::new()));
+        let connected = Arc::new(AtomicBool::new(true));
+        let (tx, rx) = mpsc::channel(EVENT_BUFFER);
+        tokio::spawn(read_loop(stdout, pending.clone(), connected.clone(), tx));
+        let client = Self {
+            stdin: tokio::sync::Mutex::new(Box::new(stdin)),
+            pending,
+            connected,
+            timeout,
+            _child: child,
+        };
+        (client, rx)
+    }
+
+    /// Run a command over the connection. Returns `None` if the command
+    /// can't be sent this way (connection gone, or an argument with a
+    /// newline), in which case the caller should run it as a subprocess
+    /// instead. Once written it is never retried: tmux may still run it, so
+    /// a missing answer is an error.
+    pub async fn command(&self, args: &[&str]) -> Option<Result<String, VibeError>> {
+        if !self.connected.load(Ordering::SeqCst) {
+            return None;
+        }
+        let line = command_line(args)?;
+
+        let (tx, rx) = oneshot::channel();
+        {
+            // Queue the reply and write under one lock so answers stay in
+            // the order commands were sent
+            let mut stdin = self.stdin.lock().await;
+            self.pending.lock().unwrap().push_back(tx);
+            if let Err(e) = stdin.write_all(line.as_bytes()).await {
+                debug!(error = %e, "tmux control connection closed");
+                self.connected.store(false, Ordering::SeqCst);
+                return None;
+            }
+        }
+
+        let result = match tokio::time::timeout(self.timeout, rx).await {
+            Ok(Ok(Ok(output))) => Ok(output.trim().to_string()),
+            Ok(Ok(Err(error))) => Err(VibeError::Tmux(error)),
+            Ok(Err(_)) => Err(VibeError::Tmux(format!(
+                "tmux control connection closed before answering {args:?}"
+            ))),
+            Err(_) => {
+                warn!(args = ?args, "tmux control command timed out");
+                Err(VibeError::Tmux(format!(
+                    "tmux did not answer {args:?} within {}s",
+                    self.timeout.as_secs()
+                )))
+            }
+        };
+        Some(result)
+    }
+}
+
+/// Attach a control client to `session` and register it for
+/// `TmuxController` to use. Returns the channel its notifications arrive on.
+pub async fn connect(session: &str) -> Result<mpsc::Receiver<ControlEvent>, VibeError> {
+    // ignore-size: the control client has no terminal and mustn't shrink
+    // the session's windows
+    let mut child = Command::new("tmux")
+        .args(["-C", "attach-session", "-t", session, "-f", "ignore-size"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?;
+    let stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| VibeError::Tmux("control client has no stdin".into()))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| VibeError::Tmux("control client has no stdout".into()))?;
+
+    let (client, rx) = ControlClient::start(stdin, stdout, Some(child), COMMAND_TIMEOUT);
+    let client = Arc::new(client);
+    // Make sure tmux is answering before anything relies on it
+    match client.command(&["display-message", "-p", "-t", session, "#{session_id}"]).await {
+        Some(Ok(_)) => {}
+        Some(Err(e)) => return Err(e),
+        None => return Err(VibeError::Tmux("tmux control mode did not respond".into())),
+    }
+    *CLIENT.lock().unwrap() = Some(client);
+    Ok(rx)
+}
+
+/// Whether a control connection is up. Without one, tmux state has to be
+/// polled.
+pub fn connected() -> bool {
+    client().is_some()
+}
+
+/// Drop the registered connection, detaching its client.
+pub fn disconnect() {
+    CLIENT.lock().unwrap().take();
+}
+
+/// The registered connection, if it is still up.
+pub(super) fn client() -> Option<Arc<ControlClient>> {
+    let client = CLIENT.lock().unwrap().clone()?;
+    client.connected.load(Ordering::SeqCst).then_some(client)
+}
+
+/// The line that runs `args` over the connection, or `None` if it can't be
+/// written as one (no command, or an argument with a newline).
+fn command_line(args: &[&str]) -> Option<String> {
+    let (name, rest) = args.split_first()?;
+    if args.iter().any(|a| a.contains('\n')) {
+        return None;
+    }
+    let mut line = name.to_string();
+    for arg in rest {
+        line.push(' ');
+        line.push_str(&quote(arg));
+    }
+    line.push('\n');
+    Some(line)
+}
+
+/// A single-quoted tmux command argument. tmux has no escapes inside single
+/// quotes, so a quote closes the string, is escaped, and reopens it.
+fn quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// A `%begin` block being read: its `time number` guard, whether it answers
+/// one of our commands, and the output so far.
+struct Block {
+    guard: String,
+    ours: bool,
+    output: String,
+}
+
+async fn read_loop(
+    stdout: impl AsyncRead + Unpin,
+    pending: Arc<Mutex<VecDeque<Reply>>>,
+    connected: Arc<AtomicBool>,
+    events: mpsc::Sender<ControlEvent>,
+) {
+    let mut reader = BufReader::new(stdout);
+    let mut raw = Vec::new();
+    let mut block: Option<Block> = None;
+    loop {
+        raw.clear();
+        match reader.read_until(b'\n', &mut raw).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        let line = String::from_utf8_lossy(&raw);
+        let line = line.trim_end_matches(['\r', '\n']);
+
+        if let Some(current) = &mut block {
+            // Output lines can look like guards; only the matching one ends
+            // the block
+            let end = line.strip_prefix("%end ").map(|rest| (rest, true));
+            let error = line.strip_prefix("%error ").map(|rest| (rest, false));
+            match end.or(error) {
+                Some((rest, success)) if guard_of(rest) == Some(current.guard.as_str()) => {
+                    let done = block.take().unwrap();
+                    if done.ours {
+                        let reply = pending.lock().unwrap().pop_front();
+                        if let Some(reply) = reply {
+                            let result = if success {
+                                Ok(done.output)
+                            } else {
+                                Err(done.output.trim().to_string())
+                            };
+                            let _ = reply.send(result);
+                        }
+                    }
+                }
+                _ => {
+                    current.output.push_str(line);
+                    current.output.push('\n');
+                }
+            }
+            continue;
+        }
+
+        let mut words = line.splitn(3, ' ');
+        match words.next() {
+            Some("%begin") => {
+                let rest = line.trim_start_matches("%begin ");
+                let Some(guard) = guard_of(rest) else {
+                    continue;
+                };
+                // Flags 1: the command came from this client. The attach
+                // itself and commands run by hooks answer with 0.
+                let ours = rest.rsplit(' ').next() == Some("1");
+                block = Some(Block {
+                    guard: guard.to_string(),
+                    ours,
+                    output: String::new(),
+                });
+            }
+            Some("%output") => {
+                if let Some(pane) = words.next() {
+                    let _ = events.try_send(ControlEvent::Output { pane: pane.to_string() });
+                }
+            }
+            // tmux reports windows of the attached session as unlinked too,
+            // once they're gone from it
+            Some("%window-close" | "%unlinked-window-close") => {
+                if let Some(window) = words.next() {
+                    let _ = events.try_send(ControlEvent::WindowClosed { window: window.to_string() });
+                }
+            }
+            Some("%exit") => break,
+            _ => {}
+        }
+    }
+
+    debug!("tmux control connection ended");
+    connected.store(false, Ordering::SeqCst);
+    // Wake anyone still waiting; their commands may or may not have run
+    pending.lock().unwrap().clear();
+}
+
+/// `time number` from the rest of a `%begin`/`%end`/`%error` line
+/// (`time number flags`).
+fn guard_of(rest: &str) -> Option<&str> {
+    let (guard, flags) = rest.rsplit_once(' ')?;
+    let valid = flags.chars().all(|c| c.is_ascii_digit())
+        && guard.split(' ').count() == 2
+        && guard.split(' ').all(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    valid.then_some(guard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_line_quoting() {
+        assert_eq!(quote("two words"), "'two words'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+        assert_eq!(
+            command_line(&["send-keys", "-t", "%1", "echo 'a b'", "Enter"]).unwrap(),
+            r"send-keys '-t' '%1' 'echo '\''a b'\''' 'Enter'".to_string() + "\n"
+        );
+        // A newline would end the command early; these go to a subprocess
+        assert_eq!(command_line(&["send-keys", "-t", "%1", "a\nb"]), None);
+        assert_eq!(command_line(&[]), None);
+    }
+
+    #[test]
+    fn test_guard_of() {
+        assert_eq!(guard_of("1700000000 42 1"), Some("1700000000 42"));
+        assert_eq!(guard_of("1700000000 42 0"), Some("1700000000 42"));
+        assert_eq!(guard_of("42 1"), None);
+        assert_eq!(guard_of("a 42 1"), None);
+        assert_eq!(guard_of("1 2 x"), None);
+    }
+
+    #[tokio::test]
+    async fn test_read_loop() {
+        let stream = [
+            // The attach's own answer isn't ours
+            "%begin 100 1 0",
+            "%end 100 1 0",
+            "%output %1 hello",
+            "%begin 100 2 1",
+            "%1",
+            // Output that looks like a guard, but not this block's
+            "%end 100 9 1",
+            "%end 100 2 1",
+            "%window-close @3",
+            "%unlinked-window-close @4",
+            "%begin 100 3 1",
+            "can't find pane: %9",
+            "%error 100 3 1",
+            "%exit",
+        ]
+        .map(|line| format!("{line}\n"))
+        .concat();
+
+        let (first_tx, first) = oneshot::channel();
+        let (second_tx, second) = oneshot::channel();
+        let pending = Arc::new(Mutex::new(VecDeque::from([first_tx, second_tx])));
+        let connected = Arc::new(AtomicBool::new(true));
+        let (tx, mut events) = mpsc::channel(EVENT_BUFFER);
+        read_loop(std::io::Cursor::new(stream.into_bytes()), pending, connected.clone(), tx).await;
+
+        assert_eq!(first.await.unwrap(), Ok("%1\n%end 100 9 1\n".to_string()));
+        assert_eq!(second.await.unwrap(), Err("can't find pane: %9".to_string()));
+        assert_eq!(events.recv().await, Some(ControlEvent::Output { pane: "%1".into() }));
+        assert_eq!(events.recv().await, Some(ControlEvent::WindowClosed { window: "@3".into() }));
+        assert_eq!(events.recv().await, Some(ControlEvent::WindowClosed { window: "@4".into() }));
+        assert_eq!(events.recv().await, None);
+        assert!(!connected.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_command_fails_instead_of_falling_back() {
+        // tmux that reads commands but never answers
+        let (_tmux_out, stdout) = tokio::io::duplex(64);
+        let (client, _events) =
+            ControlClient::start(tokio::io::sink(), stdout, None, Duration::from_millis(20));
+
+        let result = client.command(&["kill-pane", "-t", "%1"]).await;
+        let Some(Err(VibeError::Tmux(error))) = result else {
+            panic!("expected a timeout error, got {result:?}");
+        };
+        assert!(error.contains("did not answer"), "{error}");
+        // Never written, so still safe to run as a subprocess
+        assert!(client.command(&["send-keys", "-t", "%1", "a\nb"]).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_connection_lost_after_writing_fails() {
+        let (tmux_out, stdout) = tokio::io::duplex(64);
+        let (client, _events) = ControlClient::start(tokio::io::sink(), stdout, None, COMMAND_TIMEOUT);
+
+        let (result, ()) = tokio::join!(client.command(&["kill-pane", "-t", "%1"]), async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(tmux_out);
+        });
+        assert!(matches!(result, Some(Err(VibeError::Tmux(_)))), "{result:?}");
+        // Disconnected now: later commands go to a subprocess
+        assert!(client.command(&["kill-pane", "-t", "%1"]).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_loop_end_of_stream_releases_waiters() {
+        let (reply_tx, reply) = oneshot::channel();
+        let pending = Arc::new(Mutex::new(VecDeque::from([reply_tx])));
+        let connected = Arc::new(AtomicBool::new(true));
+        let (tx, _events) = mpsc::channel(EVENT_BUFFER);
+        // Cut off mid-block
+        let stream = "%begin 100 2 1\npartial\n";
+        read_loop(std::io::Cursor::new(stream.as_bytes()), pending, connected.clone(), tx).await;
+
+        assert!(reply.await.is_err());
+        assert!(!connected.load(Ordering::SeqCst));
+    }
+}
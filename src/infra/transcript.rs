@@ -0,0 +1,154 @@
+use crate::domain::transcript::{self, LineFilter, TranscriptMatch};
+use crate::domain::workspace::WorkspaceState;
+use crate::error::VibeError;
+use crate::infra::state::StateManager;
+use crate::infra::mux::{self, Multiplexer};
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// Transcripts past this size are moved to `<file>.1`, replacing the
+/// previous one.
+const MAX_TRANSCRIPT_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Start logging everything a pane prints to `file`, via the multiplexer's
+/// `pipe-pane` and `vibe record-transcript`. Panes already being logged are left alone.
+pub async fn start(target: &str, file: &Path) -> Result<(), VibeError> {
+    let exe = std::env::current_exe()?;
+    let quote = |p: &Path| format!("'{}'", p.display().to_string().replace('\'', "'\\''"));
+    let command = format!("{} record-transcript {}", quote(&exe), quote(file));
+    mux::current().pipe_pane(target, &command).await
+}
+
+/// Append pane output read from stdin to `file` as plain text, until the
+/// pane closes.
+pub fn record(file: &Path) -> Result<(), VibeError> {
+    if let Some(dir) = file.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut out = open_append(file)?;
+    let mut written = out.metadata()?.len();
+    let mut filter = LineFilter::default();
+    let mut stdin = std::io::stdin().lock();
+    let mut raw = Vec::new();
+    loop {
+        raw.clear();
+        if stdin.read_until(b'\n', &mut raw)? == 0 {
+            return Ok(());
+        }
+        let line = transcript::clean_line(&String::from_utf8_lossy(&raw));
+        if !filter.accept(&line) {
+            continue;
+        }
+        if written > MAX_TRANSCRIPT_BYTES {
+            std::fs::rename(file, rotated(file))?;
+            out = open_append(file)?;
+            written = 0;
+        }
+        writeln!(out, "{line}")?;
+        written += line.len() as u64 + 1;
+    }
+}
+
+/// A transcript's text, including its rotated part. `None` if nothing has
+/// been recorded.
+pub fn read(file: &Path) -> Option<String> {
+    let older = std::fs::read_to_string(rotated(file)).ok();
+    let current = std::fs::read_to_string(file).ok();
+    if older.is_none() && current.is_none() {
+        return None;
+    }
+    Some(older.unwrap_or_default() + &current.unwrap_or_default())
+}
+
+/// A search match in a session's or one of its agents' transcripts.
+#[derive(Debug, Clone)]
+pub struct TranscriptHit {
+    pub session: String,
+    pub agent: Option<String>,
+    /// The session was killed and only its transcripts are left.
+    pub killed: bool,
+    pub found: TranscriptMatch,
+}
+
+impl TranscriptHit {
+    /// `session` or `session/agent`, marked if the session was killed.
+    pub fn source(&self) -> String {
+        let session = if self.killed {
+            format!("{} (killed)", self.session)
+        } else {
+            self.session.clone()
+        };
+        match &self.agent {
+            Some(agent) => format!("{session}/{agent}"),
+            None => session,
+        }
+    }
+}
+
+/// Search every session's and agent's transcript for `query`, ignoring case,
+/// including those kept from killed sessions.
+pub fn search_all(
+    state_manager: &StateManager,
+    state: &WorkspaceState,
+    query: &str,
+) -> Vec<TranscriptHit> {
+    let mut hits = vec![];
+    for session in &state.sessions {
+        let agents = state.agents_for_session(session.id);
+        let transcripts = std::iter::once((None, state_manager.session_transcript(session.id)))
+            .chain(
+                agents
+                    .iter()
+                    .map(|agent| (Some(agent.name.clone()), state_manager.agent_transcript(agent.id))),
+            );
+        search_session(&mut hits, &session.name, false, transcripts, query);
+    }
+    for archived in state_manager.archived_sessions() {
+        let transcripts = std::iter::once((None, state_manager.session_transcript(archived.id)))
+            .chain(
+                archived
+                    .agents
+                    .iter()
+                    .map(|agent| (Some(agent.name.clone()), state_manager.agent_transcript(agent.id))),
+            );
+        search_session(&mut hits, &archived.name, true, transcripts, query);
+    }
+    hits
+}
+
+/// Add the matches in one session's transcripts, given as (agent, file).
+fn search_session(
+    hits: &mut Vec<TranscriptHit>,
+    session: &str,
+    killed: bool,
+    transcripts: impl Iterator<Item = (Option<String>, PathBuf)>,
+    query: &str,
+) {
+    for (agent, file) in transcripts {
+        let Some(text) = read(&file) else {
+            continue;
+        };
+        hits.extend(
+            transcript::search(&text, query)
+                .into_iter()
+                .map(|found| TranscriptHit {
+                    session: session.to_string(),
+                    agent: agent.clone(),
+                    killed,
+                    found,
+                }),
+        );
+    }
+}
+
+fn open_append(file: &Path) -> std::io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(file)
+}
+
+fn rotated(file: &Path) -> PathBuf {
+    let mut name = OsString::from(file.as_os_str());
+    name.push(".1");
+    PathBuf::from(name)
+}
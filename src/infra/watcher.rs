@@ -10,6 +10,8 @@ use uuid::Uuid;
 pub enum WatcherEvent {
     AgentCompleted { agent_id: Uuid, result: AgentResult },
     AgentOutputWritten { path: PathBuf },
+    /// A session's Claude Code hooks appended to its events log.
+    SessionActivity { session: String },
 }
 
 pub struct VibeWatcher {
@@ -17,15 +19,18 @@ pub struct VibeWatcher {
 }
 
 impl VibeWatcher {
-    /// Watch .vibe/agents/ for output.json files being created.
+    /// Watch .vibe/agents/ for output.json files being created, and
+    /// .vibe/events/ for session activity logs being appended to.
     /// Uses a bounded channel sender to prevent unbounded memory growth
     /// if events arrive faster than the TUI can drain them.
     pub fn start(
         agents_dir: PathBuf,
+        events_dir: PathBuf,
         tx: mpsc::Sender<WatcherEvent>,
     ) -> Result<Self, notify::Error> {
-        // Ensure directory exists
+        // Ensure directories exist
         let _ = std::fs::create_dir_all(&agents_dir);
+        let _ = std::fs::create_dir_all(&events_dir);
         info!(dir = %agents_dir.display(), "starting file watcher");
 
         let mut watcher = RecommendedWatcher::new(
@@ -34,7 +39,15 @@ impl VibeWatcher {
                     match event.kind {
                         EventKind::Create(_) | EventKind::Modify(_) => {
                             for path in &event.paths {
-                                if path.file_name().is_some_and(|n| n == "output.json") {
+                                if path.extension().is_some_and(|e| e == "jsonl") {
+                                    if let Some(session) =
+                                        path.file_stem().and_then(|n| n.to_str())
+                                    {
+                                        let _ = tx.try_send(WatcherEvent::SessionActivity {
+                                            session: session.to_string(),
+                                        });
+                                    }
+                                } else if path.file_name().is_some_and(|n| n == "output.json") {
                                     // Extract agent UUID from parent directory name
                                     if let Some(agent_id) = path
                                         .parent()
@@ -75,6 +88,7 @@ impl VibeWatcher {
         )?;
 
         watcher.watch(&agents_dir, RecursiveMode::Recursive)?;
+        watcher.watch(&events_dir, RecursiveMode::NonRecursive)?;
 
         Ok(Self { _watcher: watcher })
     }
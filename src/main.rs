@@ -4,10 +4,18 @@ mod config;
 mod domain;
 mod error;
 mod infra;
+#[cfg(test)]
+mod test_support;
 mod tui;
 
 use clap::Parser;
-use cli::{Cli, Commands, ListSubcommand, PlanSubcommand};
+use cli::{
+    CiSubcommand, Cli, Commands, ListSubcommand, PlanSubcommand, PrSubcommand, ReviewSubcommand,
+    SessionSubcommand,
+};
+use domain::agent::AgentMode;
+use domain::review::WatchFilter;
+use domain::session::RefOverrides;
 use error::VibeError;
 use std::path::Path;
 use tracing::info;
@@ -16,6 +24,20 @@ use tracing::info;
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
+    // Run by Claude hooks and tmux for every session; skip preflight checks
+    // and logging
+    match &cli.command {
+        Some(Commands::HookEvent { events_file }) => {
+            commands::hook_event::execute(events_file)?;
+            return Ok(());
+        }
+        Some(Commands::RecordTranscript { file }) => {
+            infra::transcript::record(file)?;
+            return Ok(());
+        }
+        _ => {}
+    }
+
     // Resolve workspace root.
     // 1. Explicit --workspace flag
     // 2. cwd is inside a git repo → use the repo root
@@ -32,7 +54,7 @@ async fn main() -> anyhow::Result<()> {
             return Some(cwd);
         }
         // Not yet initialized — check if there are git repos here (for `vibe init`)
-        if let Ok(repos) = infra::git::discover_repos(&cwd) {
+        if let Ok(repos) = infra::git::discover_repos(&cwd, &Default::default()) {
             if !repos.is_empty() {
                 return Some(cwd);
             }
@@ -44,7 +66,7 @@ async fn main() -> anyhow::Result<()> {
     let _guard = init_tracing(workspace_root.as_deref());
 
     // Preflight checks
-    preflight_checks(workspace_root.as_deref())?;
+    preflight_checks(workspace_root.as_deref(), cli.command.as_ref())?;
 
     info!(
         command = ?cli.command,
@@ -53,9 +75,14 @@ async fn main() -> anyhow::Result<()> {
     );
 
     match cli.command {
-        None | Some(Commands::Dashboard) => {
+        None => {
+            let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
+            tui::run(root, false).await?;
+        }
+
+        Some(Commands::Dashboard { no_attach }) => {
             let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
-            tui::run(root).await?;
+            tui::run(root, no_attach).await?;
         }
 
         Some(Commands::Init) => {
@@ -67,6 +94,8 @@ async fn main() -> anyhow::Result<()> {
             name,
             branch,
             base,
+            repos,
+            all_or_nothing,
             template,
             system_prompt,
             headless,
@@ -74,18 +103,27 @@ async fn main() -> anyhow::Result<()> {
         }) => {
             let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
             let cfg = config::load_config(Some(&root))?;
-            commands::new::execute(
-                &root,
-                name,
+            let branch = RefOverrides::parse(&branch).map_err(VibeError::User)?;
+            let base = RefOverrides::parse(&base).map_err(VibeError::User)?;
+            let options = commands::new::NewSessionOptions {
+                name: name.clone(),
                 branch,
                 base,
+                repos,
+                all_or_nothing,
                 template,
                 system_prompt,
                 headless,
                 prompt,
-                &cfg,
-            )
-            .await?;
+            };
+            let failed = commands::new::execute(&root, options, &cfg).await?;
+            if !failed.is_empty() {
+                eprintln!("Warning: some repo worktrees could not be created:");
+                for (repo, err) in &failed {
+                    eprintln!("  {repo}: {err}");
+                }
+                eprintln!("Run `vibe session repair {name}` to retry them.");
+            }
         }
 
         Some(Commands::Spawn {
@@ -99,21 +137,25 @@ async fn main() -> anyhow::Result<()> {
             commands::spawn::execute(&root, prompt, session, template, None, interactive, &cfg).await?;
         }
 
-        Some(Commands::Status { json }) => {
+        Some(Commands::Status {
+            json,
+            conflicts,
+            trial_merge,
+        }) => {
             let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
-            commands::status::execute(&root, json).await?;
+            commands::status::execute(&root, json, conflicts, trial_merge).await?;
         }
 
         Some(Commands::List { what }) => {
             let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
             match what {
                 ListSubcommand::Sessions => {
-                    commands::status::execute(&root, false).await?;
+                    commands::status::execute(&root, false, false, false).await?;
                 }
                 ListSubcommand::Agents { session } => {
                     // TODO: filtered agent list
                     let _ = session;
-                    commands::status::execute(&root, false).await?;
+                    commands::status::execute(&root, false, false, false).await?;
                 }
                 ListSubcommand::Plans => {
                     commands::plan::list(&root).await?;
@@ -137,10 +179,11 @@ async fn main() -> anyhow::Result<()> {
             target,
             force,
             delete_branch,
+            purge,
         }) => {
             let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
             let cfg = config::load_config(Some(&root))?;
-            commands::kill::execute(&root, target, force, delete_branch, &cfg).await?;
+            commands::kill::execute(&root, target, force, delete_branch, purge, &cfg).await?;
         }
 
         Some(Commands::Attach { session }) => {
@@ -148,10 +191,35 @@ async fn main() -> anyhow::Result<()> {
             commands::attach::execute(&root, session).await?;
         }
 
-        Some(Commands::Review { pr, interactive }) => {
+        Some(Commands::Review {
+            action: Some(ReviewSubcommand::Watch {
+                label,
+                author,
+                interval,
+                once,
+            }),
+            ..
+        }) => {
+            let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
+            let cfg = config::load_config(Some(&root))?;
+            let filter = WatchFilter { label, author };
+            let interval = std::time::Duration::from_secs(interval.max(1));
+            commands::review::watch(&root, filter, interval, once, &cfg).await?;
+        }
+
+        Some(Commands::Review {
+            action: None,
+            pr,
+            interactive,
+            post,
+            event,
+            yes,
+        }) => {
             let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
             let cfg = config::load_config(Some(&root))?;
-            commands::review::execute(&root, pr, interactive, &cfg).await?;
+            let pr = pr.ok_or_else(|| VibeError::User("Missing PR number or URL".into()))?;
+            let post = post.then(|| event.unwrap_or_default());
+            commands::review::execute(&root, pr, interactive, post, yes, &cfg).await?;
         }
 
         Some(Commands::Plan { action }) => {
@@ -177,14 +245,127 @@ async fn main() -> anyhow::Result<()> {
             commands::doctor::execute(&root).await?;
         }
 
-        Some(Commands::Cleanup { all, dry_run }) => {
+        Some(Commands::Cleanup { all, dry_run, purge }) => {
+            let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
+            let cfg = config::load_config(Some(&root))?;
+            commands::cleanup::execute(&root, all, dry_run, purge, &cfg).await?;
+        }
+
+        Some(Commands::Sync {
+            session,
+            all,
+            rebase,
+            merge,
+            abort_on_conflict,
+        }) => {
+            let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
+            let cfg = config::load_config(Some(&root))?;
+            let strategy = if merge {
+                Some(domain::sync::SyncStrategy::Merge)
+            } else if rebase {
+                Some(domain::sync::SyncStrategy::Rebase)
+            } else {
+                None
+            };
+            commands::sync::execute(&root, session, all, strategy, abort_on_conflict, &cfg).await?;
+        }
+
+        Some(Commands::Send { session, message }) => {
+            let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
+            let message = match message {
+                Some(message) => message,
+                None => std::io::read_to_string(std::io::stdin())?,
+            };
+            commands::send::execute(&root, &session, &message).await?;
+            println!("Sent to {session}");
+        }
+
+        Some(Commands::Log { session, follow }) => {
             let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
-            commands::cleanup::execute(&root, all, dry_run).await?;
+            commands::log::execute(&root, &session, follow).await?;
         }
 
-        Some(Commands::RefreshRepos) => {
+        Some(Commands::Search { query }) => {
             let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
-            commands::refresh_repos::execute(&root).await?;
+            commands::search::execute(&root, &query).await?;
+        }
+
+        // Handled before preflight checks
+        Some(Commands::HookEvent { .. } | Commands::RecordTranscript { .. }) => {}
+
+        Some(Commands::Ci {
+            action: CiSubcommand::Fix { target },
+        }) => {
+            let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
+            let cfg = config::load_config(Some(&root))?;
+            commands::ci::fix(&root, target, &cfg).await?;
+        }
+
+        Some(Commands::Pr { action }) => {
+            let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
+            match action {
+                PrSubcommand::Open {
+                    session,
+                    title,
+                    body,
+                    draft,
+                } => {
+                    commands::pr::open(&root, session, title, body, draft).await?;
+                }
+                PrSubcommand::Status { session } => {
+                    commands::pr::status(&root, session).await?;
+                }
+                PrSubcommand::Address { pr, reply, yes } => {
+                    let cfg = config::load_config(Some(&root))?;
+                    commands::pr::address(&root, pr, reply, yes, &cfg).await?;
+                }
+            }
+        }
+
+        Some(Commands::Session { action }) => {
+            let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
+            let cfg = config::load_config(Some(&root))?;
+            match action {
+                SessionSubcommand::AddRepo {
+                    session,
+                    repo,
+                    branch,
+                    base,
+                } => {
+                    commands::session::add_repo(&root, session, repo, branch, base, &cfg).await?;
+                }
+                SessionSubcommand::RemoveRepo {
+                    session,
+                    repo,
+                    force,
+                } => {
+                    commands::session::remove_repo(&root, session, repo, force, &cfg).await?;
+                }
+                SessionSubcommand::Repair { session } => {
+                    commands::session::repair(&root, session, &cfg).await?;
+                }
+            }
+        }
+
+        Some(Commands::Integrate {
+            sessions,
+            into,
+            base,
+            check,
+            fix,
+        }) => {
+            let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
+            let cfg = config::load_config(Some(&root))?;
+            commands::integrate::execute(&root, sessions, into, base, check, fix, &cfg).await?;
+        }
+
+        Some(Commands::RefreshRepos {
+            apply_to_sessions,
+            dry_run,
+        }) => {
+            let root = workspace_root.ok_or(VibeError::NotGitRepo)?;
+            let cfg = config::load_config(Some(&root))?;
+            commands::refresh_repos::execute(&root, apply_to_sessions, dry_run, &cfg).await?;
         }
 
     }
@@ -192,17 +373,70 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn preflight_checks(workspace_root: Option<&Path>) -> Result<(), VibeError> {
-    if !infra::tmux::TmuxController::is_available() {
-        return Err(VibeError::TmuxNotInstalled);
-    }
+fn preflight_checks(workspace_root: Option<&Path>, command: Option<&Commands>) -> Result<(), VibeError> {
     let cfg = config::load_config(workspace_root)?;
+    match cfg.multiplexer() {
+        infra::mux::MuxKind::Tmux => {
+            if !infra::tmux::TmuxController::is_available() {
+                return Err(VibeError::TmuxNotInstalled);
+            }
+        }
+        infra::mux::MuxKind::Pty => {
+            if command.is_some_and(|c| opens_terminals(c, workspace_root, &cfg)) {
+                return Err(VibeError::User(
+                    "With multiplexer = \"pty\" session terminals live in the dashboard. \
+                     Run this from the dashboard (`vibe`), or switch to tmux."
+                        .into(),
+                ));
+            }
+        }
+    }
     if !infra::claude::is_available(cfg.claude_command()) {
         return Err(VibeError::ClaudeNotInstalled);
     }
     Ok(())
 }
 
+/// Commands that open, watch or type into session terminals. Under the pty
+/// multiplexer those belong to the dashboard process and another `vibe`
+/// can't reach them.
+fn opens_terminals(command: &Commands, workspace_root: Option<&Path>, cfg: &config::MergedConfig) -> bool {
+    match command {
+        // Whether the agent gets a pane depends on its template's mode
+        Commands::Spawn {
+            template,
+            interactive,
+            ..
+        } => {
+            let template = template.as_deref().and_then(|name| {
+                let dirs = cfg.template_dirs(workspace_root?);
+                domain::template::AgentTemplate::load(name, &dirs).ok()
+            });
+            commands::spawn::resolve_mode(template.as_ref(), *interactive) != AgentMode::Headless
+        }
+        // Creates a session for a PR without one, and runs an implementer
+        Commands::Ci {
+            action: CiSubcommand::Fix { .. },
+        } => true,
+        _ => matches!(
+            command,
+            Commands::New { .. }
+                | Commands::Dashboard { no_attach: true }
+                | Commands::Attach { .. }
+                | Commands::Send { .. }
+                | Commands::Integrate { .. }
+                | Commands::Pr {
+                    action: PrSubcommand::Address { .. }
+                }
+                | Commands::Review {
+                    action: None,
+                    interactive: true,
+                    ..
+                }
+        ),
+    }
+}
+
 /// Initialize tracing with a file appender. Returns a guard that must be held
 /// for the lifetime of the program (dropping it flushes the writer).
 fn init_tracing(
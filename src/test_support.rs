@@ -0,0 +1,112 @@
+//! Fixtures shared by tests that need real git repos on disk.
+
+use crate::config::{GlobalConfig, MergedConfig, WorkspaceConfig};
+use crate::domain::workspace::{RepoInfo, Workspace, WorkspaceKind, WorkspaceState};
+use crate::infra::state::StateManager;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Run git in `dir` as a throwaway identity and return its trimmed stdout.
+/// Panics if git fails.
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .current_dir(dir)
+        .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?}: {}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Create a repo at `dir` on `main` with one commit. The identity is set in
+/// the repo's config so git commands run by vibe itself can commit too.
+pub fn init_repo(dir: &Path) {
+    std::fs::create_dir_all(dir).unwrap();
+    git(dir, &["init", "-q", "-b", "main"]);
+    git(dir, &["config", "user.name", "t"]);
+    git(dir, &["config", "user.email", "t@example.com"]);
+    git(dir, &["commit", "-q", "--allow-empty", "-m", "init"]);
+}
+
+/// Write `content` to `path` in `dir` and commit it on the current branch.
+pub fn commit_file(dir: &Path, path: &str, content: &str) {
+    let file = dir.join(path);
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    std::fs::write(file, content).unwrap();
+    git(dir, &["add", path]);
+    git(dir, &["commit", "-q", "-m", path]);
+}
+
+/// Built-in defaults, with worktrees created under `worktree_base_dir`.
+pub fn config(worktree_base_dir: &Path) -> MergedConfig {
+    MergedConfig {
+        global: GlobalConfig::default(),
+        workspace: WorkspaceConfig {
+            worktree_base_dir: Some(worktree_base_dir.to_path_buf()),
+            ..Default::default()
+        },
+        global_config_dir: worktree_base_dir.join("global-config"),
+    }
+}
+
+/// Save a fresh workspace state for `root`, single-repo unless `repos` are
+/// given, and return its state.
+pub async fn init_state(root: &Path, repos: Vec<RepoInfo>) -> WorkspaceState {
+    let workspace = Workspace {
+        root: root.to_path_buf(),
+        name: "ws".into(),
+        default_branch: "main".into(),
+        remote_url: None,
+        worktree_prefix: "-vibe-".into(),
+        worktree_base_dir: root.join("worktrees"),
+        kind: if repos.is_empty() {
+            WorkspaceKind::SingleRepo
+        } else {
+            WorkspaceKind::MultiRepo
+        },
+        repos,
+    };
+    let state = WorkspaceState::new(workspace, "vibe-ws".into());
+    let state_manager = StateManager::new(root);
+    state_manager.init().await.unwrap();
+    state_manager.save(&state).await.unwrap();
+    state
+}
+
+/// Script that stands in for `gh`. It answers from `.gh-stub/` in the
+/// directory it runs in: `gh pr list ...` prints `.gh-stub/pr-list`, or fails
+/// with the text of `.gh-stub/fail-pr-list` if that exists (`gh api` reads
+/// `api`/`fail-api`). Every call is appended to `.gh-stub/calls` and the
+/// last `--input -` body is kept in `.gh-stub/input`.
+const GH_STUB: &str = r#"#!/bin/sh
+dir="$(pwd)/.gh-stub"
+[ -d "$dir" ] || { echo "gh stub: no $dir" >&2; exit 1; }
+echo "$*" >> "$dir/calls"
+if [ "$1" = api ]; then key=api; else key="$1-$2"; fi
+case " $* " in *" --input - "*) cat > "$dir/input" ;; esac
+if [ -f "$dir/fail-$key" ]; then cat "$dir/fail-$key" >&2; exit 1; fi
+[ -f "$dir/$key" ] && cat "$dir/$key"
+exit 0
+"#;
+
+/// Make `gh` the stub on this thread and return the `.gh-stub` directory
+/// for `dir`, where gh has to be run for it to answer.
+pub fn stub_gh(dir: &Path) -> PathBuf {
+    // The directory is kept alongside the path so it lives as long as the tests
+    static GH: OnceLock<(tempfile::TempDir, PathBuf)> = OnceLock::new();
+    let (_, gh) = GH.get_or_init(|| {
+        use std::os::unix::fs::PermissionsExt;
+        let bin = tempfile::tempdir().unwrap();
+        let gh = bin.path().join("gh");
+        std::fs::write(&gh, GH_STUB).unwrap();
+        std::fs::set_permissions(&gh, std::fs::Permissions::from_mode(0o755)).unwrap();
+        (bin, gh)
+    });
+    crate::infra::gh::stub_for_test(gh);
+    let stub = dir.join(".gh-stub");
+    std::fs::create_dir_all(&stub).unwrap();
+    stub
+}
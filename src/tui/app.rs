@@ -1,8 +1,15 @@
 use crate::config::MergedConfig;
+use crate::domain::diff::{DiffScope, FileDiff};
+use crate::domain::keymap::{Action, Keymap, View};
+use crate::domain::layout::PaneLayout;
+use crate::domain::overlap::SessionOverlap;
+use crate::domain::permission::PermissionPrompt;
+use crate::domain::review::WatchLedger;
 use crate::domain::workspace::WorkspaceState;
 use crate::infra::state::StateManager;
+use crate::infra::transcript::TranscriptHit;
 use ratatui::style::Color;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
 use std::time::Instant;
 use uuid::Uuid;
@@ -40,6 +47,54 @@ pub struct App {
     pub attention: HashMap<String, AttentionInfo>,
     /// Round-robin index for incremental attention checking.
     pub attention_next_session: usize,
+    /// Session whose first pane each pane ID is, as last seen by an
+    /// attention check. Maps control-mode output back to sessions.
+    pub session_panes: HashMap<String, String>,
+    /// Panes that printed output (tmux control mode) since the last tick
+    pub dirty_panes: HashSet<String>,
+    /// Layout last picked with the cycle key, per session name. Sessions not
+    /// in here use the workspace `layout`.
+    pub session_layouts: HashMap<String, PaneLayout>,
+    /// Changed files for the session shown in the diff view
+    pub diff_files: Vec<FileDiff>,
+    /// Which base the diff view compares against
+    pub diff_scope: DiffScope,
+    /// Selected file in the diff view
+    pub diff_selected_file: usize,
+    /// Selected hunk within the selected file
+    pub diff_selected_hunk: usize,
+    /// Sessions changing the same files, from the last background overlap check
+    pub overlaps: Vec<SessionOverlap>,
+    /// Name entered for a new multi-repo session while its repos are picked
+    pub pending_session_name: String,
+    /// Repo multi-select for a new multi-repo session: (repo name, checked)
+    pub repo_choices: Vec<(String, bool)>,
+    /// Highlighted row in the repo multi-select
+    pub selected_repo_choice: usize,
+    /// Reviews done by `vibe review watch`, reloaded with the state
+    pub review_watch: WatchLedger,
+    /// Watched reviews finished before this have already been notified
+    pub review_watch_seen: chrono::DateTime<chrono::Utc>,
+    /// Permission prompts shown in the answer/approve-all popup: (session, prompt)
+    pub permission_prompts: Vec<(String, PermissionPrompt)>,
+    /// Panes already piped to their transcript this run
+    pub transcribed_panes: HashSet<String>,
+    /// Query and matches shown in the transcript search popup
+    pub search_query: String,
+    pub search_results: Vec<TranscriptHit>,
+    pub selected_search_result: usize,
+    /// Watching another dashboard's sessions (`--no-attach`): keys that
+    /// change sessions or switch tmux windows are refused
+    pub read_only: bool,
+    /// Action each key triggers, defaults plus `[keys]`
+    pub keymap: Keymap,
+    /// Help overlay listing the keys of the current view is open
+    pub show_help: bool,
+    /// Everything the command palette offers, gathered when it opens
+    pub palette_items: Vec<PaletteItem>,
+    /// Indices into `palette_items` matching the query, best first
+    pub palette_matches: Vec<usize>,
+    pub selected_palette_match: usize,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -53,6 +108,9 @@ pub enum ViewMode {
     Dashboard,
     AgentOutput,
     SessionOverview,
+    Diff,
+    /// A pane of the built-in multiplexer, full-screen and live.
+    Pane { pane_id: String },
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -60,9 +118,23 @@ pub enum InputMode {
     Normal,
     NewSession,
     SpawnAgent,
+    /// Message for the selected session's Claude
+    QuickReply,
     SelectTemplate,
     ConfirmKillSession,
     ConfirmKillAgent,
+    ConfirmDiscardHunk,
+    SelectRepos,
+    /// Approve or deny the prompt in `permission_prompts`
+    AnswerPermission,
+    /// Text to search transcripts for
+    Search,
+    /// Pick a match in `search_results` to jump to its session
+    SearchResults,
+    /// Approve every prompt in `permission_prompts`
+    ConfirmApproveAll,
+    /// Fuzzy-find an entry of `palette_items` to jump to or run
+    Palette,
 }
 
 /// Actions queued by key handlers for processing outside the event drain loop.
@@ -91,7 +163,34 @@ pub enum DeferredAction {
     },
     CreateSession {
         name: String,
+        /// Subset of workspace repos to check out (`None` = all)
+        repos: Option<Vec<String>>,
     },
+    OpenDiff,
+    StageHunk,
+    DiscardHunk,
+    SendMessage {
+        session_name: String,
+        message: String,
+    },
+    SearchTranscripts {
+        query: String,
+    },
+    /// Re-lay out a session's window with the next preset layout
+    CycleLayout { session_name: String },
+    /// Move an interactive agent's pane into its own window, or back
+    PopAgentPane {
+        session_name: String,
+        agent_name: String,
+        pane_id: String,
+    },
+    /// Answer permission prompts as shown to the user: (session, prompt)
+    AnswerPermissions {
+        prompts: Vec<(String, PermissionPrompt)>,
+        approve: bool,
+    },
+    /// Rebase or merge a session onto its base branch
+    SyncSession { session_name: String },
 }
 
 pub struct AgentEntry {
@@ -100,6 +199,37 @@ pub struct AgentEntry {
     pub source: AgentSource,
 }
 
+/// A row of the command palette.
+pub struct PaletteItem {
+    /// What it is ("session", "agent", ...), shown before the label
+    pub kind: &'static str,
+    pub label: String,
+    /// Dimmed text after the label
+    pub detail: String,
+    pub target: PaletteTarget,
+}
+
+impl PaletteItem {
+    /// Text the query is matched against.
+    pub fn haystack(&self) -> String {
+        format!("{} {}", self.kind, self.label)
+    }
+}
+
+/// What choosing a palette row does.
+pub enum PaletteTarget {
+    /// Select the session and open it
+    Session(String),
+    /// Select an agent of a session and open it
+    Agent { session_name: String, agent_id: Uuid },
+    /// Spawn from a template picker entry in the selected session
+    Spawn(AgentEntry),
+    /// Copy a plan's file to the clipboard
+    Plan { title: String, path: PathBuf },
+    /// Do what the action's key does
+    Action(Action),
+}
+
 pub enum AgentSource {
     /// Open a bare shell pane in the session's tmux window
     Shell,
@@ -137,9 +267,11 @@ pub enum AttentionReason {
     /// Claude process exited — pane is back at a shell prompt.
     ProcessExited,
     /// Permission/confirmation prompt detected (e.g., [Y/n]).
-    PermissionPrompt,
+    PermissionPrompt(PermissionPrompt),
     /// Claude idle at its input prompt for longer than the threshold.
     IdleAtPrompt,
+    /// `vibe sync` left a rebase/merge with conflicts in the worktree.
+    SyncConflict,
 }
 
 /// Transient in-memory attention state for a single session.
@@ -203,6 +335,40 @@ impl App {
             deferred_actions: VecDeque::new(),
             attention: HashMap::new(),
             attention_next_session: 0,
+            session_panes: HashMap::new(),
+            dirty_panes: HashSet::new(),
+            session_layouts: HashMap::new(),
+            diff_files: vec![],
+            diff_scope: DiffScope::Base,
+            diff_selected_file: 0,
+            diff_selected_hunk: 0,
+            overlaps: vec![],
+            pending_session_name: String::new(),
+            repo_choices: vec![],
+            selected_repo_choice: 0,
+            review_watch: WatchLedger::default(),
+            review_watch_seen: chrono::Utc::now(),
+            permission_prompts: vec![],
+            transcribed_panes: HashSet::new(),
+            search_query: String::new(),
+            search_results: vec![],
+            selected_search_result: 0,
+            read_only: false,
+            keymap: Keymap::default(),
+            show_help: false,
+            palette_items: vec![],
+            palette_matches: vec![],
+            selected_palette_match: 0,
+        }
+    }
+
+    /// Which view's keys apply.
+    pub fn key_view(&self) -> View {
+        match self.view_mode {
+            ViewMode::SessionOverview => View::Overview,
+            ViewMode::Diff => View::Diff,
+            ViewMode::AgentOutput => View::Output,
+            ViewMode::Dashboard | ViewMode::Pane { .. } => View::Dashboard,
         }
     }
 
@@ -230,6 +396,18 @@ impl App {
         visible
     }
 
+    /// Select a visible session by name, with the focus on the session
+    /// list. False if it isn't shown.
+    pub fn select_session(&mut self, name: &str) -> bool {
+        let Some(index) = self.visible_sessions().iter().position(|s| s.name == name) else {
+            return false;
+        };
+        self.selected_session = index;
+        self.selected_agent = 0;
+        self.focus = Focus::SessionList;
+        true
+    }
+
     /// Get the currently selected session
     pub fn selected_session(&self) -> Option<&crate::domain::session::Session> {
         self.visible_sessions().get(self.selected_session).copied()
@@ -266,6 +444,19 @@ impl App {
             .unwrap_or(Color::Gray)
     }
 
+    /// Get the file currently selected in the diff view
+    pub fn selected_diff_file(&self) -> Option<&FileDiff> {
+        self.diff_files.get(self.diff_selected_file)
+    }
+
+    /// Overlaps involving the named session.
+    pub fn session_overlaps(&self, session_name: &str) -> Vec<&SessionOverlap> {
+        self.overlaps
+            .iter()
+            .filter(|o| o.involves(session_name))
+            .collect()
+    }
+
     /// Returns true if the named session has an active attention condition.
     pub fn session_needs_attention(&self, session_name: &str) -> bool {
         self.attention.get(session_name).is_some_and(|a| a.active)
@@ -276,6 +467,22 @@ impl App {
         self.attention.remove(session_name);
     }
 
+    /// The permission prompt a session is waiting on, if detected.
+    pub fn permission_prompt(&self, session_name: &str) -> Option<&PermissionPrompt> {
+        match &self.attention.get(session_name)?.reason {
+            AttentionReason::PermissionPrompt(prompt) => Some(prompt),
+            _ => None,
+        }
+    }
+
+    /// Every detected permission prompt, in session list order.
+    pub fn pending_permission_prompts(&self) -> Vec<(String, PermissionPrompt)> {
+        self.visible_sessions()
+            .into_iter()
+            .filter_map(|s| Some((s.name.clone(), self.permission_prompt(&s.name)?.clone())))
+            .collect()
+    }
+
     /// Count of sessions currently needing attention.
     pub fn attention_count(&self) -> usize {
         self.attention.values().filter(|a| a.active).count()
@@ -285,10 +492,37 @@ impl App {
         if let Ok(state) = self.state_manager.load().await {
             self.state = state;
         }
+        if let Ok(ledger) = self.state_manager.load_review_watch().await {
+            self.review_watch = ledger;
+            self.notify_watched_reviews();
+        }
         self.last_refresh = Instant::now();
         self.clamp_selection_indices();
     }
 
+    /// Notify about reviews `vibe review watch` finished since the last refresh.
+    fn notify_watched_reviews(&mut self) {
+        let fresh: Vec<(String, NotifyLevel)> = self
+            .review_watch
+            .reviewed_since(self.review_watch_seen)
+            .into_iter()
+            .map(|(pr_number, review)| {
+                let level = if review.error.is_some() {
+                    NotifyLevel::Error
+                } else {
+                    NotifyLevel::Success
+                };
+                (review.summary(pr_number), level)
+            })
+            .collect();
+        if let Some(latest) = self.review_watch.prs.values().map(|r| r.reviewed_at).max() {
+            self.review_watch_seen = self.review_watch_seen.max(latest);
+        }
+        for (message, level) in fresh {
+            self.push_notification(message, level);
+        }
+    }
+
     /// Ensure selection indices are within valid bounds after state changes.
     /// Prevents out-of-bounds access when sessions/agents are added or removed
     /// externally (e.g., by another vibe instance or CLI command).
@@ -313,7 +547,7 @@ impl App {
     /// Full reconciliation of all agents — used at startup before the event loop.
     pub async fn reconcile_tmux_state_full(&mut self) {
         use crate::domain::agent::AgentStatus;
-        use crate::infra::tmux::TmuxController;
+        use crate::infra::mux::{self, Multiplexer};
 
         let mut needs_save = false;
 
@@ -322,7 +556,7 @@ impl App {
                 continue;
             }
             let pane_id = agent.tmux_pane.as_ref().unwrap();
-            if !TmuxController::pane_exists(pane_id).await {
+            if !mux::current().pane_exists(pane_id).await {
                 tracing::info!(
                     agent = %agent.name,
                     pane = %pane_id,
@@ -342,12 +576,47 @@ impl App {
         }
     }
 
+    /// Pipe session windows and agent panes to their transcripts, including
+    /// ones vibe didn't open this run. One tmux call; each pane is piped once.
+    pub async fn ensure_transcripts(&mut self) {
+        use crate::infra::mux::{self, Multiplexer};
+        use crate::infra::transcript;
+
+        let Ok(panes) = mux::current().list_panes(&self.state.tmux_session_name).await else {
+            return;
+        };
+        let mut seen_windows = HashSet::new();
+        for pane in panes {
+            let first_in_window = seen_windows.insert(pane.window_name.clone());
+            if self.transcribed_panes.contains(&pane.pane_id) {
+                continue;
+            }
+            let agent = self
+                .state
+                .agents
+                .iter()
+                .find(|a| a.tmux_pane.as_deref() == Some(pane.pane_id.as_str()));
+            let file = match agent {
+                Some(agent) => self.state_manager.agent_transcript(agent.id),
+                None if first_in_window => match self.state.find_session_by_name(&pane.window_name) {
+                    Some(session) => self.state_manager.session_transcript(session.id),
+                    None => continue,
+                },
+                None => continue,
+            };
+            if let Err(e) = transcript::start(&pane.pane_id, &file).await {
+                tracing::warn!(pane = %pane.pane_id, error = %e, "failed to start transcript");
+            }
+            self.transcribed_panes.insert(pane.pane_id);
+        }
+    }
+
     /// Incrementally reconcile ONE agent with tmux reality per call (round-robin).
     /// Distributes pane_exists() checks across ticks instead of blocking the
     /// event loop to check all agents at once.
     pub async fn reconcile_tmux_state(&mut self) {
         use crate::domain::agent::AgentStatus;
-        use crate::infra::tmux::TmuxController;
+        use crate::infra::mux::{self, Multiplexer};
 
         let agent_count = self.state.agents.len();
         if agent_count == 0 {
@@ -370,7 +639,7 @@ impl App {
 
             let pane_id = agent.tmux_pane.as_ref().unwrap().clone();
 
-            if !TmuxController::pane_exists(&pane_id).await {
+            if !mux::current().pane_exists(&pane_id).await {
                 tracing::info!(
                     agent = %self.state.agents[idx].name,
                     pane = %pane_id,
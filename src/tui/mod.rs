@@ -3,7 +3,7 @@ pub mod widgets;
 
 use app::{
     AgentEntry, AgentSource, App, AttentionInfo, AttentionReason, DeferredAction, Focus, InputMode,
-    NotifyLevel, ViewMode,
+    NotifyLevel, PaletteItem, PaletteTarget, ViewMode,
 };
 use crossterm::event::{self, Event, KeyCode, KeyModifiers};
 use crossterm::execute;
@@ -16,46 +16,84 @@ use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph};
 use ratatui::Terminal;
+use std::collections::HashSet;
 use std::io;
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
 use crate::commands;
 use crate::config;
+use crate::domain::activity::{Activity, ActivityEvent};
 use crate::domain::agent::{AgentMode, AgentStatus};
+use crate::domain::keymap::{Action, Key, KeyChord, Keymap, View};
+use crate::domain::layout::PaneLayout;
+use crate::domain::overlap::SessionOverlap;
+use crate::domain::palette;
+use crate::domain::permission::PermissionPrompt;
+use crate::domain::plan::Plan;
+use crate::domain::sync::{SyncOutcome, SyncStrategy};
 use crate::domain::template::AgentTemplate;
+use crate::infra::mux::{self, Multiplexer, MuxKind, PtyMux};
 use crate::infra::state::StateManager;
+use crate::infra::tmux::control::{self, ControlEvent};
 use crate::infra::tmux::TmuxController;
 use crate::infra::watcher::{VibeWatcher, WatcherEvent};
 use tokio::sync::mpsc;
 
+/// Longest wait between frames while a pane of the built-in multiplexer is
+/// shown.
+const PANE_FRAME: Duration = Duration::from_millis(30);
+
 /// Result from the background nav-binding health checker.
 enum NavBindingStatus {
     Restored,
     Failed(String),
 }
 
-pub async fn run(workspace_root: PathBuf) -> anyhow::Result<()> {
+/// Run the dashboard. With `no_attach` it renders in this terminal and only
+/// watches the sessions of a dashboard running in the vibe tmux session.
+pub async fn run(workspace_root: PathBuf, no_attach: bool) -> anyhow::Result<()> {
     let state_manager = StateManager::new(&workspace_root);
     let state = state_manager.load().await?;
     let cfg = config::load_config(Some(&workspace_root))?;
+    // Checked before tmux so the problems show where vibe was started
+    let keymap = Keymap::new(&cfg.global.keys).map_err(|problems| {
+        crate::error::VibeError::Config(format!("[keys]: {}", problems.join("; ")))
+    })?;
 
     // Ensure vibe runs inside its tmux session so Enter/Escape window
     // switching works. If we're not already inside, bootstrap into it.
+    // With the built-in multiplexer the dashboard holds the terminals itself
+    // and runs wherever it was started.
     let tmux_session = state.tmux_session_name.clone();
-    let inside_vibe_tmux = if std::env::var("TMUX").is_ok() {
-        TmuxController::current_session_name()
-            .await
-            .map(|name| name == tmux_session)
-            .unwrap_or(false)
+    let pty = cfg.multiplexer() == MuxKind::Pty;
+    if no_attach {
+        // Watching from another terminal: talk to the tmux server, but
+        // leave sessions, bindings and state to the attached dashboard
+        if !TmuxController::session_exists(&tmux_session).await? {
+            return Err(crate::error::VibeError::User(format!(
+                "No vibe tmux session '{tmux_session}' — start the dashboard with `vibe` first"
+            ))
+            .into());
+        }
+    } else if pty {
+        mux::install(mux::Mux::Pty(PtyMux::new()));
     } else {
-        false
-    };
-    if !inside_vibe_tmux {
-        return bootstrap_into_tmux(&workspace_root, &tmux_session).await;
+        let inside_vibe_tmux = if std::env::var("TMUX").is_ok() {
+            TmuxController::current_session_name()
+                .await
+                .map(|name| name == tmux_session)
+                .unwrap_or(false)
+        } else {
+            false
+        };
+        if !inside_vibe_tmux {
+            return bootstrap_into_tmux(&workspace_root, &tmux_session).await;
+        }
     }
 
-    // === Running inside the vibe tmux session ===
+    // === Running inside the vibe tmux session (or on its own PTYs, or
+    // watching from outside) ===
 
     // Setup terminal
     enable_raw_mode()?;
@@ -64,58 +102,61 @@ pub async fn run(workspace_root: PathBuf) -> anyhow::Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
+    let state_manager = if no_attach {
+        state_manager.read_only()
+    } else {
+        state_manager
+    };
     let mut app = App::new(workspace_root.clone(), state, cfg, state_manager);
+    app.keymap = keymap;
+    app.read_only = no_attach;
 
-    // Name this window "dashboard" and bind nav keys to switch back here.
-    if let Err(e) = TmuxController::rename_window("dashboard").await {
-        tracing::warn!(error = %e, "failed to rename dashboard window");
-    }
-    if let Err(e) = TmuxController::disable_auto_rename_for(&format!("{}:dashboard", tmux_session)).await {
-        tracing::warn!(error = %e, "failed to disable auto-rename");
-    }
-    if let Err(e) = TmuxController::set_escape_time(app.config.global.escape_time_ms).await {
-        tracing::error!(error = %e, "failed to set escape-time");
-    }
-    if let Err(e) = TmuxController::enable_extended_keys().await {
-        tracing::warn!(error = %e, "failed to enable extended keys");
-    }
-    if let Err(e) = TmuxController::setup_nav_bindings(
-        &tmux_session,
-        &app.config.global.dashboard_key,
-        &app.config.global.overview_key,
-        Some(&workspace_root),
-    ).await {
-        tracing::error!(error = %e, "nav binding setup failed");
-        app.push_notification(
-            "Hotkey setup failed — restart vibe to retry".into(),
-            NotifyLevel::Error,
-        );
-    }
-    if let Err(e) = TmuxController::hide_status_bar(&tmux_session).await {
-        tracing::warn!(error = %e, "failed to hide status bar");
-    }
-    if let Err(e) = TmuxController::configure_scrollback(&tmux_session).await {
-        tracing::warn!(error = %e, "failed to configure scrollback");
+    if !pty && !no_attach {
+        setup_tmux_dashboard(&mut app, &tmux_session, &workspace_root).await;
     }
 
     // Ensure the permanent "main" session exists (workspace root, no worktree)
-    ensure_main_session(&mut app).await;
-
-    // Start all active session windows in background so they're ready
-    start_background_sessions(&mut app).await;
+    if !no_attach {
+        ensure_main_session(&mut app).await;
+    }
 
-    // Reconcile state with tmux reality (validate pane IDs) — full scan at startup
+    // Reconcile state with tmux reality (validate pane IDs) — full scan at
+    // startup, before new panes can take the IDs of ones that are gone
     app.reconcile_tmux_state_full().await;
 
+    // Start all active session windows in background so they're ready
+    if !no_attach {
+        start_background_sessions(&mut app).await;
+    }
+
     // Start file watcher for agent completion (bounded to prevent OOM)
     let (watcher_tx, mut watcher_rx) = mpsc::channel(100);
-    let agents_dir = workspace_root.join(".vibe").join("agents");
-    let _watcher = VibeWatcher::start(agents_dir, watcher_tx).ok();
+    let _watcher = VibeWatcher::start(
+        app.state_manager.agents_dir(),
+        app.state_manager.events_dir(),
+        watcher_tx,
+    )
+    .ok();
+
+    // tmux control mode: queries go over one connection instead of a process
+    // each, and pane output is pushed instead of polled. Falls back to
+    // polling when it can't attach.
+    let mut control_rx = if pty {
+        None
+    } else {
+        match control::connect(&tmux_session).await {
+            Ok(rx) => Some(rx),
+            Err(e) => {
+                tracing::warn!(error = %e, "tmux control mode unavailable, polling instead");
+                None
+            }
+        }
+    };
 
     // Background nav-binding health checker: runs every 3 seconds off the main
     // event loop so verification + re-establishment never blocks key input.
     let (nav_tx, mut nav_rx) = mpsc::unbounded_channel::<NavBindingStatus>();
-    {
+    if !pty && !no_attach {
         let dashboard_key = app.config.global.dashboard_key.clone();
         let overview_key = app.config.global.overview_key.clone();
         let nav_tmux_session = tmux_session.clone();
@@ -162,6 +203,33 @@ pub async fn run(workspace_root: PathBuf) -> anyhow::Result<()> {
         let _ = shutdown_flag;
     }
 
+    // Background overlap checker: compares changed files across sessions every
+    // `overlap_check_secs`. git2 work runs on the blocking pool.
+    let (overlap_tx, mut overlap_rx) = mpsc::unbounded_channel::<Vec<SessionOverlap>>();
+    if app.config.global.overlap_check_secs > 0 {
+        let period = Duration::from_secs(app.config.global.overlap_check_secs);
+        let trial_merge = app.config.global.overlap_trial_merge;
+        let overlap_workspace_root = workspace_root.clone();
+        tokio::spawn(async move {
+            let state_manager = StateManager::new(&overlap_workspace_root);
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                let Ok(state) = state_manager.load().await else {
+                    continue;
+                };
+                let overlaps = tokio::task::spawn_blocking(move || {
+                    commands::status::detect_overlaps(&state, trial_merge)
+                })
+                .await
+                .unwrap_or_default();
+                if overlap_tx.send(overlaps).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
     // Signal handler: best-effort cleanup on SIGTERM/SIGHUP so bindings don't
     // persist after an unclean shutdown. SIGKILL can't be caught — stale PID
     // locks are detected by verify_nav_bindings and re-claimed automatically.
@@ -186,8 +254,14 @@ pub async fn run(workspace_root: PathBuf) -> anyhow::Result<()> {
                 }
             }
 
-            let _ = TmuxController::cleanup_nav_bindings(Some(&signal_workspace_root)).await;
-            let _ = TmuxController::show_status_bar(&tmux_session).await;
+            match mux::current().pty() {
+                Some(pty) => pty.shutdown(),
+                None if no_attach => {}
+                None => {
+                    let _ = TmuxController::cleanup_nav_bindings(Some(&signal_workspace_root)).await;
+                    let _ = TmuxController::show_status_bar(&tmux_session).await;
+                }
+            }
 
             // Restore terminal state immediately — the main loop may not get
             // a chance to run its cleanup if the signal arrives during poll().
@@ -212,7 +286,11 @@ pub async fn run(workspace_root: PathBuf) -> anyhow::Result<()> {
         // Poll and drain all buffered events (not just one per tick).
         // This prevents key events from queuing behind resize/mouse events
         // and reduces perceived input latency for rapid keypresses.
-        let timeout = tick_rate.saturating_sub(last_tick.elapsed());
+        let mut timeout = tick_rate.saturating_sub(last_tick.elapsed());
+        if matches!(app.view_mode, ViewMode::Pane { .. }) {
+            // Redraw a live terminal often enough for typing to feel direct
+            timeout = timeout.min(PANE_FRAME);
+        }
         if event::poll(timeout)? {
             let mut should_quit = false;
             loop {
@@ -241,10 +319,18 @@ pub async fn run(workspace_root: PathBuf) -> anyhow::Result<()> {
             }
         }
 
+        if let Some(pty) = mux::current().pty() {
+            sync_pane_view(&mut app, pty, terminal.size()?);
+        }
+
         // Check for watcher events (non-blocking, capped to avoid monopolizing loop)
         for _ in 0..5 {
             match watcher_rx.try_recv() {
                 Ok(event) => match event {
+                    // The attached dashboard records and announces it
+                    WatcherEvent::AgentCompleted { .. } if app.read_only => {
+                        app.refresh_state().await;
+                    }
                     WatcherEvent::AgentCompleted { agent_id, result } => {
                         // Update agent in state
                         if let Some(agent) = app.state.find_agent_by_id_mut(agent_id) {
@@ -286,18 +372,47 @@ pub async fn run(workspace_root: PathBuf) -> anyhow::Result<()> {
                     WatcherEvent::AgentOutputWritten { .. } => {
                         app.refresh_state().await;
                     }
+                    WatcherEvent::SessionActivity { session } => {
+                        if let Some(session) = app.state.find_session_by_name(&session).cloned() {
+                            check_attention_for(&mut app, &session).await;
+                        }
+                    }
                 },
                 Err(_) => break,
             }
         }
 
+        // Pane output and closed windows from tmux control mode
+        let mut window_closed = false;
+        if let Some(rx) = control_rx.as_mut() {
+            while let Ok(event) = rx.try_recv() {
+                match event {
+                    ControlEvent::Output { pane } => {
+                        app.dirty_panes.insert(pane);
+                    }
+                    ControlEvent::WindowClosed { .. } => window_closed = true,
+                }
+            }
+        }
+        if window_closed {
+            handle_window_closed(&mut app).await;
+        }
+
         // Tick
         if last_tick.elapsed() >= tick_rate {
             last_tick = Instant::now();
+            let pushed = control::connected();
+            let dirty = std::mem::take(&mut app.dirty_panes);
 
             // Periodic state refresh
             if app.last_refresh.elapsed() >= refresh_interval {
                 app.refresh_state().await;
+                if !app.read_only {
+                    app.ensure_transcripts().await;
+                }
+                if pushed {
+                    check_all_sessions_attention(&mut app).await;
+                }
             }
 
             // Process one deferred action per tick (keeps event loop responsive)
@@ -310,9 +425,14 @@ pub async fn run(workspace_root: PathBuf) -> anyhow::Result<()> {
             // pane_exists() calls.
             app.reconcile_tmux_state().await;
 
-            // Incremental attention detection — checks one session per tick
+            // Attention detection: sessions whose pane printed since the last
+            // tick, or without control mode one session per tick
             // (round-robin). Detects Claude exit, permission prompts, idle state.
-            check_session_attention(&mut app).await;
+            if pushed {
+                check_dirty_sessions_attention(&mut app, &dirty).await;
+            } else {
+                check_session_attention(&mut app).await;
+            }
 
             // Check nav-binding health from background task (non-blocking)
             while let Ok(status) = nav_rx.try_recv() {
@@ -329,12 +449,33 @@ pub async fn run(workspace_root: PathBuf) -> anyhow::Result<()> {
                 }
             }
 
-            // Periodic overview capture refresh — one tile per tick (round-robin).
-            // 250ms per tile keeps content visibly fresh without blocking.
-            if app.view_mode == ViewMode::SessionOverview
-                && app.overview_last_capture.elapsed() >= Duration::from_millis(250)
-            {
-                refresh_overview_capture_incremental(&mut app).await;
+            // Latest overlap check from background task — notify on new pairs only
+            while let Ok(overlaps) = overlap_rx.try_recv() {
+                for overlap in &overlaps {
+                    let known = app
+                        .overlaps
+                        .iter()
+                        .any(|o| o.sessions == overlap.sessions && o.repo == overlap.repo);
+                    if !known {
+                        let [a, b] = &overlap.sessions;
+                        app.push_notification(
+                            format!("{a} and {b} both touch {}", overlap.files_summary()),
+                            NotifyLevel::Info,
+                        );
+                    }
+                }
+                app.overlaps = overlaps;
+            }
+
+            // Overview capture refresh — tiles whose pane printed, or without
+            // control mode one tile per tick (round-robin). 250ms per tile
+            // keeps content visibly fresh without blocking.
+            if app.view_mode == ViewMode::SessionOverview {
+                if pushed {
+                    refresh_dirty_overview_tiles(&mut app, &dirty).await;
+                } else if app.overview_last_capture.elapsed() >= Duration::from_millis(250) {
+                    refresh_overview_capture_incremental(&mut app).await;
+                }
             }
 
             // Expire old notifications
@@ -348,9 +489,18 @@ pub async fn run(workspace_root: PathBuf) -> anyhow::Result<()> {
         }
     }
 
-    // Clean up tmux bindings and status bar (only if we own the PID lock)
-    let _ = TmuxController::show_status_bar(&tmux_session).await;
-    let _ = TmuxController::cleanup_nav_bindings(Some(&workspace_root)).await;
+    control::disconnect();
+
+    // Clean up tmux bindings and status bar (only if we own the PID lock).
+    // The built-in multiplexer's terminals go with the dashboard.
+    match mux::current().pty() {
+        Some(pty) => pty.shutdown(),
+        None if no_attach => {}
+        None => {
+            let _ = TmuxController::show_status_bar(&tmux_session).await;
+            let _ = TmuxController::cleanup_nav_bindings(Some(&workspace_root)).await;
+        }
+    }
 
     // Restore terminal
     disable_raw_mode()?;
@@ -359,11 +509,49 @@ pub async fn run(workspace_root: PathBuf) -> anyhow::Result<()> {
 
     // Detach from the tmux session — session windows (and their Claude Code
     // processes) survive. Next `vibe` launch will reconnect to them.
-    let _ = TmuxController::detach_client().await;
+    if !pty && !no_attach {
+        let _ = TmuxController::detach_client().await;
+    }
 
     Ok(())
 }
 
+/// Name the dashboard's window, bind the nav keys that switch back to it and
+/// hide tmux's status bar.
+async fn setup_tmux_dashboard(app: &mut App, tmux_session: &str, workspace_root: &std::path::Path) {
+    // Name this window "dashboard" and bind nav keys to switch back here.
+    if let Err(e) = TmuxController::rename_window("dashboard").await {
+        tracing::warn!(error = %e, "failed to rename dashboard window");
+    }
+    if let Err(e) = TmuxController::disable_auto_rename_for(&format!("{}:dashboard", tmux_session)).await {
+        tracing::warn!(error = %e, "failed to disable auto-rename");
+    }
+    if let Err(e) = TmuxController::set_escape_time(app.config.global.escape_time_ms).await {
+        tracing::error!(error = %e, "failed to set escape-time");
+    }
+    if let Err(e) = TmuxController::enable_extended_keys().await {
+        tracing::warn!(error = %e, "failed to enable extended keys");
+    }
+    if let Err(e) = TmuxController::setup_nav_bindings(
+        tmux_session,
+        &app.config.global.dashboard_key,
+        &app.config.global.overview_key,
+        Some(workspace_root),
+    ).await {
+        tracing::error!(error = %e, "nav binding setup failed");
+        app.push_notification(
+            "Hotkey setup failed — restart vibe to retry".into(),
+            NotifyLevel::Error,
+        );
+    }
+    if let Err(e) = TmuxController::hide_status_bar(tmux_session).await {
+        tracing::warn!(error = %e, "failed to hide status bar");
+    }
+    if let Err(e) = TmuxController::configure_scrollback(tmux_session).await {
+        tracing::warn!(error = %e, "failed to configure scrollback");
+    }
+}
+
 /// If vibe is not already running inside its tmux session, bootstrap into it:
 /// always create a fresh "dashboard" window, launch the current binary, and attach.
 /// Any previously running vibe process in the dashboard is killed — session
@@ -524,15 +712,14 @@ async fn start_background_sessions(app: &mut App) {
         let working_dir = session.worktree_path.to_str().unwrap_or(".");
 
         // Only create window if it doesn't exist
-        if TmuxController::window_exists(&session_target).await {
+        if mux::current().window_exists(&session_target).await {
             continue;
         }
 
         // Create the window
-        match TmuxController::create_window(&tmux_session, &session.name, working_dir).await {
+        match mux::current().create_window(&tmux_session, &session.name, working_dir).await {
             Ok(window_id) => {
-                // Lock the window name so tmux doesn't auto-rename it
-                let _ = TmuxController::disable_auto_rename_for(&session_target).await;
+                crate::commands::new::install_activity_hooks(&app.state_manager, &session);
 
                 // Build and send claude command
                 let resolved_system_prompt = resolve_session_system_prompt(&session, app);
@@ -547,7 +734,7 @@ async fn start_background_sessions(app: &mut App) {
                     &app.config.global.claude_extra_args,
                 );
 
-                if let Err(e) = TmuxController::send_keys(&session_target, &cmd).await {
+                if let Err(e) = mux::current().send_keys(&session_target, &cmd).await {
                     tracing::warn!(
                         session = %session.name,
                         error = %e,
@@ -579,7 +766,7 @@ async fn start_background_sessions(app: &mut App) {
     // Ensure dashboard is the active window after background setup
     // (create_window may have switched the active window to a session)
     let dashboard_target = format!("{}:dashboard", tmux_session);
-    let _ = TmuxController::select_window(&dashboard_target).await;
+    let _ = mux::current().select_window(&dashboard_target).await;
 }
 
 /// Resolve the system prompt for a session (shared helper)
@@ -602,6 +789,11 @@ fn resolve_session_system_prompt(
 fn draw(f: &mut ratatui::Frame, app: &App) {
     let size = f.area();
 
+    if let ViewMode::Pane { pane_id } = &app.view_mode {
+        draw_pane(f, app, pane_id);
+        return;
+    }
+
     // Main layout: banner + body + status bar
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -626,6 +818,11 @@ fn draw(f: &mut ratatui::Frame, app: &App) {
         ViewMode::AgentOutput => {
             widgets::output_viewer::render_fullscreen(f, app, main_chunks[1]);
         }
+        ViewMode::Diff => {
+            widgets::diff_viewer::render(f, app, main_chunks[1]);
+        }
+        // Drawn full-screen above
+        ViewMode::Pane { .. } => {}
     }
 
     // Status bar
@@ -633,6 +830,24 @@ fn draw(f: &mut ratatui::Frame, app: &App) {
 
     // Popup overlay (rendered last, on top of everything)
     render_popup_overlay(f, app, size);
+    if app.show_help {
+        render_help(f, app, size);
+    }
+}
+
+/// A pane of the built-in multiplexer over the whole screen, with the status
+/// bar below it.
+fn draw_pane(f: &mut ratatui::Frame, app: &App, pane_id: &str) {
+    let size = f.area();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(size);
+    if let Some(pty) = mux::current().pty() {
+        widgets::pane_view::render(f, pty, pane_id, chunks[0]);
+    }
+    widgets::status_bar::render(f, app, chunks[1]);
+    render_popup_overlay(f, app, size);
 }
 
 fn draw_dashboard(f: &mut ratatui::Frame, app: &App, area: Rect) {
@@ -744,12 +959,24 @@ fn render_banner(f: &mut ratatui::Frame, area: Rect) {
 fn render_popup_overlay(f: &mut ratatui::Frame, app: &App, area: Rect) {
     match &app.input_mode {
         InputMode::Normal => {}
-        InputMode::NewSession | InputMode::SpawnAgent => {
+        InputMode::NewSession | InputMode::SpawnAgent | InputMode::QuickReply | InputMode::Search => {
             render_input_popup(f, app, area);
         }
+        InputMode::SearchResults => {
+            render_search_results(f, app, area);
+        }
+        InputMode::Palette => {
+            render_palette(f, app, area);
+        }
         InputMode::SelectTemplate => {
             render_template_picker(f, app, area);
         }
+        InputMode::SelectRepos => {
+            render_repo_picker(f, app, area);
+        }
+        InputMode::AnswerPermission | InputMode::ConfirmApproveAll => {
+            render_permission_popup(f, app, area);
+        }
         InputMode::ConfirmKillSession => {
             let session_name = app
                 .selected_session()
@@ -784,6 +1011,23 @@ fn render_popup_overlay(f: &mut ratatui::Frame, app: &App, area: Rect) {
             ]);
             render_popup(f, " Confirm ", content, area);
         }
+        InputMode::ConfirmDiscardHunk => {
+            let file_name = app
+                .selected_diff_file()
+                .map(|d| d.display_path())
+                .unwrap_or_else(|| "?".into());
+            let content = Line::from(vec![
+                Span::styled(
+                    format!("Discard hunk in '{file_name}'?  "),
+                    Style::default().fg(Color::White),
+                ),
+                key_span("[Enter]"),
+                Span::raw(" confirm  "),
+                key_span("[Esc]"),
+                Span::raw(" cancel"),
+            ]);
+            render_popup(f, " Confirm ", content, area);
+        }
     }
 }
 
@@ -840,19 +1084,16 @@ fn key_span(text: &str) -> Span<'_> {
     )
 }
 
-fn render_template_picker(f: &mut ratatui::Frame, app: &App, area: Rect) {
-    let entries = &app.agent_entries;
-
+fn render_repo_picker(f: &mut ratatui::Frame, app: &App, area: Rect) {
     let popup_width = 50u16.min(area.width.saturating_sub(4));
-    // borders(2) + entries + separator(1) + custom(1) + footer(1)
-    let inner_lines = entries.len() + 3;
-    let popup_height = ((inner_lines + 2) as u16).min(area.height.saturating_sub(2));
+    // borders(2) + repos + footer(1)
+    let popup_height = ((app.repo_choices.len() + 3) as u16).min(area.height.saturating_sub(2));
     let popup_area = centered_rect(popup_width, popup_height, area);
 
     f.render_widget(Clear, popup_area);
     let block = Block::default()
         .title(Span::styled(
-            " Spawn Agent ",
+            format!(" Repos for '{}' ", app.pending_session_name),
             Style::default()
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::BOLD),
@@ -861,13 +1102,11 @@ fn render_template_picker(f: &mut ratatui::Frame, app: &App, area: Rect) {
         .border_type(BorderType::Rounded)
         .border_style(Style::default().fg(Color::Cyan));
 
-    let inner_width = (popup_width as usize).saturating_sub(4);
     let mut lines = Vec::new();
-
-    for (i, entry) in entries.iter().enumerate() {
-        let selected = i == app.selected_template;
+    for (i, (name, checked)) in app.repo_choices.iter().enumerate() {
+        let selected = i == app.selected_repo_choice;
         let marker = if selected { "\u{25b8} " } else { "  " };
-        let name_padded = format!("{:<14}", entry.name);
+        let checkbox = if *checked { "[x] " } else { "[ ] " };
         let style = if selected {
             Style::default()
                 .fg(app.current_session_color())
@@ -875,54 +1114,21 @@ fn render_template_picker(f: &mut ratatui::Frame, app: &App, area: Rect) {
         } else {
             Style::default().fg(Color::White)
         };
-        let desc_style = if selected {
-            Style::default().fg(app.current_session_color())
-        } else {
-            Style::default().fg(Color::Gray)
-        };
         lines.push(Line::from(vec![
             Span::styled(marker, style),
-            Span::styled(name_padded, style),
-            Span::styled(entry.description.clone(), desc_style),
+            Span::styled(checkbox, style),
+            Span::styled(name.clone(), style),
         ]));
     }
 
-    // Separator
-    lines.push(Line::from(Span::styled(
-        "\u{2500}".repeat(inner_width),
-        Style::default().fg(Color::DarkGray),
-    )));
-
-    // Custom prompt option
-    let custom_selected = app.selected_template == entries.len();
-    let marker = if custom_selected { "\u{25b8} " } else { "  " };
-    let style = if custom_selected {
-        Style::default()
-            .fg(app.current_session_color())
-            .add_modifier(Modifier::BOLD)
-    } else {
-        Style::default().fg(Color::White)
-    };
-    lines.push(Line::from(Span::styled(
-        format!("{marker}Custom prompt..."),
-        style,
-    )));
-
-    // Footer
     lines.push(Line::from(vec![
-        Span::styled(
-            "  \u{23ce} ",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw("select  "),
-        Span::styled(
-            "Esc ",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ),
+        key_span("  Space "),
+        Span::raw("toggle  "),
+        key_span("a "),
+        Span::raw("all  "),
+        key_span("\u{23ce} "),
+        Span::raw("create  "),
+        key_span("Esc "),
         Span::raw("cancel"),
     ]));
 
@@ -930,49 +1136,444 @@ fn render_template_picker(f: &mut ratatui::Frame, app: &App, area: Rect) {
     f.render_widget(paragraph, popup_area);
 }
 
-// ─── Key handling ────────────────────────────────────────────────────────────
+fn render_permission_popup(f: &mut ratatui::Frame, app: &App, area: Rect) {
+    let approve_all = app.input_mode == InputMode::ConfirmApproveAll;
+    let mut lines = Vec::new();
+    if approve_all {
+        for (session_name, prompt) in &app.permission_prompts {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{session_name}: "),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(prompt.summary().to_string(), Style::default().fg(Color::White)),
+            ]));
+        }
+    } else if let Some((_, prompt)) = app.permission_prompts.first() {
+        for line in prompt.text.lines() {
+            lines.push(Line::from(Span::styled(
+                line.to_string(),
+                Style::default().fg(Color::White),
+            )));
+        }
+    }
 
-/// Handle a key event. Returns true if the app should quit.
-async fn handle_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> anyhow::Result<bool> {
-    match &app.input_mode {
-        InputMode::Normal => handle_normal_key(app, code, modifiers).await,
-        InputMode::NewSession => handle_input_key(app, code).await,
-        InputMode::SpawnAgent => handle_input_key(app, code).await,
-        InputMode::SelectTemplate => handle_select_template_key(app, code).await,
-        InputMode::ConfirmKillSession => handle_confirm_kill_session(app, code).await,
-        InputMode::ConfirmKillAgent => handle_confirm_kill_agent(app, code).await,
+    lines.push(Line::from(""));
+    if approve_all {
+        lines.push(Line::from(vec![
+            key_span("  \u{23ce} "),
+            Span::raw(format!("approve all {}  ", app.permission_prompts.len())),
+            key_span("Esc "),
+            Span::raw("cancel"),
+        ]));
+    } else {
+        lines.push(Line::from(vec![
+            key_span("  y "),
+            Span::raw("approve  "),
+            key_span("n "),
+            Span::raw("deny  "),
+            key_span("Esc "),
+            Span::raw("cancel"),
+        ]));
     }
+
+    let title = match (approve_all, app.permission_prompts.first()) {
+        (true, _) => " Approve all pending prompts? ".to_string(),
+        (false, Some((session_name, _))) => format!(" Permission: {session_name} "),
+        (false, None) => " Permission ".to_string(),
+    };
+    let popup_width = 70u16.min(area.width.saturating_sub(4));
+    // borders(2) + lines
+    let popup_height = ((lines.len() + 2) as u16).min(area.height.saturating_sub(2));
+    let popup_area = centered_rect(popup_width, popup_height, area);
+
+    f.render_widget(Clear, popup_area);
+    let block = Block::default()
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::Cyan));
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(ratatui::widgets::Wrap { trim: false });
+    f.render_widget(paragraph, popup_area);
 }
 
-async fn handle_normal_key(
-    app: &mut App,
-    code: KeyCode,
-    _modifiers: KeyModifiers,
-) -> anyhow::Result<bool> {
-    // Full-screen agent output mode — restricted keys
-    if app.view_mode == ViewMode::AgentOutput {
-        return handle_agent_output_key(app, code).await;
+/// The keys in effect for the current view, from the keymap.
+fn render_help(f: &mut ratatui::Frame, app: &App, area: Rect) {
+    let view = app.key_view();
+    let mut rows = app.keymap.help(view);
+    if view == View::Dashboard {
+        rows.push((app.config.global.overview_key_display(), "Session overview"));
     }
+    let key_width = rows.iter().map(|(keys, _)| keys.chars().count()).max().unwrap_or(0);
+    let mut lines: Vec<Line> = rows
+        .into_iter()
+        .map(|(keys, description)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("  {keys:<key_width$}  "),
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(description, Style::default().fg(Color::White)),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  Any key closes. Rebind keys with [keys] in the global config.",
+        Style::default().fg(Color::DarkGray),
+    )));
 
-    // Session overview mode — restricted keys
-    if app.view_mode == ViewMode::SessionOverview {
-        return handle_overview_key(app, code).await;
-    }
+    let popup_width = 70u16.min(area.width.saturating_sub(4));
+    // borders(2) + lines
+    let popup_height = ((lines.len() + 2) as u16).min(area.height.saturating_sub(2));
+    let popup_area = centered_rect(popup_width, popup_height, area);
 
-    match code {
-        // Quit
-        KeyCode::Char('q') => return Ok(true),
+    f.render_widget(Clear, popup_area);
+    let block = Block::default()
+        .title(Span::styled(
+            " Keys ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::Cyan));
+    f.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
+
+fn render_search_results(f: &mut ratatui::Frame, app: &App, area: Rect) {
+    let popup_width = 100u16.min(area.width.saturating_sub(4));
+    let popup_height = 20u16.min(area.height.saturating_sub(2));
+    // borders(2) + blank(1) + footer(1)
+    let visible = (popup_height as usize).saturating_sub(4).max(1);
+    // Keep the selection in view
+    let first = app.selected_search_result.saturating_sub(visible - 1);
+    let inner_width = (popup_width as usize).saturating_sub(2);
+
+    let mut lines = Vec::new();
+    for (i, hit) in app.search_results.iter().enumerate().skip(first).take(visible) {
+        let selected = i == app.selected_search_result;
+        let marker = if selected { "\u{25b8} " } else { "  " };
+        let source = format!("{}:{} ", hit.source(), hit.found.line_number);
+        let text: String = hit
+            .found
+            .line
+            .chars()
+            .take(inner_width.saturating_sub(source.chars().count() + 2))
+            .collect();
+        let text_style = if selected {
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(marker, Style::default().fg(Color::Cyan)),
+            Span::styled(source, Style::default().fg(Color::Yellow)),
+            Span::styled(text, text_style),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        key_span("  \u{23ce} "),
+        Span::raw("go to session  "),
+        key_span("Esc "),
+        Span::raw("close"),
+    ]));
+
+    let popup_area = centered_rect(popup_width, popup_height, area);
+    f.render_widget(Clear, popup_area);
+    let block = Block::default()
+        .title(Span::styled(
+            format!(
+                " \"{}\": {} match(es) ",
+                app.search_query,
+                app.search_results.len()
+            ),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::Cyan));
+    f.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
+
+fn render_palette(f: &mut ratatui::Frame, app: &App, area: Rect) {
+    let popup_width = 90u16.min(area.width.saturating_sub(4));
+    let popup_height = 22u16.min(area.height.saturating_sub(2));
+    // borders(2) + query(1) + blank(1) + blank(1) + footer(1)
+    let visible = (popup_height as usize).saturating_sub(6).max(1);
+    // Keep the selection in view
+    let first = app.selected_palette_match.saturating_sub(visible - 1);
+    let inner_width = (popup_width as usize).saturating_sub(2);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::styled(&app.input_buffer, Style::default().fg(Color::White)),
+            Span::styled("\u{2588}", Style::default().fg(Color::Cyan)),
+        ]),
+        Line::from(""),
+    ];
+    for (i, &index) in app.palette_matches.iter().enumerate().skip(first).take(visible) {
+        let item = &app.palette_items[index];
+        let selected = i == app.selected_palette_match;
+        let marker = if selected { "\u{25b8} " } else { "  " };
+        let label_style = if selected {
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        let label_width = inner_width.saturating_sub(12);
+        let label: String = item.label.chars().take(label_width).collect();
+        let detail: String = item
+            .detail
+            .chars()
+            .take(label_width.saturating_sub(label.chars().count() + 2))
+            .collect();
+        lines.push(Line::from(vec![
+            Span::styled(marker, Style::default().fg(Color::Cyan)),
+            Span::styled(format!("{:<8}  ", item.kind), Style::default().fg(Color::Yellow)),
+            Span::styled(label, label_style),
+            Span::styled(format!("  {detail}"), Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+    if app.palette_matches.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  Nothing matches",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    // Footer at the bottom, however few matches there are
+    while lines.len() < visible + 3 {
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(vec![
+        key_span("  \u{23ce} "),
+        Span::raw("go  "),
+        key_span("\u{2191}\u{2193} "),
+        Span::raw("move  "),
+        key_span("Esc "),
+        Span::raw("close"),
+    ]));
+
+    let popup_area = centered_rect(popup_width, popup_height, area);
+    f.render_widget(Clear, popup_area);
+    let block = Block::default()
+        .title(Span::styled(
+            format!(" Palette: {} of {} ", app.palette_matches.len(), app.palette_items.len()),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::Cyan));
+    f.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
 
-        // Esc: back navigation
-        KeyCode::Esc => match app.focus {
+fn render_template_picker(f: &mut ratatui::Frame, app: &App, area: Rect) {
+    let entries = &app.agent_entries;
+
+    let popup_width = 50u16.min(area.width.saturating_sub(4));
+    // borders(2) + entries + separator(1) + custom(1) + footer(1)
+    let inner_lines = entries.len() + 3;
+    let popup_height = ((inner_lines + 2) as u16).min(area.height.saturating_sub(2));
+    let popup_area = centered_rect(popup_width, popup_height, area);
+
+    f.render_widget(Clear, popup_area);
+    let block = Block::default()
+        .title(Span::styled(
+            " Spawn Agent ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner_width = (popup_width as usize).saturating_sub(4);
+    let mut lines = Vec::new();
+
+    for (i, entry) in entries.iter().enumerate() {
+        let selected = i == app.selected_template;
+        let marker = if selected { "\u{25b8} " } else { "  " };
+        let name_padded = format!("{:<14}", entry.name);
+        let style = if selected {
+            Style::default()
+                .fg(app.current_session_color())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let desc_style = if selected {
+            Style::default().fg(app.current_session_color())
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(marker, style),
+            Span::styled(name_padded, style),
+            Span::styled(entry.description.clone(), desc_style),
+        ]));
+    }
+
+    // Separator
+    lines.push(Line::from(Span::styled(
+        "\u{2500}".repeat(inner_width),
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    // Custom prompt option
+    let custom_selected = app.selected_template == entries.len();
+    let marker = if custom_selected { "\u{25b8} " } else { "  " };
+    let style = if custom_selected {
+        Style::default()
+            .fg(app.current_session_color())
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::White)
+    };
+    lines.push(Line::from(Span::styled(
+        format!("{marker}Custom prompt..."),
+        style,
+    )));
+
+    // Footer
+    lines.push(Line::from(vec![
+        Span::styled(
+            "  \u{23ce} ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw("select  "),
+        Span::styled(
+            "Esc ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw("cancel"),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(block);
+    f.render_widget(paragraph, popup_area);
+}
+
+// ─── Key handling ────────────────────────────────────────────────────────────
+
+/// Handle a key event. Returns true if the app should quit.
+async fn handle_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> anyhow::Result<bool> {
+    match &app.input_mode {
+        InputMode::Normal => handle_normal_key(app, code, modifiers).await,
+        InputMode::NewSession => handle_input_key(app, code).await,
+        InputMode::SpawnAgent => handle_input_key(app, code).await,
+        InputMode::QuickReply => handle_input_key(app, code).await,
+        InputMode::Search => handle_input_key(app, code).await,
+        InputMode::SearchResults => handle_search_results_key(app, code).await,
+        InputMode::SelectTemplate => handle_select_template_key(app, code).await,
+        InputMode::ConfirmKillSession => handle_confirm_kill_session(app, code).await,
+        InputMode::ConfirmKillAgent => handle_confirm_kill_agent(app, code).await,
+        InputMode::ConfirmDiscardHunk => handle_confirm_discard_hunk(app, code).await,
+        InputMode::SelectRepos => handle_select_repos_key(app, code).await,
+        InputMode::AnswerPermission => handle_answer_permission_key(app, code).await,
+        InputMode::ConfirmApproveAll => handle_confirm_approve_all_key(app, code).await,
+        InputMode::Palette => handle_palette_key(app, code, modifiers).await,
+    }
+}
+
+async fn handle_normal_key(
+    app: &mut App,
+    code: KeyCode,
+    modifiers: KeyModifiers,
+) -> anyhow::Result<bool> {
+    // Built-in multiplexer pane — keys go to its terminal
+    if let ViewMode::Pane { pane_id } = &app.view_mode {
+        let pane_id = pane_id.clone();
+        handle_pane_key(app, &pane_id, code, modifiers).await?;
+        return Ok(false);
+    }
+
+    // The help overlay closes on the next key
+    if app.show_help {
+        app.show_help = false;
+        return Ok(false);
+    }
+
+    let action = key_chord(code, modifiers).and_then(|chord| app.keymap.action(app.key_view(), chord));
+    if action == Some(Action::Help) {
+        app.show_help = true;
+        return Ok(false);
+    }
+
+    // Full-screen agent output mode — restricted keys
+    if app.view_mode == ViewMode::AgentOutput {
+        return handle_agent_output_key(app, action).await;
+    }
+
+    // Session overview mode — restricted keys
+    if app.view_mode == ViewMode::SessionOverview {
+        return handle_overview_key(app, action).await;
+    }
+
+    // Diff view — restricted keys
+    if app.view_mode == ViewMode::Diff {
+        return handle_diff_key(app, action).await;
+    }
+
+    // Session overview — § is the internal trigger character, sent by tmux
+    // when the overview user-key (\e[33~) is pressed. Without tmux the
+    // key itself arrives.
+    if code == KeyCode::Char('§') || app.config.global.matches_overview_key(&code) {
+        if app.visible_session_count() > 0 {
+            app.deferred_actions.push_back(DeferredAction::EnterOverview);
+        }
+        return Ok(false);
+    }
+
+    let Some(action) = action else {
+        return Ok(false);
+    };
+    if read_only_refuses(app, changes_sessions(app, action)) {
+        return Ok(false);
+    }
+
+    run_dashboard_action(app, action).await
+}
+
+/// Do what a dashboard action's key does, to the selected session or agent.
+/// Returns true if the app should quit.
+async fn run_dashboard_action(app: &mut App, action: Action) -> anyhow::Result<bool> {
+    match action {
+        Action::Quit => return Ok(true),
+
+        // Back navigation
+        Action::Back => match app.focus {
             Focus::AgentList => {
                 app.focus = Focus::SessionList;
             }
             Focus::SessionList => {}
         },
 
-        // Navigation: j/k/arrows
-        KeyCode::Char('j') | KeyCode::Down => match app.focus {
+        // Navigation
+        Action::Down => match app.focus {
             Focus::SessionList => {
                 let count = app.visible_session_count();
                 if count > 0 {
@@ -989,7 +1590,7 @@ async fn handle_normal_key(
                 }
             }
         },
-        KeyCode::Char('k') | KeyCode::Up => match app.focus {
+        Action::Up => match app.focus {
             Focus::SessionList => {
                 let count = app.visible_session_count();
                 if count > 0 {
@@ -1010,21 +1611,15 @@ async fn handle_normal_key(
         },
 
         // Focus cycling
-        KeyCode::Tab => {
-            app.focus = match app.focus {
-                Focus::SessionList => Focus::AgentList,
-                Focus::AgentList => Focus::SessionList,
-            };
-        }
-        KeyCode::BackTab => {
+        Action::SwitchFocus => {
             app.focus = match app.focus {
                 Focus::SessionList => Focus::AgentList,
                 Focus::AgentList => Focus::SessionList,
             };
         }
 
-        // Enter: primary action
-        KeyCode::Enter => match app.focus {
+        // Primary action
+        Action::Open => match app.focus {
             Focus::SessionList => {
                 app.deferred_actions.push_back(DeferredAction::OpenSession);
             }
@@ -1038,17 +1633,14 @@ async fn handle_normal_key(
                             "{}:{}~{}",
                             tmux_session, session.name, agent.name
                         );
-                        let _ = TmuxController::select_window(&shell_target).await;
+                        let _ = mux::current().select_window(&shell_target).await;
                     } else if agent.tmux_pane.is_some() && agent.is_running() {
-                        // Interactive agent — switch to session window + select its pane
-                        let session = app.selected_session().unwrap();
-                        let tmux_session = app.state.tmux_session_name.clone();
-                        let tmux_target =
-                            format!("{}:{}", tmux_session, session.name);
+                        // Interactive agent — switch to the window holding its
+                        // pane (the session's, or its own) + select the pane
                         let pane_id = agent.tmux_pane.clone();
-                        let _ = TmuxController::select_window(&tmux_target).await;
                         if let Some(ref pid) = pane_id {
-                            let _ = TmuxController::select_pane(pid).await;
+                            let _ = mux::current().select_window(pid).await;
+                            let _ = mux::current().select_pane(pid).await;
                         }
                     } else {
                         // Headless agent with output — fullscreen view
@@ -1059,8 +1651,8 @@ async fn handle_normal_key(
             }
         },
 
-        // Backspace: kill/remove (context-sensitive)
-        KeyCode::Backspace => match app.focus {
+        // Kill/remove (context-sensitive)
+        Action::Kill => match app.focus {
             Focus::SessionList => {
                 if let Some(session) = app.selected_session() {
                     if session.is_main {
@@ -1078,15 +1670,14 @@ async fn handle_normal_key(
             }
         },
 
-        // New session
-        KeyCode::Char('n') => {
+        Action::NewSession => {
             app.input_mode = InputMode::NewSession;
             app.input_buffer.clear();
             app.input_label = "New session name".to_string();
         }
 
         // Spawn agent (template picker)
-        KeyCode::Char('s') => {
+        Action::Spawn => {
             if app.selected_session().is_some() {
                 app.agent_entries = load_agent_entries(&app.workspace_root, &app.config);
                 app.selected_template = 0;
@@ -1096,23 +1687,108 @@ async fn handle_normal_key(
             }
         }
 
+        // Answer the selected session's permission prompt
+        Action::Permission => {
+            if let Some(name) = app.selected_session().map(|s| s.name.clone()) {
+                open_permission_prompt(app, &name);
+            }
+        }
+
+        // Approve every pending permission prompt
+        Action::ApproveAll => open_approve_all(app),
+
+        // Quick reply to the selected session's Claude
+        Action::Message => match app.selected_session() {
+            Some(session) => {
+                app.input_label = format!("Message to {}", session.name);
+                app.input_buffer.clear();
+                app.input_mode = InputMode::QuickReply;
+            }
+            None => {
+                app.push_notification("No session selected".into(), NotifyLevel::Error);
+            }
+        },
+
+        // Search session and agent transcripts
+        Action::Search => {
+            app.input_label = "Search transcripts".into();
+            app.input_buffer.clear();
+            app.input_mode = InputMode::Search;
+        }
+
+        // Cycle the selected session window's layout
+        Action::Layout => match app.selected_session() {
+            Some(session) => {
+                let session_name = session.name.clone();
+                app.deferred_actions
+                    .push_back(DeferredAction::CycleLayout { session_name });
+            }
+            None => {
+                app.push_notification("No session selected".into(), NotifyLevel::Error);
+            }
+        },
+
+        // Pop the selected interactive agent into its own window, or back
+        Action::PopPane if mux::current().kind() != MuxKind::Tmux => {
+            // Windows are tmux's; the PTY mux already shows every pane on its own
+            app.push_notification("Popping out panes needs tmux".into(), NotifyLevel::Info);
+        }
+        Action::PopPane => {
+            let session_name = app.selected_session().map(|s| s.name.clone());
+            let agent = app
+                .selected_agent()
+                .filter(|a| a.mode == AgentMode::Interactive && a.is_running())
+                .and_then(|a| Some((a.name.clone(), a.tmux_pane.clone()?)));
+            match (session_name, agent) {
+                (Some(session_name), Some((agent_name, pane_id))) => {
+                    app.deferred_actions.push_back(DeferredAction::PopAgentPane {
+                        session_name,
+                        agent_name,
+                        pane_id,
+                    });
+                }
+                _ => {
+                    app.push_notification(
+                        "Select a running interactive agent".into(),
+                        NotifyLevel::Error,
+                    );
+                }
+            }
+        }
+
         // Copy agent output
-        KeyCode::Char('c') => {
+        Action::Copy => {
             do_copy(app);
         }
 
-        // Refresh
-        KeyCode::Char('r') => {
-            app.refresh_state().await;
-            app.push_notification("State refreshed".into(), NotifyLevel::Info);
+        // Diff of the selected session's worktree
+        Action::Diff if app.selected_session().is_some() => {
+            app.diff_scope = crate::domain::diff::DiffScope::Base;
+            app.deferred_actions.push_back(DeferredAction::OpenDiff);
         }
 
-        // Session overview — § is the internal trigger character, sent by tmux
-        // when the overview user-key (\e[33~) is pressed.
-        KeyCode::Char('§') => {
-            if app.visible_session_count() > 0 {
-                app.deferred_actions.push_back(DeferredAction::EnterOverview);
+        // Sync the selected session onto its base branch
+        Action::Sync => match app.selected_session() {
+            Some(session) if session.is_main => {
+                app.push_notification("The main session has no base to sync onto".into(), NotifyLevel::Error);
+            }
+            Some(session) => {
+                let session_name = session.name.clone();
+                app.push_notification(format!("Syncing {session_name}..."), NotifyLevel::Info);
+                app.deferred_actions
+                    .push_back(DeferredAction::SyncSession { session_name });
+            }
+            None => {
+                app.push_notification("No session selected".into(), NotifyLevel::Error);
             }
+        },
+
+        // Fuzzy-find anything to jump to or run
+        Action::Palette => open_palette(app),
+
+        Action::Refresh => {
+            app.refresh_state().await;
+            app.push_notification("State refreshed".into(), NotifyLevel::Info);
         }
 
         _ => {}
@@ -1121,21 +1797,58 @@ async fn handle_normal_key(
     Ok(false)
 }
 
+/// Dashboard actions that change sessions, type into them or switch the
+/// tmux client's window.
+fn changes_sessions(app: &App, action: Action) -> bool {
+    match action {
+        // Only a headless agent's output opens in the dashboard itself
+        Action::Open => match app.focus {
+            Focus::SessionList => true,
+            Focus::AgentList => app.selected_agent().is_some_and(|a| {
+                a.is_running() && (a.mode == AgentMode::Shell || a.tmux_pane.is_some())
+            }),
+        },
+        Action::Kill
+        | Action::NewSession
+        | Action::Spawn
+        | Action::Permission
+        | Action::ApproveAll
+        | Action::Message
+        | Action::Layout
+        | Action::PopPane
+        | Action::Sync => true,
+        _ => false,
+    }
+}
+
+/// Refuse a key that `changes` sessions when the dashboard is read-only.
+/// True if it was refused.
+fn read_only_refuses(app: &mut App, changes: bool) -> bool {
+    if app.read_only && changes {
+        app.push_notification(
+            "Read-only dashboard — do this from the one attached to tmux".into(),
+            NotifyLevel::Error,
+        );
+        return true;
+    }
+    false
+}
+
 /// Handle keys in full-screen agent output view
-async fn handle_agent_output_key(app: &mut App, code: KeyCode) -> anyhow::Result<bool> {
-    match code {
-        KeyCode::Char('q') => return Ok(true),
-        KeyCode::Esc => {
+async fn handle_agent_output_key(app: &mut App, action: Option<Action>) -> anyhow::Result<bool> {
+    match action {
+        Some(Action::Quit) => return Ok(true),
+        Some(Action::Back) => {
             app.view_mode = ViewMode::Dashboard;
             app.output_scroll = 0;
         }
-        KeyCode::Char('j') | KeyCode::Down => {
+        Some(Action::Down) => {
             app.output_scroll = app.output_scroll.saturating_add(1);
         }
-        KeyCode::Char('k') | KeyCode::Up => {
+        Some(Action::Up) => {
             app.output_scroll = app.output_scroll.saturating_sub(1);
         }
-        KeyCode::Char('c') => {
+        Some(Action::Copy) => {
             do_copy(app);
         }
         _ => {}
@@ -1143,6 +1856,70 @@ async fn handle_agent_output_key(app: &mut App, code: KeyCode) -> anyhow::Result
     Ok(false)
 }
 
+/// Handle keys in the diff view
+async fn handle_diff_key(app: &mut App, action: Option<Action>) -> anyhow::Result<bool> {
+    if read_only_refuses(app, matches!(action, Some(Action::Stage | Action::Discard))) {
+        return Ok(false);
+    }
+    match action {
+        Some(Action::Quit) => return Ok(true),
+        Some(Action::Back) => {
+            app.view_mode = ViewMode::Dashboard;
+            app.diff_files.clear();
+        }
+        Some(Action::Down) => {
+            let count = app.diff_files.len();
+            if count > 0 {
+                app.diff_selected_file = (app.diff_selected_file + 1) % count;
+                app.diff_selected_hunk = 0;
+            }
+        }
+        Some(Action::Up) => {
+            let count = app.diff_files.len();
+            if count > 0 {
+                app.diff_selected_file =
+                    app.diff_selected_file.checked_sub(1).unwrap_or(count - 1);
+                app.diff_selected_hunk = 0;
+            }
+        }
+        Some(Action::NextHunk) => {
+            let count = app.selected_diff_file().map_or(0, |d| d.hunks.len());
+            app.diff_selected_hunk = (app.diff_selected_hunk + 1).min(count.saturating_sub(1));
+        }
+        Some(Action::PrevHunk) => {
+            app.diff_selected_hunk = app.diff_selected_hunk.saturating_sub(1);
+        }
+        Some(Action::ToggleBase) => {
+            app.diff_scope = app.diff_scope.toggle();
+            app.deferred_actions.push_back(DeferredAction::OpenDiff);
+        }
+        Some(Action::Refresh) => {
+            app.deferred_actions.push_back(DeferredAction::OpenDiff);
+        }
+        // Hunks against the merge-base don't line up with the index, so
+        // staging and discarding only work on uncommitted changes
+        Some(Action::Stage | Action::Discard)
+            if app.diff_scope == crate::domain::diff::DiffScope::Base =>
+        {
+            app.push_notification(
+                format!(
+                    "Stage and discard work on uncommitted changes; press {} to show them",
+                    app.keymap.label(Action::ToggleBase)
+                ),
+                NotifyLevel::Info,
+            );
+        }
+        Some(Action::Stage) if app.selected_diff_file().is_some_and(|d| !d.hunks.is_empty()) => {
+            app.deferred_actions.push_back(DeferredAction::StageHunk);
+        }
+        Some(Action::Discard) if app.selected_diff_file().is_some_and(|d| !d.hunks.is_empty()) => {
+            app.input_mode = InputMode::ConfirmDiscardHunk;
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
 // ─── Actions ─────────────────────────────────────────────────────────────────
 
 /// Open the selected session by switching to its tmux window.
@@ -1162,7 +1939,7 @@ async fn do_open_session(app: &mut App) -> anyhow::Result<()> {
     let tmux_session = app.state.tmux_session_name.clone();
 
     // Ensure tmux session exists
-    if let Err(e) = TmuxController::ensure_session(&tmux_session).await {
+    if let Err(e) = mux::current().ensure_session(&tmux_session).await {
         app.push_notification(format!("tmux error: {e}"), NotifyLevel::Error);
         return Ok(());
     }
@@ -1170,13 +1947,14 @@ async fn do_open_session(app: &mut App) -> anyhow::Result<()> {
     let tmux_target = format!("{tmux_session}:{session_name}");
 
     // If window doesn't exist, recreate it
-    if TmuxController::select_window(&tmux_target).await.is_err() {
+    if mux::current().select_window(&tmux_target).await.is_err() {
         let working_dir = worktree_path.to_str().unwrap_or(".").to_string();
 
-        match TmuxController::create_window(&tmux_session, &session_name, &working_dir).await {
+        match mux::current().create_window(&tmux_session, &session_name, &working_dir).await {
             Ok(window_id) => {
-                // Lock the window name so tmux doesn't auto-rename it
-                let _ = TmuxController::disable_auto_rename_for(&tmux_target).await;
+                if let Some(session) = app.state.find_session_by_name(&session_name) {
+                    crate::commands::new::install_activity_hooks(&app.state_manager, session);
+                }
 
                 // Build claude command
                 let resolved_system_prompt = if let Some(ref sp) = system_prompt_override {
@@ -1199,7 +1977,8 @@ async fn do_open_session(app: &mut App) -> anyhow::Result<()> {
                     None,
                     &app.config.global.claude_extra_args,
                 );
-                let _ = TmuxController::send_keys(&tmux_target, &cmd).await;
+                let _ = mux::current().send_keys(&tmux_target, &cmd).await;
+                let _ = mux::current().select_window(&tmux_target).await;
 
                 // Update session in state
                 if let Some(s) = app.state.find_session_by_name_mut(&session_name) {
@@ -1267,7 +2046,7 @@ async fn do_open_shell(app: &mut App) -> anyhow::Result<()> {
     let window_name = format!("{}~{}", session_name, shell_name);
     let window_target = format!("{tmux_session}:{window_name}");
 
-    match TmuxController::create_window(&tmux_session, &window_name, &working_dir).await {
+    match mux::current().create_window(&tmux_session, &window_name, &working_dir).await {
         Ok(_window_id) => {}
         Err(e) => {
             app.push_notification(format!("Failed to create shell window: {e}"), NotifyLevel::Error);
@@ -1275,11 +2054,8 @@ async fn do_open_shell(app: &mut App) -> anyhow::Result<()> {
         }
     }
 
-    // Lock the window name so tmux doesn't auto-rename it
-    let _ = TmuxController::disable_auto_rename_for(&window_target).await;
-
     // Get the pane ID of the new window (for reconciliation/status tracking)
-    let pane_id = TmuxController::first_pane_id(&window_target)
+    let pane_id = mux::current().first_pane_id(&window_target)
         .await
         .unwrap_or_default();
 
@@ -1303,7 +2079,7 @@ async fn do_open_shell(app: &mut App) -> anyhow::Result<()> {
     app.push_notification(format!("Shell '{shell_name}' opened"), NotifyLevel::Success);
 
     // Switch to the new shell window
-    let _ = TmuxController::select_window(&window_target).await;
+    let _ = mux::current().select_window(&window_target).await;
 
     Ok(())
 }
@@ -1322,16 +2098,16 @@ async fn enter_overview(app: &mut App) -> anyhow::Result<()> {
     for session in &visible {
         let session_target = format!("{tmux_session}:{}", session.name);
 
-        if !TmuxController::window_exists(&session_target).await {
+        if !mux::current().window_exists(&session_target).await {
             continue;
         }
 
-        let pane_id = match TmuxController::first_pane_id(&session_target).await {
+        let pane_id = match mux::current().first_pane_id(&session_target).await {
             Ok(id) if !id.is_empty() => id,
             _ => continue,
         };
 
-        let content = TmuxController::capture_pane(&pane_id, 50)
+        let content = mux::current().capture_pane(&pane_id, 50)
             .await
             .unwrap_or_default();
 
@@ -1374,7 +2150,7 @@ async fn refresh_overview_capture_incremental(app: &mut App) {
         .map(|t| app.session_needs_attention(&t.session_name))
         .unwrap_or(false);
     if let Some(tile) = app.overview_captures.get_mut(idx) {
-        if let Ok(content) = TmuxController::capture_pane(&tile.pane_id, 50).await {
+        if let Ok(content) = mux::current().capture_pane(&tile.pane_id, 50).await {
             tile.content = content;
         }
         tile.needs_attention = needs_attention;
@@ -1383,11 +2159,363 @@ async fn refresh_overview_capture_incremental(app: &mut App) {
     app.overview_last_capture = Instant::now();
 }
 
+/// Refresh the overview tiles whose pane printed since the last tick.
+async fn refresh_dirty_overview_tiles(app: &mut App, dirty: &HashSet<String>) {
+    for idx in 0..app.overview_captures.len() {
+        let tile = &app.overview_captures[idx];
+        if !dirty.contains(&tile.pane_id) {
+            continue;
+        }
+        let content = mux::current().capture_pane(&tile.pane_id, 50).await;
+        let needs_attention = app.session_needs_attention(&tile.session_name);
+        let tile = &mut app.overview_captures[idx];
+        if let Ok(content) = content {
+            tile.content = content;
+        }
+        tile.needs_attention = needs_attention;
+    }
+    app.overview_last_capture = Instant::now();
+}
+
+/// A window closed (tmux control mode): mark agents whose pane went with it
+/// now rather than on their round-robin turn, and drop overview tiles of
+/// closed sessions.
+async fn handle_window_closed(app: &mut App) {
+    app.reconcile_tmux_state_full().await;
+
+    let mut tiles = Vec::new();
+    for tile in std::mem::take(&mut app.overview_captures) {
+        if mux::current().pane_exists(&tile.pane_id).await {
+            tiles.push(tile);
+        }
+    }
+    app.overview_captures = tiles;
+    app.overview_selected = app
+        .overview_selected
+        .min(app.overview_captures.len().saturating_sub(1));
+}
+
+/// Show the pane the dashboard last selected, and keep the shown pane sized
+/// to the screen. Back to the dashboard once its shell exits.
+fn sync_pane_view(app: &mut App, pty: &PtyMux, size: ratatui::layout::Size) {
+    if let Some(pane_id) = pty.take_focus() {
+        app.view_mode = ViewMode::Pane { pane_id };
+    }
+    let ViewMode::Pane { pane_id } = &app.view_mode else {
+        return;
+    };
+    if pty.screen(pane_id).is_none() {
+        app.view_mode = ViewMode::Dashboard;
+        return;
+    }
+    // The status bar takes the last row
+    pty.resize(pane_id, size.height.saturating_sub(1).max(1), size.width.max(1));
+}
+
+/// A key pressed while a pane is shown: Ctrl-] or the dashboard key goes
+/// back, the overview key opens the overview, anything else is typed into
+/// the pane.
+async fn handle_pane_key(
+    app: &mut App,
+    pane_id: &str,
+    code: KeyCode,
+    modifiers: KeyModifiers,
+) -> anyhow::Result<()> {
+    let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+    // Terminals send Ctrl-] as 0x1d, which crossterm reports as Ctrl-5
+    if (ctrl && matches!(code, KeyCode::Char(']') | KeyCode::Char('5')))
+        || app.config.global.matches_dashboard_key(&code)
+    {
+        app.view_mode = ViewMode::Dashboard;
+        return Ok(());
+    }
+    if app.config.global.matches_overview_key(&code) {
+        app.view_mode = ViewMode::Dashboard;
+        return enter_overview(app).await;
+    }
+
+    let Some(pty) = mux::current().pty() else {
+        return Ok(());
+    };
+    let app_cursor = pty
+        .screen(pane_id)
+        .is_some_and(|screen| screen.lock().unwrap().screen().application_cursor());
+    let bytes = pane_key_bytes(code, modifiers, app_cursor);
+    if bytes.is_empty() {
+        return Ok(());
+    }
+    if let Err(e) = pty.write_input(pane_id, &bytes) {
+        app.push_notification(format!("Pane closed: {e}"), NotifyLevel::Error);
+    }
+    Ok(())
+}
+
+/// The chord a key event is, to look up in the keymap.
+fn key_chord(code: KeyCode, modifiers: KeyModifiers) -> Option<KeyChord> {
+    let key = match code {
+        KeyCode::Char(c) => Key::Char(c),
+        KeyCode::Enter => Key::Enter,
+        KeyCode::Esc => Key::Esc,
+        KeyCode::Tab => Key::Tab,
+        KeyCode::BackTab => Key::BackTab,
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::Delete => Key::Delete,
+        KeyCode::Up => Key::Up,
+        KeyCode::Down => Key::Down,
+        KeyCode::Left => Key::Left,
+        KeyCode::Right => Key::Right,
+        KeyCode::Home => Key::Home,
+        KeyCode::End => Key::End,
+        KeyCode::PageUp => Key::PageUp,
+        KeyCode::PageDown => Key::PageDown,
+        KeyCode::F(n) => Key::F(n),
+        _ => return None,
+    };
+    Some(KeyChord {
+        key,
+        ctrl: modifiers.contains(KeyModifiers::CONTROL),
+        alt: modifiers.contains(KeyModifiers::ALT),
+    })
+}
+
+/// Bytes an xterm sends for a key. `app_cursor` is the application cursor
+/// mode programs like editors switch on, which changes the arrow keys.
+fn pane_key_bytes(code: KeyCode, modifiers: KeyModifiers, app_cursor: bool) -> Vec<u8> {
+    let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+    let alt = modifiers.contains(KeyModifiers::ALT);
+    let shift = modifiers.contains(KeyModifiers::SHIFT);
+    // xterm's modifier parameter for cursor and function keys
+    let param = 1 + u8::from(shift) + 2 * u8::from(alt) + 4 * u8::from(ctrl);
+    let cursor = |c: char| match (param, app_cursor) {
+        (1, true) => format!("\x1bO{c}").into_bytes(),
+        (1, false) => format!("\x1b[{c}").into_bytes(),
+        _ => format!("\x1b[1;{param}{c}").into_bytes(),
+    };
+    let tilde = |n: u8| match param {
+        1 => format!("\x1b[{n}~").into_bytes(),
+        _ => format!("\x1b[{n};{param}~").into_bytes(),
+    };
+
+    let bytes = match code {
+        KeyCode::Char(c) if ctrl => match c {
+            'a'..='z' | 'A'..='Z' => vec![c.to_ascii_lowercase() as u8 - b'a' + 1],
+            ' ' | '@' | '2' => vec![0],
+            '[' | '3' => vec![0x1b],
+            '\\' | '4' => vec![0x1c],
+            ']' | '5' => vec![0x1d],
+            '^' | '6' => vec![0x1e],
+            '_' | '7' => vec![0x1f],
+            '8' => vec![0x7f],
+            _ => c.to_string().into_bytes(),
+        },
+        KeyCode::Char(c) => c.to_string().into_bytes(),
+        KeyCode::Enter => vec![b'\r'],
+        KeyCode::Tab => vec![b'\t'],
+        KeyCode::BackTab => b"\x1b[Z".to_vec(),
+        KeyCode::Backspace => vec![0x7f],
+        KeyCode::Esc => vec![0x1b],
+        KeyCode::Up => cursor('A'),
+        KeyCode::Down => cursor('B'),
+        KeyCode::Right => cursor('C'),
+        KeyCode::Left => cursor('D'),
+        KeyCode::Home => cursor('H'),
+        KeyCode::End => cursor('F'),
+        KeyCode::Insert => tilde(2),
+        KeyCode::Delete => tilde(3),
+        KeyCode::PageUp => tilde(5),
+        KeyCode::PageDown => tilde(6),
+        KeyCode::F(n @ 1..=4) => match param {
+            1 => format!("\x1bO{}", (b'P' + n - 1) as char).into_bytes(),
+            _ => format!("\x1b[1;{param}{}", (b'P' + n - 1) as char).into_bytes(),
+        },
+        KeyCode::F(n @ 5..=12) => tilde([15, 17, 18, 19, 20, 21, 23, 24][usize::from(n - 5)]),
+        _ => vec![],
+    };
+    // Alt sends Escape first, for keys that don't carry it as a parameter
+    if alt && matches!(code, KeyCode::Char(_) | KeyCode::Enter | KeyCode::Backspace) {
+        [vec![0x1b], bytes].concat()
+    } else {
+        bytes
+    }
+}
+
+/// Layout of a session's window: last picked with the cycle key, else the
+/// workspace's.
+fn session_layout(app: &App, session_name: &str) -> PaneLayout {
+    app.session_layouts
+        .get(session_name)
+        .copied()
+        .unwrap_or_else(|| app.config.pane_layout())
+}
+
+/// Re-lay out a session's window with the layout after its current one.
+async fn cycle_layout(app: &mut App, session_name: &str) {
+    let layout = session_layout(app, session_name).next();
+    let Some(name) = layout.tmux_name() else {
+        return;
+    };
+    let window = format!("{}:{session_name}", app.state.tmux_session_name);
+    match mux::current().select_layout(&window, name).await {
+        Ok(()) => {
+            app.session_layouts.insert(session_name.to_string(), layout);
+            app.push_notification(format!("{session_name}: {layout} layout"), NotifyLevel::Info);
+        }
+        Err(e) => {
+            app.push_notification(format!("Layout failed: {e}"), NotifyLevel::Error);
+        }
+    }
+}
+
+/// Move an interactive agent's pane from the session's window into a
+/// `{session}~{agent}` window of its own and switch to it, or back again.
+/// The pane keeps its ID, so the agent is tracked either way. Tmux only:
+/// the key is refused under the PTY multiplexer.
+async fn pop_agent_pane(
+    app: &mut App,
+    session_name: &str,
+    agent_name: &str,
+    pane_id: &str,
+) -> anyhow::Result<()> {
+    let session_window = format!("{}:{session_name}", app.state.tmux_session_name);
+    let in_session_window = mux::current().window_id_for_pane(pane_id).await?
+        == mux::current().window_id_for_pane(&session_window).await?;
+
+    if in_session_window {
+        TmuxController::break_pane(pane_id, &format!("{session_name}~{agent_name}")).await?;
+        let _ = TmuxController::disable_auto_rename_for(pane_id).await;
+        TmuxController::select_window(pane_id).await?;
+    } else {
+        TmuxController::join_pane(pane_id, &session_window).await?;
+        app.push_notification(
+            format!("{agent_name} moved back to {session_name}"),
+            NotifyLevel::Info,
+        );
+    }
+
+    // Re-lay out what's left in (or now back in) the session's window
+    if let Some(name) = session_layout(app, session_name).tmux_name() {
+        let _ = TmuxController::select_layout(&session_window, name).await;
+    }
+    Ok(())
+}
+
+/// Load the diff for the selected session and switch to the diff view.
+/// Multi-repo sessions diff every repo worktree, each against its own base.
+/// Keeps the file/hunk selection where possible so a reload after staging
+/// doesn't jump around.
+async fn load_session_diff(app: &mut App) -> anyhow::Result<()> {
+    use crate::infra::git;
+
+    let Some(session) = app.selected_session() else {
+        return Ok(());
+    };
+
+    let mut targets = Vec::new();
+    if session.repo_worktrees.is_empty() {
+        targets.push((
+            None,
+            session.worktree_path.clone(),
+            session
+                .base_for(None)
+                .unwrap_or(&app.state.workspace.default_branch)
+                .to_string(),
+        ));
+    } else {
+        for (repo_name, wt_path) in &session.repo_worktrees {
+            let base = session
+                .base_for(Some(repo_name))
+                .map(|b| b.to_string())
+                .or_else(|| {
+                    app.state
+                        .workspace
+                        .repos
+                        .iter()
+                        .find(|r| r.name == *repo_name)
+                        .map(|r| r.default_branch.clone())
+                })
+                .unwrap_or_else(|| app.state.workspace.default_branch.clone());
+            targets.push((Some(repo_name.clone()), wt_path.clone(), base));
+        }
+    }
+
+    let scope = app.diff_scope;
+    let files = tokio::task::spawn_blocking(move || {
+        let mut files = Vec::new();
+        for (repo_name, wt_path, default_branch) in targets {
+            let base_ref = git::resolve_base_ref(&wt_path, &default_branch);
+            files.extend(git::diff_worktree(
+                &wt_path,
+                &base_ref,
+                scope,
+                repo_name.as_deref(),
+            )?);
+        }
+        Ok::<_, crate::error::VibeError>(files)
+    })
+    .await??;
+
+    let previous = app.selected_diff_file().map(|d| d.display_path());
+    app.diff_files = files;
+    app.diff_selected_file = previous
+        .and_then(|p| app.diff_files.iter().position(|d| d.display_path() == p))
+        .unwrap_or(0);
+    let hunk_count = app.selected_diff_file().map_or(0, |d| d.hunks.len());
+    app.diff_selected_hunk = app.diff_selected_hunk.min(hunk_count.saturating_sub(1));
+    app.view_mode = ViewMode::Diff;
+    Ok(())
+}
+
+/// Stage or discard the selected hunk, then reload the diff.
+async fn apply_hunk_action(app: &mut App, discard: bool) -> anyhow::Result<()> {
+    use crate::domain::diff::HunkApply;
+    use crate::infra::git;
+
+    let Some(file) = app.selected_diff_file().cloned() else {
+        return Ok(());
+    };
+    let Some(hunk) = file.hunks.get(app.diff_selected_hunk).cloned() else {
+        return Ok(());
+    };
+
+    let display_path = file.display_path();
+    let outcome = tokio::task::spawn_blocking(move || {
+        if discard {
+            git::discard_hunk(&file, &hunk)
+        } else {
+            git::stage_hunk(&file, &hunk)
+        }
+    })
+    .await??;
+
+    match outcome {
+        HunkApply::Applied => {
+            let verb = if discard { "Discarded" } else { "Staged" };
+            app.push_notification(
+                format!("{verb} hunk in '{display_path}'"),
+                NotifyLevel::Success,
+            );
+        }
+        HunkApply::NothingUnstaged => app.push_notification(
+            "Hunk has no unstaged changes (already staged or committed)".into(),
+            NotifyLevel::Error,
+        ),
+        HunkApply::Changed => app.push_notification(
+            "Hunk changed (partly staged?), reloaded the diff; try again".into(),
+            NotifyLevel::Error,
+        ),
+    }
+
+    load_session_diff(app).await
+}
+
 // ─── Attention detection ─────────────────────────────────────────────────────
 
 /// Idle-at-prompt threshold: seconds before an idle prompt triggers attention.
 const ATTENTION_IDLE_THRESHOLD_SECS: u64 = 10;
 
+/// Pane lines captured for attention checks.
+const PERMISSION_CAPTURE_LINES: u32 = 20;
+
 /// Shells that indicate Claude has exited.
 const SHELL_COMMANDS: &[&str] = &["bash", "zsh", "fish", "sh", "dash"];
 
@@ -1403,19 +2531,71 @@ async fn check_session_attention(app: &mut App) {
     let idx = app.attention_next_session % visible.len();
     app.attention_next_session = idx + 1;
 
-    let session = &visible[idx];
+    check_attention_for(app, &visible[idx]).await;
+}
+
+/// Check the sessions whose first pane printed since the last tick (tmux
+/// control mode).
+async fn check_dirty_sessions_attention(app: &mut App, dirty: &HashSet<String>) {
+    let names: HashSet<&String> = dirty.iter().filter_map(|pane| app.session_panes.get(pane)).collect();
+    let sessions: Vec<_> = app
+        .visible_sessions()
+        .into_iter()
+        .filter(|s| names.contains(&s.name))
+        .cloned()
+        .collect();
+    for session in &sessions {
+        check_attention_for(app, session).await;
+    }
+}
+
+/// Check every visible session. With control mode this replaces the
+/// per-tick round-robin, on the refresh interval: a session going idle stops
+/// printing, so its idle threshold has to be noticed by polling.
+async fn check_all_sessions_attention(app: &mut App) {
+    let visible: Vec<_> = app.visible_sessions().into_iter().cloned().collect();
+    for session in &visible {
+        check_attention_for(app, session).await;
+    }
+}
+
+/// Update one session's attention state.
+async fn check_attention_for(app: &mut App, session: &crate::domain::session::Session) {
     let session_name = session.name.clone();
     let tmux_session = app.state.tmux_session_name.clone();
     let session_target = format!("{tmux_session}:{session_name}");
 
+    // Check 0: worktree left mid-rebase/merge by `vibe sync`? Takes priority
+    // over pane state — Claude can't make progress until it's resolved.
+    if !session.sync_conflicts.is_empty() {
+        let unresolved = session.sync_conflicts.iter().any(|c| {
+            session
+                .repo_worktree(c.repo.as_deref())
+                .is_some_and(crate::infra::git::has_unresolved_sync)
+        });
+        if unresolved {
+            set_attention(app, &session_name, AttentionReason::SyncConflict, true);
+            return;
+        }
+        // Continued or aborted outside vibe — drop the record
+        if let Some(s) = app.state.find_session_by_name_mut(&session_name) {
+            s.sync_conflicts.clear();
+        }
+        if let Err(e) = app.state_manager.save(&app.state).await {
+            tracing::error!(error = %e, "failed to save state after sync conflict resolved");
+        }
+        app.attention.remove(&session_name);
+    }
+
     // Single tmux call: get pane_id + current_command (also validates window exists)
-    let (pane_id, cmd) = match TmuxController::first_pane_info(&session_target).await {
+    let (pane_id, cmd) = match mux::current().first_pane_info(&session_target).await {
         Ok(info) => info,
         Err(_) => {
             app.attention.remove(&session_name);
             return;
         }
     };
+    app.session_panes.insert(pane_id.clone(), session_name.clone());
 
     // Check 1: Claude exited? (command is a shell)
     if SHELL_COMMANDS.contains(&cmd.to_lowercase().as_str()) {
@@ -1423,8 +2603,32 @@ async fn check_session_attention(app: &mut App) {
         return;
     }
 
-    // Check 2: Permission/idle — requires capture_pane (second tmux call)
-    let content = match TmuxController::capture_pane(&pane_id, 10).await {
+    // Check 2: Claude's hooks say what it's doing. The pane is only scraped
+    // when they haven't reported yet (hooks not installed, older Claude).
+    if let Some(event) = read_activity(app, &session_name, &pane_id).await {
+        match event.activity {
+            Activity::Working => {
+                app.attention.remove(&session_name);
+            }
+            Activity::Idle => {
+                set_attention(app, &session_name, AttentionReason::IdleAtPrompt, true);
+            }
+            Activity::NeedsPermission => {
+                // The pane still has the full prompt and how it takes answers
+                let prompt = mux::current().capture_pane(&pane_id, PERMISSION_CAPTURE_LINES)
+                    .await
+                    .ok()
+                    .and_then(|content| PermissionPrompt::detect(&content))
+                    .unwrap_or_else(|| PermissionPrompt::from_notification(event.message.as_deref()));
+                set_attention(app, &session_name, AttentionReason::PermissionPrompt(prompt), true);
+            }
+        }
+        return;
+    }
+
+    // Check 3: Permission/idle — requires capture_pane (second tmux call).
+    // Enough lines for a whole permission box.
+    let content = match mux::current().capture_pane(&pane_id, PERMISSION_CAPTURE_LINES).await {
         Ok(c) => c,
         Err(_) => {
             app.attention.remove(&session_name);
@@ -1432,8 +2636,8 @@ async fn check_session_attention(app: &mut App) {
         }
     };
 
-    if has_permission_prompt(&content) {
-        set_attention(app, &session_name, AttentionReason::PermissionPrompt, true);
+    if let Some(prompt) = PermissionPrompt::detect(&content) {
+        set_attention(app, &session_name, AttentionReason::PermissionPrompt(prompt), true);
         return;
     }
 
@@ -1459,6 +2663,47 @@ async fn check_session_attention(app: &mut App) {
     app.attention.remove(&session_name);
 }
 
+/// Latest hook-reported activity of the Claude in a session's first pane.
+async fn read_activity(app: &App, session_name: &str, pane_id: &str) -> Option<ActivityEvent> {
+    let log = tokio::fs::read_to_string(app.state_manager.events_file(session_name))
+        .await
+        .ok()?;
+    ActivityEvent::latest_for_pane(&log, pane_id)
+}
+
+/// Log an answered prompt to the session's events, so the permission request
+/// isn't reported again before Claude's next hook fires (a long tool run).
+async fn record_answer(app: &App, session_name: &str, pane_id: &str, approve: bool) {
+    use tokio::io::AsyncWriteExt;
+
+    let event = ActivityEvent {
+        at: chrono::Utc::now(),
+        hook: "vibe".into(),
+        // Denying interrupts Claude, which then waits for instructions
+        activity: if approve { Activity::Working } else { Activity::Idle },
+        pane: Some(pane_id.to_string()),
+        tool: None,
+        message: None,
+    };
+    let Ok(line) = serde_json::to_string(&event) else {
+        return;
+    };
+    let events_file = app.state_manager.events_file(session_name);
+    let result = match tokio::fs::OpenOptions::new()
+        .append(true)
+        .open(&events_file)
+        .await
+    {
+        Ok(mut file) => file.write_all(format!("{line}\n").as_bytes()).await,
+        // No log yet: the session isn't reporting through hooks
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        tracing::warn!(session = session_name, error = %e, "failed to record answered prompt");
+    }
+}
+
 /// Set or update attention state for a session.
 fn set_attention(app: &mut App, session_name: &str, reason: AttentionReason, immediate: bool) {
     let info = app
@@ -1475,15 +2720,6 @@ fn set_attention(app: &mut App, session_name: &str, reason: AttentionReason, imm
     }
 }
 
-/// Check if pane content contains a permission/confirmation prompt.
-fn has_permission_prompt(content: &str) -> bool {
-    let tail: String = content.lines().rev().take(5).collect::<Vec<_>>().join("\n");
-    tail.contains("[Y/n]")
-        || tail.contains("[y/N]")
-        || tail.contains("Do you want to")
-        || tail.contains("allow this action")
-}
-
 /// Check if Claude is idle at its input prompt.
 fn is_idle_at_prompt(content: &str) -> bool {
     // Find the last non-empty line
@@ -1502,7 +2738,7 @@ fn is_idle_at_prompt(content: &str) -> bool {
 }
 
 /// Handle keys in the session overview view
-async fn handle_overview_key(app: &mut App, code: KeyCode) -> anyhow::Result<bool> {
+async fn handle_overview_key(app: &mut App, action: Option<Action>) -> anyhow::Result<bool> {
     let tile_count = app.overview_captures.len();
     if tile_count == 0 {
         app.view_mode = ViewMode::Dashboard;
@@ -1512,42 +2748,60 @@ async fn handle_overview_key(app: &mut App, code: KeyCode) -> anyhow::Result<boo
     // Compute columns for grid navigation (must match overview widget layout)
     let cols = crate::tui::widgets::overview::compute_columns(tile_count, 0, 0);
 
-    match code {
-        KeyCode::Char('q') => return Ok(true),
+    if read_only_refuses(
+        app,
+        matches!(action, Some(Action::Open | Action::Permission | Action::ApproveAll)),
+    ) {
+        return Ok(false);
+    }
 
-        KeyCode::Esc => {
+    match action {
+        Some(Action::Quit) => return Ok(true),
+
+        Some(Action::Back) => {
             app.view_mode = ViewMode::Dashboard;
             app.overview_captures.clear();
         }
 
-        KeyCode::Right | KeyCode::Char('l') => {
+        Some(Action::Right) => {
             app.overview_selected = (app.overview_selected + 1) % tile_count;
         }
-        KeyCode::Left | KeyCode::Char('h') => {
+        Some(Action::Left) => {
             app.overview_selected = app
                 .overview_selected
                 .checked_sub(1)
                 .unwrap_or(tile_count - 1);
         }
-        KeyCode::Down | KeyCode::Char('j') => {
+        Some(Action::Down) => {
             let next = app.overview_selected + cols;
             if next < tile_count {
                 app.overview_selected = next;
             }
         }
-        KeyCode::Up | KeyCode::Char('k') => {
+        Some(Action::Up) => {
             if app.overview_selected >= cols {
                 app.overview_selected -= cols;
             }
         }
 
-        // Enter: switch to selected session's tmux window
-        KeyCode::Enter => {
+        Some(Action::Permission) => {
+            if let Some(name) = app
+                .overview_captures
+                .get(app.overview_selected)
+                .map(|t| t.session_name.clone())
+            {
+                open_permission_prompt(app, &name);
+            }
+        }
+        Some(Action::ApproveAll) => open_approve_all(app),
+
+        // Switch to selected session's tmux window
+        Some(Action::Open) => {
             if let Some(tile) = app.overview_captures.get(app.overview_selected) {
                 let session_name = tile.session_name.clone();
                 let tmux_session = app.state.tmux_session_name.clone();
                 let target = format!("{}:{}", tmux_session, session_name);
-                let _ = TmuxController::select_window(&target).await;
+                let _ = mux::current().select_window(&target).await;
                 app.clear_attention(&session_name);
             }
             app.view_mode = ViewMode::Dashboard;
@@ -1668,18 +2922,211 @@ fn load_agent_entries(
         }
     }
 
-    // 2. Vibe templates (workspace overrides > user global > built-ins)
-    let dirs = config.template_dirs(workspace_root);
-    let templates = AgentTemplate::load_all(&dirs);
-    for t in templates {
-        entries.push(AgentEntry {
-            name: t.name,
-            description: t.description,
-            source: AgentSource::VibeTemplate,
-        });
+    // 2. Vibe templates (workspace overrides > user global > built-ins)
+    let dirs = config.template_dirs(workspace_root);
+    let templates = AgentTemplate::load_all(&dirs);
+    for t in templates {
+        entries.push(AgentEntry {
+            name: t.name,
+            description: t.description,
+            source: AgentSource::VibeTemplate,
+        });
+    }
+
+    entries
+}
+
+// ─── Command palette ─────────────────────────────────────────────────────────
+
+/// Dashboard actions the palette offers beside sessions, agents, templates
+/// and plans.
+const PALETTE_ACTIONS: &[Action] = &[
+    Action::NewSession,
+    Action::Spawn,
+    Action::Kill,
+    Action::Sync,
+    Action::Diff,
+    Action::Message,
+    Action::Permission,
+    Action::ApproveAll,
+    Action::Search,
+    Action::Layout,
+    Action::PopPane,
+    Action::Copy,
+    Action::Refresh,
+];
+
+/// Gather everything the palette offers and open it with nothing typed.
+fn open_palette(app: &mut App) {
+    let mut items = Vec::new();
+    for session in app.visible_sessions() {
+        items.push(PaletteItem {
+            kind: "session",
+            label: session.name.clone(),
+            detail: format!("{} · {}", session.branch, session.status),
+            target: PaletteTarget::Session(session.name.clone()),
+        });
+    }
+    for session in app.visible_sessions() {
+        for agent in app.state.agents_for_session(session.id) {
+            items.push(PaletteItem {
+                kind: "agent",
+                label: format!("{}/{}", session.name, agent.name),
+                detail: format!("{} · {}", agent.mode, agent.status),
+                target: PaletteTarget::Agent {
+                    session_name: session.name.clone(),
+                    agent_id: agent.id,
+                },
+            });
+        }
+    }
+    for entry in load_agent_entries(&app.workspace_root, &app.config) {
+        items.push(PaletteItem {
+            kind: "spawn",
+            label: entry.name.clone(),
+            detail: entry.description.clone(),
+            target: PaletteTarget::Spawn(entry),
+        });
+    }
+    for plan in Plan::load_all(&app.state_manager.plans_dir()) {
+        let detail = match &plan.session_name {
+            Some(session) => format!("{} · {session}", plan.status),
+            None => plan.status.to_string(),
+        };
+        items.push(PaletteItem {
+            kind: "plan",
+            label: plan.title.clone(),
+            detail,
+            target: PaletteTarget::Plan {
+                title: plan.title,
+                path: plan.file_path,
+            },
+        });
+    }
+    for &action in PALETTE_ACTIONS {
+        items.push(PaletteItem {
+            kind: "action",
+            label: action.description().to_string(),
+            detail: app.keymap.label(action),
+            target: PaletteTarget::Action(action),
+        });
+    }
+
+    app.palette_items = items;
+    app.input_buffer.clear();
+    filter_palette(app);
+    app.input_mode = InputMode::Palette;
+}
+
+/// Match the palette items against the query typed so far.
+fn filter_palette(app: &mut App) {
+    let haystacks: Vec<String> = app.palette_items.iter().map(PaletteItem::haystack).collect();
+    app.palette_matches = palette::rank(&app.input_buffer, haystacks.iter().map(String::as_str));
+    app.selected_palette_match = 0;
+}
+
+fn close_palette(app: &mut App) {
+    app.input_mode = InputMode::Normal;
+    app.input_buffer.clear();
+    app.palette_items.clear();
+    app.palette_matches.clear();
+}
+
+async fn handle_palette_key(
+    app: &mut App,
+    code: KeyCode,
+    modifiers: KeyModifiers,
+) -> anyhow::Result<bool> {
+    let total = app.palette_matches.len();
+    let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+    match (code, ctrl) {
+        (KeyCode::Esc, _) => close_palette(app),
+        (KeyCode::Down, _) | (KeyCode::Char('n'), true) if total > 0 => {
+            app.selected_palette_match = (app.selected_palette_match + 1) % total;
+        }
+        (KeyCode::Up, _) | (KeyCode::Char('p'), true) if total > 0 => {
+            app.selected_palette_match = app.selected_palette_match.checked_sub(1).unwrap_or(total - 1);
+        }
+        (KeyCode::Enter, _) => {
+            let Some(&index) = app.palette_matches.get(app.selected_palette_match) else {
+                return Ok(false);
+            };
+            let item = app.palette_items.swap_remove(index);
+            close_palette(app);
+            return run_palette_item(app, item).await;
+        }
+        (KeyCode::Backspace, _) => {
+            app.input_buffer.pop();
+            filter_palette(app);
+        }
+        (KeyCode::Char(c), false) => {
+            app.input_buffer.push(c);
+            filter_palette(app);
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
+/// Jump to or run what a palette item stands for. Returns true if the app
+/// should quit.
+async fn run_palette_item(app: &mut App, item: PaletteItem) -> anyhow::Result<bool> {
+    match item.target {
+        PaletteTarget::Session(name) => {
+            // A read-only dashboard just selects it
+            if app.select_session(&name) && !app.read_only {
+                app.deferred_actions.push_back(DeferredAction::OpenSession);
+            }
+        }
+        PaletteTarget::Agent {
+            session_name,
+            agent_id,
+        } => {
+            if !app.select_session(&session_name) {
+                return Ok(false);
+            }
+            let Some(index) = app
+                .selected_session_agents()
+                .iter()
+                .position(|a| a.id == agent_id)
+            else {
+                return Ok(false);
+            };
+            app.selected_agent = index;
+            app.focus = Focus::AgentList;
+            if !app.read_only || !changes_sessions(app, Action::Open) {
+                return run_dashboard_action(app, Action::Open).await;
+            }
+        }
+        PaletteTarget::Spawn(entry) => {
+            if app.selected_session().is_none() {
+                app.push_notification("No session selected".into(), NotifyLevel::Error);
+            } else if !read_only_refuses(app, true) {
+                spawn_agent_entry(app, entry);
+            }
+        }
+        PaletteTarget::Plan { title, path } => {
+            let copied = Plan::load(&path)
+                .and_then(|(_, body)| crate::infra::clipboard::copy_text(&body));
+            match copied {
+                Ok(()) => {
+                    app.push_notification(
+                        format!("Plan '{title}' copied to clipboard"),
+                        NotifyLevel::Success,
+                    );
+                }
+                Err(e) => {
+                    app.push_notification(format!("Copy failed: {e}"), NotifyLevel::Error);
+                }
+            }
+        }
+        PaletteTarget::Action(action) => {
+            if !read_only_refuses(app, changes_sessions(app, action)) {
+                return run_dashboard_action(app, action).await;
+            }
+        }
     }
-
-    entries
+    Ok(false)
 }
 
 // ─── Input / Confirm handlers ────────────────────────────────────────────────
@@ -1704,9 +3151,25 @@ async fn handle_input_key(app: &mut App, code: KeyCode) -> anyhow::Result<bool>
                         .trim()
                         .replace(' ', "-")
                         .to_lowercase();
-                    app.input_mode = InputMode::Normal;
-                    app.deferred_actions
-                        .push_back(DeferredAction::CreateSession { name: input });
+                    if app.state.workspace.is_multi_repo() {
+                        // Pick which repos to check out before creating
+                        app.pending_session_name = input;
+                        app.repo_choices = app
+                            .state
+                            .workspace
+                            .repos
+                            .iter()
+                            .map(|r| (r.name.clone(), true))
+                            .collect();
+                        app.selected_repo_choice = 0;
+                        app.input_mode = InputMode::SelectRepos;
+                    } else {
+                        app.input_mode = InputMode::Normal;
+                        app.deferred_actions.push_back(DeferredAction::CreateSession {
+                            name: input,
+                            repos: None,
+                        });
+                    }
                 }
                 InputMode::SpawnAgent => {
                     app.input_mode = InputMode::Normal;
@@ -1716,6 +3179,21 @@ async fn handle_input_key(app: &mut App, code: KeyCode) -> anyhow::Result<bool>
                         session_name,
                     });
                 }
+                InputMode::Search => {
+                    app.input_mode = InputMode::Normal;
+                    app.deferred_actions.push_back(DeferredAction::SearchTranscripts {
+                        query: raw_input.clone(),
+                    });
+                }
+                InputMode::QuickReply => {
+                    app.input_mode = InputMode::Normal;
+                    if let Some(session) = app.selected_session() {
+                        app.deferred_actions.push_back(DeferredAction::SendMessage {
+                            session_name: session.name.clone(),
+                            message: raw_input.clone(),
+                        });
+                    }
+                }
                 _ => {}
             }
             app.input_buffer.clear();
@@ -1731,6 +3209,102 @@ async fn handle_input_key(app: &mut App, code: KeyCode) -> anyhow::Result<bool>
     Ok(false)
 }
 
+async fn handle_search_results_key(app: &mut App, code: KeyCode) -> anyhow::Result<bool> {
+    let total = app.search_results.len();
+    match code {
+        KeyCode::Esc => {
+            app.input_mode = InputMode::Normal;
+            app.search_results.clear();
+        }
+        KeyCode::Char('j') | KeyCode::Down if total > 0 => {
+            app.selected_search_result = (app.selected_search_result + 1) % total;
+        }
+        KeyCode::Char('k') | KeyCode::Up if total > 0 => {
+            app.selected_search_result = app.selected_search_result.checked_sub(1).unwrap_or(total - 1);
+        }
+        KeyCode::Enter => {
+            let Some(hit) = app.search_results.get(app.selected_search_result) else {
+                return Ok(false);
+            };
+            let session_name = hit.session.clone();
+            let killed = hit.killed;
+            app.input_mode = InputMode::Normal;
+            app.search_results.clear();
+            if killed {
+                app.push_notification(
+                    format!("Session '{session_name}' was killed; `vibe log {session_name}` prints its transcript"),
+                    NotifyLevel::Info,
+                );
+            } else if app.select_session(&session_name) {
+                // A read-only dashboard just selects it
+                if !app.read_only {
+                    app.deferred_actions.push_back(DeferredAction::OpenSession);
+                }
+            } else {
+                app.push_notification(
+                    format!("Session '{session_name}' is archived"),
+                    NotifyLevel::Info,
+                );
+            }
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
+async fn handle_select_repos_key(app: &mut App, code: KeyCode) -> anyhow::Result<bool> {
+    let total = app.repo_choices.len();
+    if total == 0 {
+        app.input_mode = InputMode::Normal;
+        return Ok(false);
+    }
+
+    match code {
+        KeyCode::Esc => {
+            app.input_mode = InputMode::Normal;
+            app.repo_choices.clear();
+            app.pending_session_name.clear();
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.selected_repo_choice = (app.selected_repo_choice + 1) % total;
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.selected_repo_choice = app.selected_repo_choice.checked_sub(1).unwrap_or(total - 1);
+        }
+        KeyCode::Char(' ') => {
+            if let Some(choice) = app.repo_choices.get_mut(app.selected_repo_choice) {
+                choice.1 = !choice.1;
+            }
+        }
+        KeyCode::Char('a') => {
+            let all_checked = app.repo_choices.iter().all(|(_, checked)| *checked);
+            for choice in &mut app.repo_choices {
+                choice.1 = !all_checked;
+            }
+        }
+        KeyCode::Enter => {
+            let chosen: Vec<String> = app
+                .repo_choices
+                .iter()
+                .filter(|(_, checked)| *checked)
+                .map(|(name, _)| name.clone())
+                .collect();
+            if chosen.is_empty() {
+                app.push_notification("Select at least one repo".into(), NotifyLevel::Error);
+                return Ok(false);
+            }
+            let repos = if chosen.len() == total { None } else { Some(chosen) };
+            let name = std::mem::take(&mut app.pending_session_name);
+            app.repo_choices.clear();
+            app.input_mode = InputMode::Normal;
+            app.deferred_actions
+                .push_back(DeferredAction::CreateSession { name, repos });
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
 async fn handle_select_template_key(app: &mut App, code: KeyCode) -> anyhow::Result<bool> {
     let total = app.agent_entries.len() + 1; // +1 for "Custom prompt..."
 
@@ -1748,36 +3322,10 @@ async fn handle_select_template_key(app: &mut App, code: KeyCode) -> anyhow::Res
         KeyCode::Enter => {
             let idx = app.selected_template;
             if idx < app.agent_entries.len() {
-                // Check if this is a Shell entry
-                if matches!(app.agent_entries[idx].source, AgentSource::Shell) {
-                    app.input_mode = InputMode::Normal;
-                    app.agent_entries.clear();
-                    app.deferred_actions.push_back(DeferredAction::OpenShell);
-                } else {
-                    // Extract needed values before mutating app
-                    let entry_name = app.agent_entries[idx].name.clone();
-                    let entry_desc = app.agent_entries[idx].description.clone();
-                    let template_name = match &app.agent_entries[idx].source {
-                        AgentSource::VibeTemplate => Some(entry_name.clone()),
-                        AgentSource::ClaudeCode(_) | AgentSource::Shell => None,
-                    };
-                    let system_prompt = match &app.agent_entries[idx].source {
-                        AgentSource::ClaudeCode(content) => Some(content.clone()),
-                        AgentSource::VibeTemplate | AgentSource::Shell => None,
-                    };
-                    let session_name = app.selected_session().map(|s| s.name.clone());
-
-                    app.input_mode = InputMode::Normal;
-                    app.agent_entries.clear();
-
-                    app.deferred_actions.push_back(DeferredAction::SpawnFromTemplate {
-                        description: entry_desc,
-                        session_name,
-                        template_name,
-                        system_prompt,
-                        entry_name,
-                    });
-                }
+                let entry = app.agent_entries.swap_remove(idx);
+                app.input_mode = InputMode::Normal;
+                app.agent_entries.clear();
+                spawn_agent_entry(app, entry);
             } else {
                 // "Custom prompt..." — switch to free text input
                 app.input_mode = InputMode::SpawnAgent;
@@ -1792,6 +3340,156 @@ async fn handle_select_template_key(app: &mut App, code: KeyCode) -> anyhow::Res
     Ok(false)
 }
 
+/// Open a shell or spawn an agent from a template picker entry, in the
+/// selected session.
+fn spawn_agent_entry(app: &mut App, entry: AgentEntry) {
+    let (template_name, system_prompt) = match entry.source {
+        AgentSource::Shell => {
+            app.deferred_actions.push_back(DeferredAction::OpenShell);
+            return;
+        }
+        AgentSource::VibeTemplate => (Some(entry.name.clone()), None),
+        AgentSource::ClaudeCode(content) => (None, Some(content)),
+    };
+    let session_name = app.selected_session().map(|s| s.name.clone());
+    app.deferred_actions.push_back(DeferredAction::SpawnFromTemplate {
+        description: entry.description,
+        session_name,
+        template_name,
+        system_prompt,
+        entry_name: entry.name,
+    });
+}
+
+/// Show the permission prompt a session is waiting on, to approve or deny.
+fn open_permission_prompt(app: &mut App, session_name: &str) {
+    match app.permission_prompt(session_name).cloned() {
+        Some(prompt) => {
+            app.permission_prompts = vec![(session_name.to_string(), prompt)];
+            app.input_mode = InputMode::AnswerPermission;
+        }
+        None => {
+            app.push_notification(
+                format!("No permission prompt waiting in {session_name}"),
+                NotifyLevel::Info,
+            );
+        }
+    }
+}
+
+/// List every pending permission prompt for confirmation before approving all.
+fn open_approve_all(app: &mut App) {
+    let prompts = app.pending_permission_prompts();
+    if prompts.is_empty() {
+        app.push_notification("No permission prompts waiting".into(), NotifyLevel::Info);
+        return;
+    }
+    app.permission_prompts = prompts;
+    app.input_mode = InputMode::ConfirmApproveAll;
+}
+
+async fn handle_answer_permission_key(app: &mut App, code: KeyCode) -> anyhow::Result<bool> {
+    let approve = match code {
+        KeyCode::Char('y') | KeyCode::Enter => true,
+        KeyCode::Char('n') => false,
+        KeyCode::Esc => {
+            app.input_mode = InputMode::Normal;
+            app.permission_prompts.clear();
+            return Ok(false);
+        }
+        _ => return Ok(false),
+    };
+    app.input_mode = InputMode::Normal;
+    app.deferred_actions.push_back(DeferredAction::AnswerPermissions {
+        prompts: std::mem::take(&mut app.permission_prompts),
+        approve,
+    });
+    Ok(false)
+}
+
+async fn handle_confirm_approve_all_key(app: &mut App, code: KeyCode) -> anyhow::Result<bool> {
+    match code {
+        KeyCode::Enter => {
+            app.input_mode = InputMode::Normal;
+            app.deferred_actions.push_back(DeferredAction::AnswerPermissions {
+                prompts: std::mem::take(&mut app.permission_prompts),
+                approve: true,
+            });
+        }
+        KeyCode::Esc => {
+            app.input_mode = InputMode::Normal;
+            app.permission_prompts.clear();
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
+/// Answer prompts by sending their keys to each session's pane. The pane is
+/// captured again first and skipped unless it still shows the prompt the user
+/// saw, so a stray "1" or "y" never lands in Claude's input. Hooks can say a
+/// prompt is pending but not which menu is up, so they're not enough.
+async fn answer_permissions(app: &mut App, prompts: Vec<(String, PermissionPrompt)>, approve: bool) {
+    let tmux_session = app.state.tmux_session_name.clone();
+    let mut answered = 0;
+    let mut stale = vec![];
+    for (session_name, shown) in prompts {
+        let target = format!("{tmux_session}:{session_name}");
+        let Ok((pane_id, _)) = mux::current().first_pane_info(&target).await else {
+            stale.push(session_name);
+            continue;
+        };
+        let detected = mux::current().capture_pane(&pane_id, PERMISSION_CAPTURE_LINES)
+            .await
+            .ok()
+            .and_then(|content| PermissionPrompt::detect(&content));
+        let prompt = match detected {
+            Some(prompt) if prompt == shown => prompt,
+            Some(_) => {
+                stale.push(session_name);
+                continue;
+            }
+            None => {
+                app.push_notification(
+                    format!("Can't see {session_name}'s prompt on screen; answer it in its pane"),
+                    NotifyLevel::Info,
+                );
+                continue;
+            }
+        };
+
+        let keys = if approve {
+            prompt.approve_keys()
+        } else {
+            prompt.deny_keys()
+        };
+        match mux::current().send_key_names(&pane_id, keys).await {
+            Ok(()) => {
+                answered += 1;
+                record_answer(app, &session_name, &pane_id, approve).await;
+                app.clear_attention(&session_name);
+            }
+            Err(e) => {
+                app.push_notification(
+                    format!("Failed to answer {session_name}: {e}"),
+                    NotifyLevel::Error,
+                );
+            }
+        }
+    }
+
+    let verb = if approve { "Approved" } else { "Denied" };
+    if answered > 0 {
+        app.push_notification(format!("{verb} {answered} prompt(s)"), NotifyLevel::Success);
+    }
+    if !stale.is_empty() {
+        app.push_notification(
+            format!("No longer waiting (skipped): {}", stale.join(", ")),
+            NotifyLevel::Info,
+        );
+    }
+}
+
 async fn handle_confirm_kill_session(app: &mut App, code: KeyCode) -> anyhow::Result<bool> {
     match code {
         KeyCode::Enter => {
@@ -1835,12 +3533,44 @@ async fn handle_confirm_kill_agent(app: &mut App, code: KeyCode) -> anyhow::Resu
     Ok(false)
 }
 
+async fn handle_confirm_discard_hunk(app: &mut App, code: KeyCode) -> anyhow::Result<bool> {
+    match code {
+        KeyCode::Enter => {
+            app.input_mode = InputMode::Normal;
+            app.deferred_actions.push_back(DeferredAction::DiscardHunk);
+        }
+        KeyCode::Esc => {
+            app.input_mode = InputMode::Normal;
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
 // ─── Deferred action processing ──────────────────────────────────────────────
 
 /// Process a single deferred action. Called once per tick outside the event
 /// drain loop so heavy tmux operations don't block keyboard input.
 async fn process_deferred_action(app: &mut App, action: DeferredAction) {
     match action {
+        DeferredAction::SearchTranscripts { query } => {
+            let state_manager = StateManager::new(&app.workspace_root);
+            let state = app.state.clone();
+            let search_query = query.clone();
+            let hits = tokio::task::spawn_blocking(move || {
+                crate::infra::transcript::search_all(&state_manager, &state, &search_query)
+            })
+            .await
+            .unwrap_or_default();
+            if hits.is_empty() {
+                app.push_notification(format!("No matches for \"{query}\""), NotifyLevel::Info);
+            } else {
+                app.search_query = query;
+                app.search_results = hits;
+                app.selected_search_result = 0;
+                app.input_mode = InputMode::SearchResults;
+            }
+        }
         DeferredAction::OpenSession => {
             if let Err(e) = do_open_session(app).await {
                 app.push_notification(format!("Error: {e}"), NotifyLevel::Error);
@@ -1856,12 +3586,75 @@ async fn process_deferred_action(app: &mut App, action: DeferredAction) {
                 app.push_notification(format!("Error: {e}"), NotifyLevel::Error);
             }
         }
+        DeferredAction::CycleLayout { session_name } => {
+            if mux::current().kind() == MuxKind::Tmux {
+                cycle_layout(app, &session_name).await;
+            } else {
+                app.push_notification("Layouts need tmux".into(), NotifyLevel::Info);
+            }
+        }
+        DeferredAction::PopAgentPane {
+            session_name,
+            agent_name,
+            pane_id,
+        } => {
+            if let Err(e) = pop_agent_pane(app, &session_name, &agent_name, &pane_id).await {
+                app.push_notification(format!("Error: {e}"), NotifyLevel::Error);
+            }
+        }
+        DeferredAction::OpenDiff => {
+            if let Err(e) = load_session_diff(app).await {
+                app.push_notification(format!("Diff failed: {e}"), NotifyLevel::Error);
+            }
+        }
+        DeferredAction::StageHunk => {
+            if let Err(e) = apply_hunk_action(app, false).await {
+                app.push_notification(format!("Stage failed: {e}"), NotifyLevel::Error);
+            }
+        }
+        DeferredAction::DiscardHunk => {
+            if let Err(e) = apply_hunk_action(app, true).await {
+                app.push_notification(format!("Discard failed: {e}"), NotifyLevel::Error);
+            }
+        }
+        DeferredAction::AnswerPermissions { prompts, approve } => {
+            answer_permissions(app, prompts, approve).await;
+        }
+        DeferredAction::SyncSession { session_name } => {
+            let strategy = app.config.sync_strategy();
+            // Nobody is watching the worktree: leave no rebase half-done in it
+            match commands::sync::run(&app.workspace_root, &[session_name], strategy, true).await {
+                Ok(reports) => {
+                    app.refresh_state().await;
+                    for report in &reports {
+                        let (message, level) = sync_notification(report, strategy);
+                        app.push_notification(message, level);
+                    }
+                }
+                Err(e) => {
+                    app.push_notification(format!("Sync failed: {e}"), NotifyLevel::Error);
+                }
+            }
+        }
+        DeferredAction::SendMessage {
+            session_name,
+            message,
+        } => match commands::send::execute(&app.workspace_root, &session_name, &message).await {
+            Ok(()) => {
+                app.clear_attention(&session_name);
+                app.push_notification(format!("Sent to {session_name}"), NotifyLevel::Success);
+            }
+            Err(e) => {
+                app.push_notification(format!("Send failed: {e}"), NotifyLevel::Error);
+            }
+        },
         DeferredAction::KillSession { name } => {
             match commands::kill::execute(
                 &app.workspace_root,
                 name.clone(),
                 true,  // force
                 false, // don't delete branch
+                false, // keep the transcript
                 &app.config,
             )
             .await
@@ -1895,7 +3688,7 @@ async fn process_deferred_action(app: &mut App, action: DeferredAction) {
                     let tmux_session = app.state.tmux_session_name.clone();
                     let window_target =
                         format!("{}:{}~{}", tmux_session, session_name, agent_name);
-                    if let Err(e) = TmuxController::kill_window(&window_target).await {
+                    if let Err(e) = mux::current().kill_window(&window_target).await {
                         tracing::warn!(window = %window_target, error = %e, "failed to kill shell window");
                     }
                 }
@@ -1908,7 +3701,7 @@ async fn process_deferred_action(app: &mut App, action: DeferredAction) {
                     .find(|a| a.id == agent_id)
                     .and_then(|a| a.tmux_pane.clone());
                 if let Some(ref pane_id) = pane {
-                    if let Err(e) = TmuxController::kill_pane(pane_id).await {
+                    if let Err(e) = mux::current().kill_pane(pane_id).await {
                         tracing::warn!(pane = %pane_id, error = %e, "failed to kill agent pane");
                     }
                 }
@@ -1943,7 +3736,7 @@ async fn process_deferred_action(app: &mut App, action: DeferredAction) {
             )
             .await
             {
-                Ok(()) => {
+                Ok(_) => {
                     app.refresh_state().await;
                     app.push_notification(
                         format!("Agent '{entry_name}' spawned"),
@@ -1970,7 +3763,7 @@ async fn process_deferred_action(app: &mut App, action: DeferredAction) {
             )
             .await
             {
-                Ok(()) => {
+                Ok(_) => {
                     app.refresh_state().await;
                     app.push_notification("Agent spawned".into(), NotifyLevel::Success);
                 }
@@ -1979,27 +3772,32 @@ async fn process_deferred_action(app: &mut App, action: DeferredAction) {
                 }
             }
         }
-        DeferredAction::CreateSession { name } => {
-            match commands::new::execute(
-                &app.workspace_root,
-                name.clone(),
-                None,
-                None,
-                None,
-                None,
-                false,
-                None,
-                &app.config,
-            )
-            .await
+        DeferredAction::CreateSession { name, repos } => {
+            let options = commands::new::NewSessionOptions {
+                name: name.clone(),
+                repos,
+                ..Default::default()
+            };
+            match commands::new::execute(&app.workspace_root, options, &app.config).await
             {
-                Ok(()) => {
+                Ok(failed) if failed.is_empty() => {
                     app.refresh_state().await;
                     app.push_notification(
                         format!("Session '{name}' created"),
                         NotifyLevel::Success,
                     );
                 }
+                Ok(failed) => {
+                    app.refresh_state().await;
+                    let repos: Vec<&str> = failed.keys().map(|r| r.as_str()).collect();
+                    app.push_notification(
+                        format!(
+                            "Session '{name}' created without {}; run `vibe session repair {name}`",
+                            repos.join(", ")
+                        ),
+                        NotifyLevel::Error,
+                    );
+                }
                 Err(e) => {
                     app.push_notification(format!("Error: {e}"), NotifyLevel::Error);
                 }
@@ -2007,3 +3805,22 @@ async fn process_deferred_action(app: &mut App, action: DeferredAction) {
         }
     }
 }
+
+/// Notification for how syncing one worktree went.
+fn sync_notification(report: &commands::sync::SyncReport, strategy: SyncStrategy) -> (String, NotifyLevel) {
+    let label = report.label();
+    match &report.outcome {
+        SyncOutcome::UpToDate => (format!("{label}: up to date with {}", report.onto), NotifyLevel::Info),
+        SyncOutcome::Synced => (format!("{label}: {strategy}d onto {}", report.onto), NotifyLevel::Success),
+        SyncOutcome::Conflicted { files, .. } => (
+            format!("{label}: {} conflicting file(s) — {strategy} aborted, branch unchanged", files.len()),
+            NotifyLevel::Error,
+        ),
+        SyncOutcome::InProgress => (
+            format!("{label}: a rebase/merge is already in progress"),
+            NotifyLevel::Error,
+        ),
+        SyncOutcome::Skipped(reason) => (format!("{label}: skipped — {reason}"), NotifyLevel::Info),
+        SyncOutcome::Failed(err) => (format!("{label}: sync failed — {err}"), NotifyLevel::Error),
+    }
+}
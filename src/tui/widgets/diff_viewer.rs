@@ -0,0 +1,144 @@
+use crate::domain::diff::{DiffLineKind, FileChange};
+use crate::domain::syntax::{Syntax, TokenKind};
+use crate::tui::app::App;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let scolor = app.current_session_color();
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(40), Constraint::Min(30)])
+        .split(area);
+
+    render_file_list(f, app, scolor, chunks[0]);
+    render_hunks(f, app, scolor, chunks[1]);
+}
+
+fn render_file_list(f: &mut Frame, app: &App, scolor: Color, area: Rect) {
+    let session_name = app.selected_session().map(|s| s.name.as_str()).unwrap_or("?");
+    let title = format!("{session_name} {} ({})", app.diff_scope, app.diff_files.len());
+    let block = super::panel_block(&title, scolor, true);
+
+    if app.diff_files.is_empty() {
+        let paragraph = Paragraph::new("  No changes.")
+            .block(block)
+            .style(Style::default().fg(Color::Gray));
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = app
+        .diff_files
+        .iter()
+        .map(|file| {
+            let marker_color = match file.change {
+                FileChange::Added | FileChange::Untracked => Color::Green,
+                FileChange::Deleted => Color::Red,
+                _ => Color::Yellow,
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!(" {} ", file.change.marker()),
+                    Style::default().fg(marker_color),
+                ),
+                Span::styled(file.display_path(), Style::default()),
+                Span::styled(
+                    format!(" +{}", file.additions()),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(
+                    format!(" -{}", file.deletions()),
+                    Style::default().fg(Color::Red),
+                ),
+            ]))
+        })
+        .collect();
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.diff_selected_file));
+
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn render_hunks(f: &mut Frame, app: &App, scolor: Color, area: Rect) {
+    let Some(file) = app.selected_diff_file() else {
+        let block = super::panel_block("Diff", scolor, false);
+        f.render_widget(Paragraph::new("").block(block), area);
+        return;
+    };
+
+    let block = super::panel_block(&file.display_path(), scolor, false);
+
+    if file.binary {
+        let paragraph = Paragraph::new("  Binary file")
+            .block(block)
+            .style(Style::default().fg(Color::Gray));
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let syntax = Syntax::for_path(&file.path);
+    let mut lines = Vec::new();
+    for (i, hunk) in file.hunks.iter().enumerate() {
+        let selected = i == app.diff_selected_hunk;
+        let marker = if selected { "\u{25b8} " } else { "  " };
+        let header_style = if selected {
+            Style::default().fg(scolor).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Cyan)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(marker, header_style),
+            Span::styled(hunk.header.clone(), header_style),
+        ]));
+
+        for line in &hunk.lines {
+            let (prefix, color) = match line.kind {
+                DiffLineKind::Added => ("+", Color::Green),
+                DiffLineKind::Removed => ("-", Color::Red),
+                DiffLineKind::Context => (" ", Color::Gray),
+            };
+            let gutter = if selected { "\u{2502} " } else { "  " };
+            let mut spans = vec![
+                Span::styled(gutter, Style::default().fg(scolor)),
+                Span::styled(prefix, Style::default().fg(color)),
+            ];
+            match syntax {
+                Some(syntax) => spans.extend(
+                    syntax
+                        .tokens(&line.content)
+                        .into_iter()
+                        .map(|(kind, text)| Span::styled(text.to_string(), token_style(kind, color))),
+                ),
+                None => spans.push(Span::styled(line.content.clone(), Style::default().fg(color))),
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+
+    // Keep the selected hunk's header at the top of the panel
+    let scroll = file.hunk_offset(app.diff_selected_hunk) as u16;
+    let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+    f.render_widget(paragraph, area);
+}
+
+/// Colour of a highlighted token; plain text keeps the +/-/context colour.
+fn token_style(kind: TokenKind, line_color: Color) -> Style {
+    let color = match kind {
+        TokenKind::Plain => line_color,
+        TokenKind::Keyword => Color::Magenta,
+        TokenKind::String => Color::Yellow,
+        TokenKind::Comment => Color::DarkGray,
+        TokenKind::Number => Color::Cyan,
+    };
+    Style::default().fg(color)
+}
@@ -1,6 +1,8 @@
 pub mod agent_list;
+pub mod diff_viewer;
 pub mod output_viewer;
 pub mod overview;
+pub mod pane_view;
 pub mod session_detail;
 pub mod session_list;
 pub mod status_bar;
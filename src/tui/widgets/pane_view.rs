@@ -0,0 +1,67 @@
+use crate::infra::mux::PtyMux;
+use ratatui::layout::{Position, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::Frame;
+
+/// Draw a pane of the built-in multiplexer cell by cell, with its cursor.
+pub fn render(f: &mut Frame, pty: &PtyMux, pane_id: &str, area: Rect) {
+    let Some(parser) = pty.screen(pane_id) else {
+        return;
+    };
+    let parser = parser.lock().unwrap();
+    let screen = parser.screen();
+    let (rows, cols) = screen.size();
+
+    let buf = f.buffer_mut();
+    for row in 0..rows.min(area.height) {
+        for col in 0..cols.min(area.width) {
+            let Some(cell) = screen.cell(row, col) else {
+                continue;
+            };
+            // The left half of a wide character covers this one
+            if cell.is_wide_continuation() {
+                continue;
+            }
+            let Some(target) = buf.cell_mut(Position::new(area.x + col, area.y + row)) else {
+                continue;
+            };
+            let contents = cell.contents();
+            target.set_symbol(if contents.is_empty() { " " } else { contents });
+            target.set_style(cell_style(cell));
+        }
+    }
+
+    if !screen.hide_cursor() {
+        let (row, col) = screen.cursor_position();
+        if row < area.height && col < area.width {
+            f.set_cursor_position(Position::new(area.x + col, area.y + row));
+        }
+    }
+}
+
+fn cell_style(cell: &vt100::Cell) -> Style {
+    let mut style = Style::default()
+        .fg(color(cell.fgcolor()))
+        .bg(color(cell.bgcolor()));
+    let attrs = [
+        (cell.bold(), Modifier::BOLD),
+        (cell.dim(), Modifier::DIM),
+        (cell.italic(), Modifier::ITALIC),
+        (cell.underline(), Modifier::UNDERLINED),
+        (cell.inverse(), Modifier::REVERSED),
+    ];
+    for (on, modifier) in attrs {
+        if on {
+            style = style.add_modifier(modifier);
+        }
+    }
+    style
+}
+
+fn color(color: vt100::Color) -> Color {
+    match color {
+        vt100::Color::Default => Color::Reset,
+        vt100::Color::Idx(i) => Color::Indexed(i),
+        vt100::Color::Rgb(r, g, b) => Color::Rgb(r, g, b),
+    }
+}
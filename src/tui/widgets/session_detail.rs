@@ -63,13 +63,105 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
                 ),
             ]));
             for (repo_name, _) in &session.repo_worktrees {
-                lines.push(Line::from(vec![
+                let mut spans = vec![
                     Span::styled("  ", Style::default()),
                     Span::styled(repo_name, Style::default().fg(scolor)),
-                ]));
+                ];
+                // Show the repo's branch/base only where it differs from the session's
+                let branch = session.branch_for(Some(repo_name));
+                if branch != session.branch {
+                    spans.push(Span::styled(
+                        format!("  {branch}"),
+                        Style::default().fg(Color::Gray),
+                    ));
+                }
+                if let Some(base) = session.base_for(Some(repo_name)) {
+                    spans.push(Span::styled(
+                        format!("  (from {base})"),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                lines.push(Line::from(spans));
             }
         }
 
+        for (repo_name, err) in &session.failed_repos {
+            // Git errors are multi-line; the first line carries the reason
+            let reason = err.lines().next().unwrap_or_default();
+            lines.push(Line::from(vec![
+                Span::styled("Failed: ", Style::default().fg(Color::Gray)),
+                Span::styled(
+                    format!("{repo_name} — {reason}"),
+                    Style::default().fg(Color::Red),
+                ),
+            ]));
+        }
+        if !session.failed_repos.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!("  vibe session repair {}", session.name),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+
+        for conflict in &session.sync_conflicts {
+            let label = match &conflict.repo {
+                Some(repo) => format!("{} conflict in {repo}", conflict.strategy),
+                None => format!("{} conflict", conflict.strategy),
+            };
+            lines.push(Line::from(vec![
+                Span::styled("Sync: ", Style::default().fg(Color::Gray)),
+                Span::styled(
+                    format!("{label} ({} file(s))", conflict.files.len()),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ),
+            ]));
+        }
+
+        for overlap in app.session_overlaps(&session.name) {
+            let color = if overlap.conflicts() {
+                Color::Red
+            } else {
+                Color::Yellow
+            };
+            lines.push(Line::from(vec![
+                Span::styled("Overlap: ", Style::default().fg(Color::Gray)),
+                Span::styled(
+                    format!("{} — {}", overlap.other(&session.name), overlap.files_summary()),
+                    Style::default().fg(color),
+                ),
+            ]));
+        }
+
+        let prs: Vec<String> = match session.metadata.pr_number {
+            Some(number) => vec![format!("#{number}")],
+            None => session
+                .repo_prs
+                .iter()
+                .map(|(repo, number)| format!("{repo}#{number}"))
+                .collect(),
+        };
+        if !prs.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled("PRs: ", Style::default().fg(Color::Gray)),
+                Span::styled(prs.join(", "), Style::default().fg(Color::Cyan)),
+            ]));
+        }
+        if let Some((number, review)) = session
+            .metadata
+            .pr_number
+            .and_then(|number| app.review_watch.prs.get(&number).map(|r| (number, r)))
+        {
+            let color = if review.error.is_some() {
+                Color::Red
+            } else {
+                Color::Green
+            };
+            lines.push(Line::from(vec![
+                Span::styled("Review: ", Style::default().fg(Color::Gray)),
+                Span::styled(review.summary(number), Style::default().fg(color)),
+            ]));
+        }
+
         lines.push(Line::from(""));
         lines.push(Line::from(vec![
             Span::styled("Template: ", Style::default().fg(Color::Gray)),
@@ -60,6 +60,17 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
                 ),
             ];
 
+            // Another session changes the same files (red if they'd conflict)
+            let overlaps = app.session_overlaps(&session.name);
+            if !overlaps.is_empty() {
+                let color = if overlaps.iter().any(|o| o.conflicts()) {
+                    Color::Red
+                } else {
+                    Color::Yellow
+                };
+                spans.push(Span::styled(" \u{2248}", Style::default().fg(color)));
+            }
+
             if needs_attention && flash_on {
                 spans.push(Span::styled(
                     " !",
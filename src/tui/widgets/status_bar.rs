@@ -1,9 +1,11 @@
+use crate::domain::keymap::Action;
 use crate::tui::app::{App, Focus, ViewMode};
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
 use ratatui::Frame;
+use std::borrow::Cow;
 use std::time::SystemTime;
 
 pub fn render(f: &mut Frame, app: &App, area: Rect) {
@@ -51,70 +53,110 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
             Span::styled(" ", Style::default()),
             Span::styled(msg, Style::default().fg(Color::Yellow)),
         ])
+    } else if let ViewMode::Pane { pane_id } = &app.view_mode {
+        // A pane of the built-in multiplexer; only these keys aren't typed
+        // into it
+        let window = crate::infra::mux::current()
+            .pty()
+            .and_then(|pty| pty.window_name(pane_id))
+            .unwrap_or_default();
+        Line::from(vec![
+            Span::styled(format!(" {window} "), Style::default().add_modifier(Modifier::BOLD)),
+            key_span("[^]]"),
+            Span::raw(" or "),
+            key_span(&dash_key_label),
+            Span::raw("back "),
+            key_span(&overview_key_label),
+            Span::raw("overview"),
+            Span::styled(right_indicators, Style::default().fg(Color::Yellow)),
+        ])
     } else if app.view_mode == ViewMode::SessionOverview {
         // TUI-rendered tiled overview with live capture previews
         let mut spans = vec![];
-        spans.push(key_span("[arrows]"));
-        spans.push(Span::raw("select "));
-        spans.push(key_span("[⏎]"));
-        spans.push(Span::raw("open "));
+        spans.extend(hint(app, &[Action::Left, Action::Down, Action::Up, Action::Right], "select"));
+        spans.extend(hint(app, &[Action::Open], "open"));
+        spans.extend(hint(app, &[Action::Permission], "permission"));
+        spans.extend(hint(app, &[Action::ApproveAll], "approve all"));
         spans.push(key_span(&dash_key_label));
         spans.push(Span::raw("back "));
-        spans.push(key_span("[q]"));
-        spans.push(Span::raw("uit"));
+        spans.extend(hint(app, &[Action::Quit], "quit"));
         spans.push(Span::styled(
             right_indicators,
             Style::default().fg(Color::Yellow),
         ));
         Line::from(spans)
+    } else if app.view_mode == ViewMode::Diff {
+        let mut spans = vec![];
+        spans.extend(hint(app, &[Action::Down, Action::Up], "file"));
+        spans.extend(hint(app, &[Action::NextHunk, Action::PrevHunk], "hunk"));
+        spans.extend(hint(app, &[Action::Stage], "stage"));
+        spans.extend(hint(app, &[Action::Discard], "discard"));
+        spans.extend(hint(app, &[Action::ToggleBase], "base/HEAD"));
+        spans.extend(hint(app, &[Action::Back], "back"));
+        spans.push(Span::styled(right_indicators, Style::default().fg(Color::Yellow)));
+        Line::from(spans)
     } else if app.view_mode == ViewMode::AgentOutput {
         // Full-screen agent output mode
         let mut spans = vec![];
-        spans.push(key_span("[j/k]"));
-        spans.push(Span::raw("scroll "));
-        spans.push(key_span("[c]"));
-        spans.push(Span::raw("opy "));
-        spans.push(key_span("[Esc]"));
-        spans.push(Span::raw("back "));
-        spans.push(key_span("[q]"));
-        spans.push(Span::raw("uit "));
+        spans.extend(hint(app, &[Action::Down, Action::Up], "scroll"));
+        spans.extend(hint(app, &[Action::Copy], "copy"));
+        spans.extend(hint(app, &[Action::Back], "back"));
+        spans.extend(hint(app, &[Action::Quit], "quit"));
         spans.push(Span::styled(
             right_indicators,
             Style::default().fg(Color::Yellow),
         ));
         Line::from(spans)
+    } else if app.read_only {
+        // Watching another dashboard's sessions — only keys that look
+        let mut spans = vec![
+            Span::styled(" read-only ", Style::default().fg(Color::Black).bg(Color::Yellow)),
+            Span::raw(" "),
+        ];
+        spans.extend(hint(app, &[Action::Down, Action::Up], "select"));
+        spans.push(key_span(&overview_key_label));
+        spans.push(Span::raw("overview "));
+        spans.extend(hint(app, &[Action::Diff], "diff"));
+        spans.extend(hint(app, &[Action::Search], "search"));
+        spans.extend(hint(app, &[Action::Palette], "palette"));
+        spans.extend(hint(app, &[Action::Refresh], "refresh"));
+        spans.extend(hint(app, &[Action::Help], "help"));
+        spans.extend(hint(app, &[Action::Quit], "quit"));
+        spans.push(Span::styled(right_indicators, Style::default().fg(Color::Yellow)));
+        Line::from(spans)
     } else {
         // Dashboard overview — session list shortcuts
         let mut spans = vec![];
 
         match app.focus {
             Focus::SessionList => {
-                spans.push(key_span("[n]"));
-                spans.push(Span::raw("ew "));
-                spans.push(key_span("[⏎]"));
-                spans.push(Span::raw("open "));
+                spans.extend(hint(app, &[Action::NewSession], "new"));
+                spans.extend(hint(app, &[Action::Open], "open"));
                 spans.push(key_span(&overview_key_label));
                 spans.push(Span::raw("overview "));
-                spans.push(key_span("[⌫]"));
-                spans.push(Span::raw("kill "));
-                spans.push(key_span("[q]"));
-                spans.push(Span::raw("uit "));
+                spans.extend(hint(app, &[Action::Diff], "diff"));
+                spans.extend(hint(app, &[Action::Message], "message"));
+                spans.extend(hint(app, &[Action::Permission], "permission"));
+                spans.extend(hint(app, &[Action::Search], "search"));
+                spans.extend(hint(app, &[Action::Palette], "palette"));
+                spans.extend(hint(app, &[Action::Layout], "layout"));
+                spans.extend(hint(app, &[Action::Kill], "kill"));
+                spans.extend(hint(app, &[Action::Help], "help"));
+                spans.extend(hint(app, &[Action::Quit], "quit"));
                 spans.push(Span::styled(
                     "Prefix+d/o",
                     Style::default().fg(Color::DarkGray),
                 ));
             }
             Focus::AgentList => {
-                spans.push(key_span("[s]"));
-                spans.push(Span::raw("pawn "));
-                spans.push(key_span("[⏎]"));
-                spans.push(Span::raw("view "));
-                spans.push(key_span("[⌫]"));
-                spans.push(Span::raw("remove "));
-                spans.push(key_span("[Esc]"));
-                spans.push(Span::raw("back "));
-                spans.push(key_span("[q]"));
-                spans.push(Span::raw("uit "));
+                spans.extend(hint(app, &[Action::Spawn], "spawn"));
+                spans.extend(hint(app, &[Action::Open], "view"));
+                spans.extend(hint(app, &[Action::PopPane], "zoom"));
+                spans.extend(hint(app, &[Action::Kill], "remove"));
+                spans.extend(hint(app, &[Action::Palette], "palette"));
+                spans.extend(hint(app, &[Action::Back], "back"));
+                spans.extend(hint(app, &[Action::Help], "help"));
+                spans.extend(hint(app, &[Action::Quit], "quit"));
             }
         }
 
@@ -130,7 +172,28 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(paragraph, area);
 }
 
-fn key_span(text: &str) -> Span<'_> {
+/// `[key]word` for actions' keys, folding a one-letter key into the word it
+/// starts (`[n]ew`). Nothing when none of them has a key.
+fn hint(app: &App, actions: &[Action], word: &str) -> Vec<Span<'static>> {
+    let keys: Vec<String> = actions
+        .iter()
+        .map(|&action| app.keymap.label(action))
+        .filter(|key| !key.is_empty())
+        .collect();
+    if keys.is_empty() {
+        return vec![];
+    }
+    let keys = keys.join("/");
+    let mut key_chars = keys.chars();
+    let mut word_chars = word.chars();
+    let rest = match (key_chars.next(), key_chars.next(), word_chars.next()) {
+        (Some(k), None, Some(w)) if k.to_lowercase().eq(w.to_lowercase()) => word_chars.as_str(),
+        _ => word,
+    };
+    vec![key_span(format!("[{keys}]")), Span::raw(format!("{rest} "))]
+}
+
+fn key_span<'a>(text: impl Into<Cow<'a, str>>) -> Span<'a> {
     Span::styled(
         text,
         Style::default()